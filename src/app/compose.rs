@@ -0,0 +1,1068 @@
+use crate::app::{
+    containers::{COMPOSE_PROJECT_LABEL, COMPOSE_SERVICE_LABEL},
+    ui::{self, icon, key},
+    App,
+};
+use crate::event::{ContainerEvent, EventRequest, NetworkEvent, VolumeEvent};
+
+use anyhow::{anyhow, Context, Result};
+use docker_api::api::{
+    ContainerCreateOpts, ContainerStatus, NetworkCreateOpts, PublishPort, VolumeCreateOpts,
+};
+use egui::{Grid, Label, RichText};
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+const NETWORK_LABEL: &str = "com.docker.compose.network";
+const VOLUME_LABEL: &str = "com.docker.compose.volume";
+const DEFAULT_NETWORK: &str = "default";
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum StringOrList {
+    String(String),
+    List(Vec<String>),
+}
+
+impl StringOrList {
+    /// Returns the items of the list, a string is split into words like in a shell.
+    fn to_vec(&self) -> Result<Vec<String>> {
+        match self {
+            StringOrList::String(s) => crate::split_shell_words(s),
+            StringOrList::List(list) => Ok(list.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+/// Either a list of `key=value` entries or a mapping of keys to optional values
+pub enum ListOrMap {
+    List(Vec<String>),
+    Map(BTreeMap<String, Option<serde_yaml::Value>>),
+}
+
+impl Default for ListOrMap {
+    fn default() -> Self {
+        ListOrMap::List(vec![])
+    }
+}
+
+impl ListOrMap {
+    fn to_pairs(&self) -> Vec<(String, String)> {
+        match self {
+            ListOrMap::List(list) => list
+                .iter()
+                .map(|entry| match entry.split_once('=') {
+                    Some((k, v)) => (k.to_string(), v.to_string()),
+                    None => (entry.to_string(), String::new()),
+                })
+                .collect(),
+            ListOrMap::Map(map) => map
+                .iter()
+                .map(|(k, v)| {
+                    let v = match v {
+                        Some(serde_yaml::Value::String(s)) => s.clone(),
+                        Some(serde_yaml::Value::Number(n)) => n.to_string(),
+                        Some(serde_yaml::Value::Bool(b)) => b.to_string(),
+                        _ => String::new(),
+                    };
+                    (k.clone(), v)
+                })
+                .collect(),
+        }
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.to_pairs().into_iter().map(|(k, _)| k).collect()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum Port {
+    Number(u32),
+    String(String),
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Service {
+    pub image: Option<String>,
+    pub container_name: Option<String>,
+    pub command: Option<StringOrList>,
+    pub entrypoint: Option<StringOrList>,
+    #[serde(default)]
+    pub environment: ListOrMap,
+    #[serde(default)]
+    pub labels: ListOrMap,
+    #[serde(default)]
+    pub ports: Vec<Port>,
+    #[serde(default)]
+    pub volumes: Vec<String>,
+    #[serde(default)]
+    pub networks: ListOrMap,
+    pub network_mode: Option<String>,
+    #[serde(default)]
+    pub depends_on: ListOrMap,
+    pub restart: Option<String>,
+    pub working_dir: Option<String>,
+    pub user: Option<String>,
+    pub hostname: Option<String>,
+    pub dns: Option<StringOrList>,
+    #[serde(default)]
+    pub extra_hosts: Vec<String>,
+    pub stop_signal: Option<String>,
+    #[serde(default)]
+    pub tty: bool,
+    #[serde(default)]
+    pub stdin_open: bool,
+    #[serde(default)]
+    pub privileged: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Network {
+    pub name: Option<String>,
+    pub driver: Option<String>,
+    #[serde(default)]
+    pub external: bool,
+    #[serde(default)]
+    pub internal: bool,
+    #[serde(default)]
+    pub attachable: bool,
+    #[serde(default)]
+    pub labels: ListOrMap,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Volume {
+    pub name: Option<String>,
+    pub driver: Option<String>,
+    #[serde(default)]
+    pub external: bool,
+    #[serde(default)]
+    pub labels: ListOrMap,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+/// The subset of the compose file specification that can be translated to docker API calls
+pub struct ComposeFile {
+    #[serde(default)]
+    pub services: BTreeMap<String, Service>,
+    #[serde(default)]
+    pub networks: BTreeMap<String, Option<Network>>,
+    #[serde(default)]
+    pub volumes: BTreeMap<String, Option<Volume>>,
+}
+
+#[derive(Debug)]
+pub enum ComposeResource {
+    Network {
+        name: String,
+        opts: NetworkCreateOpts,
+    },
+    Volume {
+        name: String,
+        opts: VolumeCreateOpts,
+    },
+    Container {
+        service: String,
+        name: String,
+        image: String,
+        opts: ContainerCreateOpts,
+        /// Networks connected after the container is created as only the first one can be set
+        /// as the network mode
+        networks: Vec<String>,
+        /// Aliases of the container in all of its networks, the service name makes services
+        /// reachable by each other
+        aliases: Vec<String>,
+    },
+}
+
+impl ComposeResource {
+    pub fn kind(&self) -> &str {
+        match self {
+            ComposeResource::Network { .. } => "network",
+            ComposeResource::Volume { .. } => "volume",
+            ComposeResource::Container { .. } => "container",
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            ComposeResource::Network { name, .. }
+            | ComposeResource::Volume { name, .. }
+            | ComposeResource::Container { name, .. } => name,
+        }
+    }
+}
+
+impl ComposeFile {
+    pub fn load(path: &Path) -> Result<Self> {
+        let data = std::fs::read(path).context("failed to read compose file")?;
+        serde_yaml::from_slice(&data).context("failed to parse compose file")
+    }
+
+    /// Returns the name of the network as created by docker.
+    fn network_name(&self, project: &str, network: &str) -> String {
+        match self.networks.get(network).and_then(|n| n.as_ref()) {
+            Some(Network {
+                name: Some(name), ..
+            }) => name.clone(),
+            Some(Network { external: true, .. }) => network.to_string(),
+            _ => format!("{}_{}", project, network),
+        }
+    }
+
+    fn volume_name(&self, project: &str, volume: &str) -> String {
+        match self.volumes.get(volume).and_then(|v| v.as_ref()) {
+            Some(Volume {
+                name: Some(name), ..
+            }) => name.clone(),
+            Some(Volume { external: true, .. }) => volume.to_string(),
+            _ => format!("{}_{}", project, volume),
+        }
+    }
+
+    pub fn container_name(&self, project: &str, service: &str) -> String {
+        self.services
+            .get(service)
+            .and_then(|s| s.container_name.clone())
+            .unwrap_or_else(|| format!("{}-{}-1", project, service))
+    }
+
+    /// Returns the names of networks a service is attached to, the default network of the project
+    /// is used when none are specified.
+    fn service_networks(service: &Service) -> Vec<String> {
+        let networks = service.networks.keys();
+        if networks.is_empty() && service.network_mode.is_none() {
+            vec![DEFAULT_NETWORK.to_string()]
+        } else {
+            networks
+        }
+    }
+
+    /// Sorts the services so that each service comes after all the services it depends on.
+    pub fn creation_order(&self) -> Result<Vec<&str>> {
+        let mut remaining = BTreeMap::new();
+        for (name, service) in &self.services {
+            let deps = service.depends_on.keys();
+            for dep in &deps {
+                if !self.services.contains_key(dep) {
+                    return Err(anyhow!(
+                        "service `{}` depends on undefined service `{}`",
+                        name,
+                        dep
+                    ));
+                }
+            }
+            remaining.insert(name.as_str(), deps.into_iter().collect::<BTreeSet<_>>());
+        }
+
+        let mut order = vec![];
+        while !remaining.is_empty() {
+            let ready = remaining
+                .iter()
+                .filter(|(_, deps)| deps.is_empty())
+                .map(|(name, _)| *name)
+                .collect::<Vec<_>>();
+            if ready.is_empty() {
+                return Err(anyhow!(
+                    "services {} have circular dependencies",
+                    remaining.keys().copied().collect::<Vec<_>>().join(", ")
+                ));
+            }
+            for name in ready {
+                remaining.remove(name);
+                for deps in remaining.values_mut() {
+                    deps.remove(name);
+                }
+                order.push(name);
+            }
+        }
+        Ok(order)
+    }
+
+    /// Translates the file into resources that have to be created in the returned order.
+    pub fn plan(&self, project: &str, base_dir: &Path) -> Result<Vec<ComposeResource>> {
+        if project.is_empty() {
+            return Err(anyhow!("project name can't be empty"));
+        }
+        let order = self.creation_order()?;
+        let mut resources = vec![];
+
+        let mut networks = self
+            .services
+            .values()
+            .flat_map(Self::service_networks)
+            .collect::<BTreeSet<_>>();
+        networks.extend(self.networks.keys().cloned());
+        for network in networks {
+            let def = match self.networks.get(&network) {
+                Some(def) => def.clone().unwrap_or_default(),
+                None if network == DEFAULT_NETWORK => Network::default(),
+                None => return Err(anyhow!("network `{}` is not defined", network)),
+            };
+            if def.external {
+                continue;
+            }
+            let name = self.network_name(project, &network);
+            let mut labels = def.labels.to_pairs();
+            labels.push((COMPOSE_PROJECT_LABEL.to_string(), project.to_string()));
+            labels.push((NETWORK_LABEL.to_string(), network.clone()));
+            let mut opts = NetworkCreateOpts::builder(&name)
+                .labels(labels)
+                .internal(def.internal)
+                .attachable(def.attachable);
+            if let Some(driver) = &def.driver {
+                opts = opts.driver(driver);
+            }
+            resources.push(ComposeResource::Network {
+                name,
+                opts: opts.build(),
+            });
+        }
+
+        for (volume, def) in &self.volumes {
+            let def = def.clone().unwrap_or_default();
+            if def.external {
+                continue;
+            }
+            let name = self.volume_name(project, volume);
+            let mut labels = def.labels.to_pairs();
+            labels.push((COMPOSE_PROJECT_LABEL.to_string(), project.to_string()));
+            labels.push((VOLUME_LABEL.to_string(), volume.clone()));
+            let mut opts = VolumeCreateOpts::builder().name(&name).labels(labels);
+            if let Some(driver) = &def.driver {
+                opts = opts.driver(driver);
+            }
+            resources.push(ComposeResource::Volume {
+                name,
+                opts: opts.build(),
+            });
+        }
+
+        for service_name in order {
+            let service = &self.services[service_name];
+            resources.push(
+                self.container_resource(project, service_name, service, base_dir)
+                    .with_context(|| format!("invalid service `{}`", service_name))?,
+            );
+        }
+
+        Ok(resources)
+    }
+
+    fn container_resource(
+        &self,
+        project: &str,
+        service_name: &str,
+        service: &Service,
+        base_dir: &Path,
+    ) -> Result<ComposeResource> {
+        let image = service
+            .image
+            .clone()
+            .ok_or_else(|| anyhow!("an image is required, building images is not supported"))?;
+        let name = self.container_name(project, service_name);
+        let mut opts = ContainerCreateOpts::builder(&image)
+            .name(&name)
+            .tty(service.tty)
+            .attach_stdin(service.stdin_open)
+            .attach_stdout(true)
+            .attach_stderr(true)
+            .privileged(service.privileged);
+
+        if let Some(cmd) = &service.command {
+            opts = opts.cmd(cmd.to_vec().context("invalid command")?);
+        }
+        if let Some(entrypoint) = &service.entrypoint {
+            opts = opts.entrypoint(entrypoint.to_vec().context("invalid entrypoint")?);
+        }
+        let env = service
+            .environment
+            .to_pairs()
+            .into_iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect::<Vec<_>>();
+        if !env.is_empty() {
+            opts = opts.env(env);
+        }
+
+        let mut labels = service.labels.to_pairs();
+        labels.push((COMPOSE_PROJECT_LABEL.to_string(), project.to_string()));
+        labels.push((COMPOSE_SERVICE_LABEL.to_string(), service_name.to_string()));
+        opts = opts.labels(labels);
+
+        let mut volumes = vec![];
+        for volume in &service.volumes {
+            let (source, target) = volume
+                .split_once(':')
+                .ok_or_else(|| anyhow!("anonymous volume `{}` is not supported", volume))?;
+            let source = if source.starts_with('.') || source.starts_with('~') {
+                let source = source.strip_prefix("~/").map_or_else(
+                    || base_dir.join(source),
+                    |rest| {
+                        std::env::var_os("HOME")
+                            .map(PathBuf::from)
+                            .unwrap_or_default()
+                            .join(rest)
+                    },
+                );
+                source.to_string_lossy().to_string()
+            } else if source.starts_with('/') {
+                source.to_string()
+            } else if self.volumes.contains_key(source) {
+                self.volume_name(project, source)
+            } else {
+                return Err(anyhow!("volume `{}` is not defined", source));
+            };
+            volumes.push(format!("{}:{}", source, target));
+        }
+        if !volumes.is_empty() {
+            opts = opts.volumes(volumes);
+        }
+
+        // like with compose, ports without a host port are published on a free one picked by
+        // docker when the host port is 0
+        for port in &service.ports {
+            opts = match port {
+                Port::Number(port) => opts.expose(PublishPort::tcp(*port), 0),
+                Port::String(port) => {
+                    let (port, protocol) = port.split_once('/').unwrap_or((port, "tcp"));
+                    let parse = |p: &str| {
+                        p.parse::<u32>()
+                            .with_context(|| format!("invalid port `{}`", p))
+                    };
+                    let mut parts = port.rsplit(':');
+                    let container_port = parse(parts.next().unwrap_or_default())?;
+                    let container_port = match protocol {
+                        "udp" => PublishPort::udp(container_port),
+                        "sctp" => PublishPort::sctp(container_port),
+                        _ => PublishPort::tcp(container_port),
+                    };
+                    let host_port = match parts.next() {
+                        Some(host_port) if !host_port.is_empty() => parse(host_port)?,
+                        _ => 0,
+                    };
+                    if parts.next().is_some() {
+                        return Err(anyhow!(
+                            "unsupported port `{}`, binding to a host IP is not supported",
+                            port
+                        ));
+                    }
+                    opts.expose(container_port, host_port)
+                }
+            };
+        }
+
+        // only one network can be set when creating a container, the rest are connected later
+        let mut networks = Self::service_networks(service)
+            .into_iter()
+            .map(|network| self.network_name(project, &network))
+            .collect::<Vec<_>>();
+        let mut aliases = vec![];
+        if let Some(network_mode) = &service.network_mode {
+            opts = opts.network_mode(network_mode);
+        } else if !networks.is_empty() {
+            opts = opts.network_mode(networks.remove(0));
+            aliases.push(service_name.to_string());
+        }
+
+        // links make dependencies reachable by their service name, docker rejects them when the
+        // container doesn't have its own network
+        let own_network = !matches!(
+            service.network_mode.as_deref(),
+            Some(mode) if mode == "host" || mode == "none" || mode.starts_with("container:")
+        );
+        let links = service
+            .depends_on
+            .keys()
+            .into_iter()
+            .map(|dep| format!("{}:{}", self.container_name(project, &dep), dep))
+            .collect::<Vec<_>>();
+        if own_network && !links.is_empty() {
+            opts = opts.links(links);
+        }
+
+        if let Some(restart) = &service.restart {
+            let (policy, max) = restart.split_once(':').unwrap_or((restart, "0"));
+            let max = max
+                .parse()
+                .with_context(|| format!("invalid restart policy `{}`", restart))?;
+            opts = opts.restart_policy(policy, max);
+        }
+        if let Some(dir) = &service.working_dir {
+            opts = opts.working_dir(dir);
+        }
+        if let Some(user) = &service.user {
+            opts = opts.user(user);
+        }
+        if let Some(hostname) = &service.hostname {
+            opts = opts.hostname(hostname);
+        }
+        if let Some(dns) = &service.dns {
+            opts = opts.dns(dns.to_vec().context("invalid DNS servers")?);
+        }
+        if !service.extra_hosts.is_empty() {
+            opts = opts.extra_hosts(service.extra_hosts.clone());
+        }
+        if let Some(signal) = &service.stop_signal {
+            opts = opts.stop_signal(signal);
+        }
+
+        Ok(ComposeResource::Container {
+            service: service_name.to_string(),
+            name,
+            image,
+            opts: opts.build(),
+            networks,
+            aliases,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct ComposeViewData {
+    pub path: Option<PathBuf>,
+    pub file: Option<ComposeFile>,
+    pub project: String,
+    /// Whether to start the containers right after creating them
+    pub start: bool,
+    pub plan: Option<Result<Vec<ComposeResource>>>,
+}
+
+impl Default for ComposeViewData {
+    fn default() -> Self {
+        Self {
+            path: None,
+            file: None,
+            project: String::new(),
+            start: true,
+            plan: None,
+        }
+    }
+}
+
+impl ComposeViewData {
+    fn base_dir(&self) -> PathBuf {
+        self.path
+            .as_ref()
+            .and_then(|p| p.parent())
+            .map(Path::to_path_buf)
+            .unwrap_or_default()
+    }
+
+    fn update_plan(&mut self) {
+        let base_dir = self.base_dir();
+        self.plan = self
+            .file
+            .as_ref()
+            .map(|file| file.plan(&self.project, &base_dir));
+    }
+}
+
+impl App {
+    pub fn compose(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Compose");
+        ui.add_space(10.);
+        ui.horizontal(|ui| {
+            if ui
+                .button(icon::ADD)
+                .on_hover_text("open a compose file")
+                .clicked()
+            {
+                self.open_compose_file();
+            }
+            if let Some(path) = &self.containers.compose_view_data.path {
+                ui.label(path.display().to_string());
+            }
+        });
+
+        if self.containers.compose_view_data.file.is_none() {
+            return;
+        }
+
+        ui.add_space(5.);
+        Grid::new("compose_project_grid").show(ui, |ui| {
+            let data = &mut self.containers.compose_view_data;
+            key!(ui, "Project:");
+            if ui.text_edit_singleline(&mut data.project).changed() {
+                data.update_plan();
+            }
+            ui.end_row();
+            ui.checkbox(&mut data.start, "Start containers");
+            ui.end_row();
+        });
+        ui.add_space(10.);
+        self.compose_preview(ui);
+    }
+
+    fn open_compose_file(&mut self) {
+        match native_dialog::FileDialog::new()
+            .add_filter("compose file", &["yml", "yaml"])
+            .show_open_single_file()
+        {
+            Ok(Some(path)) => match ComposeFile::load(&path) {
+                Ok(file) => {
+                    let data = &mut self.containers.compose_view_data;
+                    data.project = path
+                        .parent()
+                        .and_then(|p| p.file_name())
+                        .map(|name| name.to_string_lossy().to_lowercase())
+                        .unwrap_or_default();
+                    data.path = Some(path);
+                    data.file = Some(file);
+                    data.update_plan();
+                    // the preview compares the file with existing networks and volumes
+                    self.send_event_notify(EventRequest::Network(NetworkEvent::List(None)));
+                    self.send_event_notify(EventRequest::Volume(VolumeEvent::List(None)));
+                }
+                Err(e) => self.add_error(e),
+            },
+            Ok(None) => {}
+            Err(e) => self.add_error(format!("failed to spawn a file dialog - {}", e)),
+        }
+    }
+
+    fn compose_resource_exists(&self, resource: &ComposeResource) -> bool {
+        match resource {
+            ComposeResource::Network { name, .. } => self
+                .networks
+                .networks
+                .iter()
+                .any(|n| n.name.as_deref() == Some(name)),
+            ComposeResource::Volume { name, .. } => self
+                .volumes
+                .volumes
+                .as_ref()
+                .map(|v| v.volumes.iter().any(|v| &v.name == name))
+                .unwrap_or_default(),
+            ComposeResource::Container { name, .. } => self.containers.containers.iter().any(|c| {
+                c.names
+                    .iter()
+                    .any(|n| n.trim_start_matches('/') == name.as_str())
+            }),
+        }
+    }
+
+    fn compose_preview(&mut self, ui: &mut egui::Ui) {
+        let project = self.containers.compose_view_data.project.clone();
+        let mut apply = false;
+        let mut down = false;
+
+        match &self.containers.compose_view_data.plan {
+            Some(Ok(plan)) => {
+                let planned_containers = plan
+                    .iter()
+                    .filter(|r| matches!(r, ComposeResource::Container { .. }))
+                    .map(|r| r.name())
+                    .collect::<Vec<_>>();
+                Grid::new("compose_preview_grid")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        key!(ui, "Kind");
+                        key!(ui, "Name");
+                        key!(ui, "Details");
+                        key!(ui, "Action");
+                        ui.end_row();
+                        for resource in plan {
+                            ui.label(resource.kind());
+                            ui.label(resource.name());
+                            match resource {
+                                ComposeResource::Container { service, image, .. } => {
+                                    ui.label(format!("service {}, image {}", service, image))
+                                }
+                                _ => ui.label(""),
+                            };
+                            if self.compose_resource_exists(resource) {
+                                ui.label("exists, skipped");
+                            } else {
+                                ui.label(RichText::new("create").color(egui::Color32::GREEN));
+                            }
+                            ui.end_row();
+                        }
+                        for container in self.project_containers(&project) {
+                            let name = container
+                                .names
+                                .first()
+                                .map(|n| n.trim_start_matches('/'))
+                                .unwrap_or(&container.id);
+                            if planned_containers.contains(&name) {
+                                continue;
+                            }
+                            ui.label("container");
+                            ui.label(name);
+                            ui.label("not defined in the file");
+                            ui.label(RichText::new("orphan").color(egui::Color32::YELLOW));
+                            ui.end_row();
+                        }
+                    });
+                ui.add_space(5.);
+                ui.add(
+                    Label::new(RichText::new("variables in the file are not interpolated").weak())
+                        .wrap(true),
+                );
+                ui.add_space(10.);
+                ui.horizontal(|ui| {
+                    if ui
+                        .button("up")
+                        .on_hover_text("create missing resources of the project")
+                        .clicked()
+                    {
+                        apply = true;
+                    }
+                    if ui
+                        .button("down")
+                        .on_hover_text("stop and delete containers and networks of the project")
+                        .clicked()
+                    {
+                        down = true;
+                    }
+                });
+            }
+            Some(Err(e)) => {
+                ui.add(
+                    Label::new(RichText::new(format!("{:?}", e)).color(egui::Color32::RED))
+                        .wrap(true),
+                );
+            }
+            None => {}
+        }
+
+        if apply {
+            self.compose_up();
+        }
+        if down {
+            self.compose_down();
+        }
+    }
+
+    fn project_containers(&self, project: &str) -> Vec<&docker_api::api::ContainerInfo> {
+        self.containers
+            .containers
+            .iter()
+            .filter(|c| {
+                c.labels
+                    .get(COMPOSE_PROJECT_LABEL)
+                    .map(|p| p == project)
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+
+    /// Creates all resources of the project that don't exist yet. The worker handles requests in
+    /// order so dependencies are created before the containers that need them.
+    fn compose_up(&mut self) {
+        let data = &self.containers.compose_view_data;
+        let plan = match data
+            .file
+            .as_ref()
+            .map(|f| f.plan(&data.project, &data.base_dir()))
+        {
+            Some(Ok(plan)) => plan,
+            Some(Err(e)) => return self.add_error(e),
+            None => return,
+        };
+        let start = data.start;
+        let mut to_start = vec![];
+        for resource in plan {
+            if self.compose_resource_exists(&resource) {
+                continue;
+            }
+            let event = match resource {
+                ComposeResource::Network { opts, .. } => {
+                    EventRequest::Network(NetworkEvent::Create(opts))
+                }
+                ComposeResource::Volume { opts, .. } => {
+                    EventRequest::Volume(VolumeEvent::Create(opts))
+                }
+                ComposeResource::Container {
+                    name,
+                    opts,
+                    networks,
+                    aliases,
+                    ..
+                } => {
+                    to_start.push(name);
+                    EventRequest::Container(ContainerEvent::Create {
+                        opts,
+                        networks,
+                        aliases,
+                        start: false,
+                    })
+                }
+            };
+            self.send_event_notify(event);
+        }
+        if start {
+            for id in to_start {
+                self.send_event_notify(EventRequest::Container(ContainerEvent::Start { id }));
+            }
+        }
+    }
+
+    fn compose_down(&mut self) {
+        let data = &self.containers.compose_view_data;
+        let project = data.project.clone();
+        let mut actions = vec![];
+        for container in self.project_containers(&project) {
+            if matches!(
+                container.state,
+                ContainerStatus::Running | ContainerStatus::Paused
+            ) {
+                actions.push(EventRequest::Container(ContainerEvent::Stop {
+                    id: container.id.clone(),
+                }));
+            }
+            actions.push(EventRequest::Container(ContainerEvent::Delete {
+                id: container.id.clone(),
+            }));
+        }
+        if let Some(Ok(plan)) = &data.plan {
+            for resource in plan {
+                if let ComposeResource::Network { name, .. } = resource {
+                    if self.compose_resource_exists(resource) {
+                        actions.push(EventRequest::Network(NetworkEvent::Delete {
+                            id: name.clone(),
+                        }));
+                    }
+                }
+            }
+        }
+
+        let mut actions = actions.into_iter();
+        if let Some(first) = actions.next() {
+            let popup = actions
+                .fold(ui::ActionPopup::builder(first), |builder, action| {
+                    builder.action(action)
+                })
+                .title("Compose down")
+                .text(format!(
+                    "are you sure you want to delete all containers and networks of project {}? \
+                     Volumes are kept.",
+                    project
+                ))
+                .build();
+            self.popups.push_back(popup);
+        } else {
+            self.add_notification(format!("project {} has nothing to remove", project));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(yaml: &str) -> ComposeFile {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn creation_order_puts_dependencies_first() {
+        let file = parse(
+            r#"
+services:
+  web:
+    image: nginx
+    depends_on: [api, cache]
+  api:
+    image: api
+    depends_on:
+      db:
+        condition: service_started
+  db:
+    image: postgres
+  cache:
+    image: redis
+"#,
+        );
+        let order = file.creation_order().unwrap();
+        let position = |name| order.iter().position(|s| *s == name).unwrap();
+        assert_eq!(order.len(), 4);
+        assert!(position("db") < position("api"));
+        assert!(position("api") < position("web"));
+        assert!(position("cache") < position("web"));
+    }
+
+    #[test]
+    fn creation_order_detects_cycles() {
+        let file = parse(
+            r#"
+services:
+  a:
+    image: a
+    depends_on: [b]
+  b:
+    image: b
+    depends_on: [c]
+  c:
+    image: c
+    depends_on: [a]
+  d:
+    image: d
+"#,
+        );
+        let err = file.creation_order().unwrap_err().to_string();
+        assert!(err.contains("circular"), "{}", err);
+        assert!(err.contains("a, b, c"), "{}", err);
+    }
+
+    #[test]
+    fn creation_order_rejects_undefined_dependencies() {
+        let file = parse(
+            r#"
+services:
+  a:
+    image: a
+    depends_on: [missing]
+"#,
+        );
+        assert!(file.creation_order().is_err());
+    }
+
+    #[test]
+    fn plan_creates_networks_and_volumes_before_containers() {
+        let file = parse(
+            r#"
+services:
+  web:
+    image: nginx
+    depends_on: [db]
+    networks: [front, back]
+  db:
+    image: postgres
+    networks: [back]
+    volumes: ["data:/var/lib/postgresql/data"]
+  tool:
+    image: busybox
+    network_mode: host
+networks:
+  front:
+  back:
+  shared:
+    external: true
+volumes:
+  data:
+"#,
+        );
+        let plan = file.plan("proj", Path::new("/tmp")).unwrap();
+        let resources = plan
+            .iter()
+            .map(|r| format!("{} {}", r.kind(), r.name()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            resources,
+            [
+                "network proj_back",
+                "network proj_front",
+                "volume proj_data",
+                "container proj-db-1",
+                "container proj-tool-1",
+                "container proj-web-1",
+            ]
+        );
+
+        let networks = |name: &str| {
+            plan.iter()
+                .find_map(|r| match r {
+                    ComposeResource::Container {
+                        name: n, networks, ..
+                    } if n == name => Some(networks.clone()),
+                    _ => None,
+                })
+                .unwrap()
+        };
+        // the first network is the network mode of the container
+        assert_eq!(networks("proj-web-1"), ["proj_back"]);
+        assert!(networks("proj-db-1").is_empty());
+        assert!(networks("proj-tool-1").is_empty());
+    }
+
+    #[test]
+    fn plan_uses_default_network() {
+        let file = parse(
+            r#"
+services:
+  web:
+    image: nginx
+"#,
+        );
+        let plan = file.plan("proj", Path::new("/tmp")).unwrap();
+        assert_eq!(plan[0].kind(), "network");
+        assert_eq!(plan[0].name(), "proj_default");
+        assert_eq!(plan[1].name(), "proj-web-1");
+    }
+
+    #[test]
+    fn plan_rejects_undefined_networks() {
+        let file = parse(
+            r#"
+services:
+  web:
+    image: nginx
+    networks: [missing]
+"#,
+        );
+        assert!(file.plan("proj", Path::new("/tmp")).is_err());
+    }
+
+    #[test]
+    fn splits_string_commands_like_a_shell() {
+        let cmd = StringOrList::String(r#"sh -c "echo a; sleep 1""#.to_string());
+        assert_eq!(cmd.to_vec().unwrap(), ["sh", "-c", "echo a; sleep 1"]);
+
+        let file = parse(
+            r#"
+services:
+  web:
+    image: nginx
+    command: sh -c "echo a
+"#,
+        );
+        assert!(file.plan("proj", Path::new("/tmp")).is_err());
+    }
+
+    #[test]
+    fn plan_rejects_ports_bound_to_host_ip() {
+        let file = parse(
+            r#"
+services:
+  web:
+    image: nginx
+    ports: [3000, "3001/udp", "8080:80", "127.0.0.1:8443:443"]
+"#,
+        );
+        let err = file.plan("proj", Path::new("/tmp")).unwrap_err();
+        assert!(format!("{:#}", err).contains("127.0.0.1:8443:443"));
+    }
+
+    #[test]
+    fn plan_aliases_services_by_name() {
+        let file = parse(
+            r#"
+services:
+  web:
+    image: nginx
+  tool:
+    image: busybox
+    network_mode: host
+"#,
+        );
+        let plan = file.plan("proj", Path::new("/tmp")).unwrap();
+        let aliases = |name: &str| {
+            plan.iter()
+                .find_map(|r| match r {
+                    ComposeResource::Container {
+                        name: n, aliases, ..
+                    } if n == name => Some(aliases.clone()),
+                    _ => None,
+                })
+                .unwrap()
+        };
+        assert_eq!(aliases("proj-web-1"), ["web"]);
+        assert!(aliases("proj-tool-1").is_empty());
+    }
+}
@@ -1,23 +1,37 @@
+use crate::app::compose::ComposeViewData;
+use crate::app::images::trim_id;
+use crate::app::logs::{AggregatedLogsViewData, LogsViewData};
+use crate::app::ports;
+use crate::app::templates::{self, Template, TemplatesData};
 use crate::app::{
     ui,
     ui::icon,
     ui::{color, key, key_val, val},
     App,
 };
-use crate::event::{ContainerEvent, EventRequest, GuiEvent};
-use crate::worker::RunningContainerStats;
+use crate::event::{ContainerEvent, EventRequest, GuiEvent, NetworkEvent};
+use crate::worker::{
+    join_path, resolve_link, FileEntry, FileKind, RunningContainerStats, PREVIEW_LIMIT,
+};
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Error, Result};
 use docker_api::api::{
-    Change, ChangeKind, ContainerCreateOpts, ContainerDetails, ContainerId, ContainerIdRef,
-    ContainerInfo, ContainerStatus, Top,
+    Change, ChangeKind, ContainerCommitOpts, ContainerCreateOpts, ContainerDetails, ContainerId,
+    ContainerIdRef, ContainerInfo, ContainerStatus, HostConfig, Labels, PublishPort, Top,
+    UpdateContainerOpts,
 };
-use egui::containers::Frame;
+use egui::containers::{collapsing_header::CollapsingState, Frame};
 use egui::widgets::plot::{self, Line, Plot};
 use egui::{style::Margin, Grid, Label, RichText};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 const PAGE_SIZE: usize = 1024;
 
+/// Labels set by docker compose on containers of a project
+pub const COMPOSE_PROJECT_LABEL: &str = "com.docker.compose.project";
+pub const COMPOSE_SERVICE_LABEL: &str = "com.docker.compose.service";
+
 pub fn color_for_state(state: &ContainerStatus) -> egui::Color32 {
     match state {
         ContainerStatus::Running => egui::Color32::GREEN,
@@ -26,6 +40,14 @@ pub fn color_for_state(state: &ContainerStatus) -> egui::Color32 {
     }
 }
 
+/// Returns the count of running containers.
+fn running_count(containers: &[&ContainerInfo]) -> usize {
+    containers
+        .iter()
+        .filter(|c| matches!(c.state, ContainerStatus::Running))
+        .count()
+}
+
 pub fn state_icon(color: egui::Color32) -> Label {
     Label::new(RichText::new(icon::PACKAGE).color(color).heading().strong())
 }
@@ -125,6 +147,9 @@ pub enum CentralView {
     None,
     Container,
     Create,
+    Logs,
+    Compose,
+    Ports,
 }
 
 impl Default for CentralView {
@@ -141,6 +166,8 @@ pub enum ContainerView {
     Processes,
     Changes,
     Attach,
+    Exec,
+    Files,
 }
 
 impl Default for ContainerView {
@@ -149,11 +176,14 @@ impl Default for ContainerView {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
 pub struct ContainerCreateData {
     pub image: String,
     pub command: String,
+    pub entrypoint: String,
     pub name: String,
+    pub hostname: String,
     pub working_dir: String,
     pub user: String,
     pub userns_mode: String,
@@ -175,6 +205,26 @@ pub struct ContainerCreateData {
     pub volumes: Vec<String>,
     pub links: Vec<String>,
     pub capabilities: Vec<String>,
+    /// Pairs of a container port like `80/tcp` and a host port, the host port can be empty
+    pub ports: Vec<(String, String)>,
+    /// Container ports like `80/tcp` that are exposed without being published
+    pub exposed: Vec<String>,
+    pub publish_all_ports: bool,
+    pub restart_policy: String,
+    pub maximum_retry_count: u64,
+    pub dns: Vec<String>,
+    pub extra_hosts: Vec<String>,
+    pub devices: Vec<String>,
+    pub stop_signal: String,
+    pub stop_timeout: String,
+    /// Networks the container is connected to once it's created in addition to the network mode
+    pub networks: Vec<String>,
+    /// ID and name of the container this data was duplicated from
+    #[serde(skip)]
+    pub original: Option<(ContainerId, String)>,
+    /// Whether to remove the original container and create the new one in its place
+    #[serde(skip)]
+    pub recreate: bool,
 }
 
 impl Default for ContainerCreateData {
@@ -182,7 +232,9 @@ impl Default for ContainerCreateData {
         Self {
             image: "".to_string(),
             command: "".to_string(),
+            entrypoint: "".to_string(),
             name: "".to_string(),
+            hostname: "".to_string(),
             working_dir: "".to_string(),
             user: "".to_string(),
             userns_mode: "".to_string(),
@@ -204,6 +256,19 @@ impl Default for ContainerCreateData {
             volumes: vec![],
             links: vec![],
             capabilities: vec![],
+            ports: vec![],
+            exposed: vec![],
+            publish_all_ports: false,
+            restart_policy: "no".to_string(),
+            maximum_retry_count: 0,
+            dns: vec![],
+            extra_hosts: vec![],
+            devices: vec![],
+            stop_signal: "".to_string(),
+            stop_timeout: "".to_string(),
+            networks: vec![],
+            original: None,
+            recreate: false,
         }
     }
 }
@@ -213,15 +278,141 @@ impl ContainerCreateData {
         *self = ContainerCreateData::default();
     }
 
+    /// Creates data that recreates the container described by `details`.
+    pub fn from_details(details: &ContainerDetails) -> Self {
+        let host_config = &details.host_config;
+        let name = details.name.trim_start_matches('/').to_string();
+        // `default` is reported for containers created without a network mode and stands for
+        // the bridge network the container is attached to
+        let network_mode = match host_config.network_mode.as_str() {
+            "default" => "bridge".to_string(),
+            mode => mode.to_string(),
+        };
+        let mut data = ContainerCreateData {
+            image: details.image.clone(),
+            name: name.clone(),
+            userns_mode: host_config.userns_mode.clone().unwrap_or_default(),
+            log_driver: host_config
+                .log_config
+                .as_ref()
+                .map(|config| config.typ.clone())
+                .unwrap_or_default(),
+            privileged: host_config.privileged,
+            autoremove: host_config.auto_remove.unwrap_or_default(),
+            cpus: host_config.nano_cpus.unwrap_or_default() as f32 / 1_000_000_000.,
+            cpu_shares: host_config
+                .cpu_shares
+                .filter(|shares| *shares > 0)
+                .map(|shares| shares as f32)
+                .unwrap_or(-1.),
+            memory: host_config
+                .memory
+                .filter(|memory| *memory > 0)
+                .map(|memory| memory.to_string())
+                .unwrap_or_default(),
+            memory_swap: host_config
+                .memory_swap
+                .filter(|swap| *swap > 0)
+                .map(|swap| swap.to_string())
+                .unwrap_or_default(),
+            sec_ops: host_config.security_opt.clone().unwrap_or_default(),
+            volumes: host_config.binds.clone().unwrap_or_default(),
+            links: host_config.links.clone().unwrap_or_default(),
+            capabilities: host_config.cap_add.clone().unwrap_or_default(),
+            publish_all_ports: host_config.publish_all_ports,
+            dns: host_config.dns.clone().unwrap_or_default(),
+            extra_hosts: host_config.extra_hosts.clone().unwrap_or_default(),
+            networks: details
+                .network_settings
+                .networks
+                .keys()
+                .filter(|network| *network != &network_mode)
+                .cloned()
+                .collect(),
+            network_mode,
+            original: Some((details.id.clone(), name)),
+            ..Default::default()
+        };
+
+        if let Some(policy) = &host_config.restart_policy {
+            if !policy.name.is_empty() {
+                data.restart_policy = policy.name.clone();
+                data.maximum_retry_count = policy.maximum_retry_count;
+            }
+        }
+        if let Some(bindings) = &host_config.port_bindings {
+            for (port, bindings) in bindings {
+                match bindings.as_deref() {
+                    Some(bindings) if !bindings.is_empty() => {
+                        for binding in bindings {
+                            data.ports.push((port.clone(), binding.host_port.clone()));
+                        }
+                    }
+                    _ => data.exposed.push(port.clone()),
+                }
+            }
+            data.ports.sort();
+            data.exposed.sort();
+        }
+
+        if let Some(config) = &details.config {
+            // prefer the name of the image over its ID
+            if !config.image.is_empty() {
+                data.image = config.image.clone();
+            }
+            data.command = crate::join_shell_words(config.cmd.as_deref().unwrap_or_default());
+            data.entrypoint =
+                crate::join_shell_words(config.entrypoint.as_deref().unwrap_or_default());
+            // docker generates the hostname from the ID when it's not set
+            if !details.id.starts_with(&config.hostname) {
+                data.hostname = config.hostname.clone();
+            }
+            data.stop_signal = config.stop_signal.clone().unwrap_or_default();
+            data.stop_timeout = config
+                .stop_timeout
+                .map(|timeout| timeout.to_string())
+                .unwrap_or_default();
+            data.working_dir = config.working_dir.clone();
+            data.user = config.user.clone();
+            data.tty = config.tty;
+            data.stdin = config.attach_stdin;
+            data.stdout = config.attach_stdout;
+            data.stderr = config.attach_stderr;
+            data.env = config
+                .env
+                .iter()
+                .map(|var| match var.split_once('=') {
+                    Some((k, v)) => (k.to_string(), v.to_string()),
+                    None => (var.clone(), String::new()),
+                })
+                .collect();
+            if let Some(labels) = &config.labels {
+                let mut labels: Vec<_> =
+                    labels.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+                labels.sort();
+                data.labels = labels;
+            }
+        }
+
+        data
+    }
+
     pub fn as_opts(&self) -> Result<ContainerCreateOpts> {
         let mut opts = ContainerCreateOpts::builder(&self.image);
         if !self.command.is_empty() {
-            // #TODO: this should be wiser about arguments
-            opts = opts.cmd(self.command.split_ascii_whitespace());
+            opts = opts.cmd(crate::split_shell_words(&self.command).context("invalid command")?);
+        }
+        if !self.entrypoint.is_empty() {
+            opts = opts.entrypoint(
+                crate::split_shell_words(&self.entrypoint).context("invalid entrypoint")?,
+            );
         }
         if !self.name.is_empty() {
             opts = opts.name(&self.name);
         }
+        if !self.hostname.is_empty() {
+            opts = opts.hostname(&self.hostname);
+        }
         if !self.working_dir.is_empty() {
             opts = opts.working_dir(&self.working_dir);
         }
@@ -281,6 +472,9 @@ impl ContainerCreateData {
             opts = opts.security_options(self.sec_ops.clone());
         }
         if !self.volumes.is_empty() {
+            for volume in &self.volumes {
+                validate_bind(volume)?;
+            }
             opts = opts.volumes(self.volumes.clone());
         }
         if !self.links.is_empty() {
@@ -289,11 +483,126 @@ impl ContainerCreateData {
         if !self.capabilities.is_empty() {
             opts = opts.capabilities(self.capabilities.clone());
         }
+        for (port, host_port) in &self.ports {
+            let port = parse_port(port)?;
+            // docker picks a free host port when it's 0
+            let host_port = if host_port.is_empty() {
+                0
+            } else {
+                host_port
+                    .parse()
+                    .with_context(|| format!("invalid host port `{}`", host_port))?
+            };
+            opts = opts.expose(port, host_port);
+        }
+        for port in &self.exposed {
+            // only adds the port to the exposed ports without binding it on the host
+            opts = opts.publish(parse_port(port)?);
+        }
+        if self.publish_all_ports {
+            opts = opts.publish_all_ports();
+        }
+        if self.restart_policy != "no" {
+            opts = opts.restart_policy(&self.restart_policy, self.maximum_retry_count);
+        }
+        if !self.dns.is_empty() {
+            opts = opts.dns(self.dns.clone());
+        }
+        if !self.extra_hosts.is_empty() {
+            opts = opts.extra_hosts(self.extra_hosts.clone());
+        }
+        if !self.devices.is_empty() {
+            let devices = self
+                .devices
+                .iter()
+                .map(|device| parse_device(device))
+                .collect::<Result<Vec<_>>>()?;
+            opts = opts.devices(devices);
+        }
+        if !self.stop_signal.is_empty() {
+            opts = opts.stop_signal(&self.stop_signal);
+        }
+        if !self.stop_timeout.is_empty() {
+            let timeout = self
+                .stop_timeout
+                .parse()
+                .context("stop timeout must be a number of seconds")?;
+            opts = opts.stop_timeout(std::time::Duration::from_secs(timeout));
+        }
 
         Ok(opts.build())
     }
 }
 
+/// Parses a port like `80` or `53/udp`, the protocol defaults to TCP.
+fn parse_port(port: &str) -> Result<PublishPort> {
+    let (num, protocol) = port.split_once('/').unwrap_or((port, "tcp"));
+    let num = num
+        .parse()
+        .with_context(|| format!("invalid port `{}`", port))?;
+    match protocol {
+        "tcp" => Ok(PublishPort::tcp(num)),
+        "udp" => Ok(PublishPort::udp(num)),
+        "sctp" => Ok(PublishPort::sctp(num)),
+        _ => Err(Error::msg(format!("invalid protocol of port `{}`", port))),
+    }
+}
+
+/// Checks that a bind mount is in the form of `source:destination[:mode]`.
+fn validate_bind(bind: &str) -> Result<()> {
+    const MODES: &[&str] = &[
+        "ro",
+        "rw",
+        "z",
+        "Z",
+        "shared",
+        "slave",
+        "private",
+        "rshared",
+        "rslave",
+        "rprivate",
+        "nocopy",
+        "consistent",
+        "cached",
+        "delegated",
+    ];
+    let parts: Vec<_> = bind.split(':').collect();
+    match parts.as_slice() {
+        [source, destination] | [source, destination, _]
+            if !source.is_empty() && !destination.is_empty() => {}
+        _ => {
+            return Err(Error::msg(format!(
+                "invalid volume mount `{}`, expected `source:destination[:mode]`",
+                bind
+            )))
+        }
+    }
+    if let Some(mode) = parts.get(2) {
+        if let Some(invalid) = mode.split(',').find(|mode| !MODES.contains(mode)) {
+            return Err(Error::msg(format!(
+                "invalid mode `{}` of volume mount `{}`",
+                invalid, bind
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Parses a device in the form of `/dev/host[:/dev/container[:permissions]]`.
+fn parse_device(device: &str) -> Result<Labels> {
+    let mut parts = device.split(':');
+    let host = parts.next().filter(|host| !host.is_empty());
+    let host = host.ok_or_else(|| Error::msg(format!("invalid device `{}`", device)))?;
+    let container = parts.next().filter(|c| !c.is_empty()).unwrap_or(host);
+    let permissions = parts.next().unwrap_or("rwm");
+
+    let mut labels = Labels::new();
+    labels.insert("PathOnHost".to_string(), host.to_string());
+    labels.insert("PathInContainer".to_string(), container.to_string());
+    labels.insert("CgroupPermissions".to_string(), permissions.to_string());
+    Ok(labels)
+}
+
 #[derive(Debug, Default)]
 pub struct RenameWindow {
     pub show: bool,
@@ -307,11 +616,190 @@ impl RenameWindow {
     }
 }
 
+/// Restart policies accepted by docker
+const RESTART_POLICIES: &[&str] = &["no", "always", "unless-stopped", "on-failure"];
+
 #[derive(Debug, Default)]
-pub struct LogsViewData {
-    pub current_logs: Option<String>,
-    pub page: usize,
-    pub follow: bool,
+pub struct UpdateWindow {
+    pub show: bool,
+    pub id: ContainerId,
+    pub cpus: f32,
+    pub cpu_shares: f32,
+    pub memory: String,
+    pub memory_swap: String,
+    pub restart_policy: String,
+    pub maximum_retry_count: u64,
+}
+
+impl UpdateWindow {
+    fn toggle(&mut self) {
+        self.show = !self.show;
+    }
+
+    /// Fills the window with the current limits of a container.
+    fn load(&mut self, id: ContainerId, config: &HostConfig) {
+        self.id = id;
+        self.cpus = config.nano_cpus.unwrap_or_default() as f32 / 1_000_000_000.;
+        self.cpu_shares = config.cpu_shares.unwrap_or_default() as f32;
+        self.memory = config
+            .memory
+            .filter(|memory| *memory > 0)
+            .map(|memory| memory.to_string())
+            .unwrap_or_default();
+        self.memory_swap = config
+            .memory_swap
+            .filter(|swap| *swap != 0)
+            .map(|swap| swap.to_string())
+            .unwrap_or_default();
+        let (name, count) = config
+            .restart_policy
+            .as_ref()
+            .map(|policy| (policy.name.as_str(), policy.maximum_retry_count))
+            .unwrap_or_default();
+        self.restart_policy = if name.is_empty() { "no" } else { name }.to_string();
+        self.maximum_retry_count = count;
+    }
+
+    fn as_opts(&self) -> Result<UpdateContainerOpts> {
+        let mut opts = UpdateContainerOpts::builder()
+            .nano_cpus((self.cpus as f64 * 1_000_000_000.) as u64)
+            .restart_policy(
+                &self.restart_policy,
+                if self.restart_policy == "on-failure" {
+                    self.maximum_retry_count
+                } else {
+                    0
+                },
+            );
+        if self.cpu_shares > 0. {
+            opts = opts.cpu_shares(self.cpu_shares as u32);
+        }
+        let memory = if self.memory.is_empty() {
+            None
+        } else {
+            let memory = crate::convert_memory(&self.memory).context("invalid memory limit")?;
+            opts = opts.memory(memory);
+            Some(memory)
+        };
+        if self.memory_swap == "-1" {
+            opts = opts.memory_swap(-1);
+        } else if !self.memory_swap.is_empty() {
+            let swap = crate::convert_memory(&self.memory_swap).context("invalid memory swap")?;
+            if memory.map(|memory| swap < memory).unwrap_or_default() {
+                anyhow::bail!("memory swap must be greater than or equal to the memory limit");
+            }
+            opts = opts.memory_swap(swap.try_into().context("converting memory swap failed")?);
+        }
+
+        Ok(opts.build())
+    }
+}
+
+#[derive(Debug)]
+pub struct CommitWindow {
+    pub show: bool,
+    pub id: ContainerId,
+    pub repo: String,
+    pub tag: String,
+    pub comment: String,
+    pub author: String,
+    /// Dockerfile instructions to apply, one per line
+    pub changes: String,
+    pub pause: bool,
+}
+
+impl Default for CommitWindow {
+    fn default() -> Self {
+        Self {
+            show: false,
+            id: ContainerId::default(),
+            repo: String::new(),
+            tag: String::new(),
+            comment: String::new(),
+            author: String::new(),
+            changes: String::new(),
+            pause: true,
+        }
+    }
+}
+
+impl CommitWindow {
+    fn toggle(&mut self) {
+        self.show = !self.show;
+    }
+
+    fn opts(&self) -> ContainerCommitOpts {
+        let mut opts = ContainerCommitOpts::builder().pause(self.pause);
+        if !self.repo.is_empty() {
+            opts = opts.repo(&self.repo);
+        }
+        if !self.tag.is_empty() {
+            opts = opts.tag(&self.tag);
+        }
+        if !self.comment.is_empty() {
+            opts = opts.comment(&self.comment);
+        }
+        if !self.author.is_empty() {
+            opts = opts.author(&self.author);
+        }
+        let changes: Vec<_> = self
+            .changes
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .collect();
+        if !changes.is_empty() {
+            opts = opts.changes(changes);
+        }
+        opts.build()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct AttachViewData {
+    pub attached: bool,
+    pub output: String,
+    pub input: String,
+}
+
+#[derive(Debug, Default)]
+pub struct ExecViewData {
+    pub cmd: String,
+    pub user: String,
+    pub working_dir: String,
+    pub env: Vec<(String, String)>,
+    pub running: bool,
+    pub output: String,
+    pub history: Vec<String>,
+    pub history_pos: Option<usize>,
+}
+
+impl ExecViewData {
+    fn history_prev(&mut self) {
+        if self.history.is_empty() {
+            return;
+        }
+        let pos = match self.history_pos {
+            Some(pos) => pos.saturating_sub(1),
+            None => self.history.len() - 1,
+        };
+        self.history_pos = Some(pos);
+        self.cmd = self.history[pos].clone();
+    }
+
+    fn history_next(&mut self) {
+        match self.history_pos {
+            Some(pos) if pos + 1 < self.history.len() => {
+                self.history_pos = Some(pos + 1);
+                self.cmd = self.history[pos + 1].clone();
+            }
+            Some(_) => {
+                self.history_pos = None;
+                self.cmd.clear();
+            }
+            None => {}
+        }
+    }
 }
 
 #[derive(Debug, Default)]
@@ -320,6 +808,72 @@ pub struct ChangesViewData {
     pub page: usize,
 }
 
+#[derive(Debug)]
+pub struct FilePreview {
+    pub path: String,
+    pub size: usize,
+    /// `None` if the file is not a text file
+    pub text: Option<String>,
+}
+
+impl FilePreview {
+    pub fn new(path: String, bytes: Vec<u8>) -> Self {
+        let size = bytes.len();
+        let text = String::from_utf8(bytes)
+            .ok()
+            .filter(|text| !text.contains('\0'));
+        Self { path, size, text }
+    }
+}
+
+#[derive(Debug)]
+pub struct FilesViewData {
+    /// Directory currently displayed
+    pub path: String,
+    pub path_input: String,
+    pub entries: Option<Vec<FileEntry>>,
+    /// Set when only a part of the entries was listed
+    pub truncated: bool,
+    pub error: Option<String>,
+    pub loading: bool,
+    pub preview: Option<FilePreview>,
+}
+
+impl Default for FilesViewData {
+    fn default() -> Self {
+        Self {
+            path: "/".to_string(),
+            path_input: "/".to_string(),
+            entries: None,
+            truncated: false,
+            error: None,
+            loading: false,
+            preview: None,
+        }
+    }
+}
+
+impl FilesViewData {
+    /// Changes the current directory, the entries are listed the next time the view is displayed.
+    pub fn set_path(&mut self, path: String) {
+        self.path_input = path.clone();
+        self.path = path;
+        self.refresh();
+    }
+
+    pub fn refresh(&mut self) {
+        self.entries = None;
+        self.truncated = false;
+        self.error = None;
+    }
+
+    fn parent(&self) -> Option<String> {
+        std::path::Path::new(&self.path)
+            .parent()
+            .map(|parent| parent.to_string_lossy().to_string())
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct ContainersTab {
     pub containers: Vec<ContainerInfo>,
@@ -329,9 +883,19 @@ pub struct ContainersTab {
 
     pub logs_view_data: LogsViewData,
     pub changes_view_data: ChangesViewData,
+    pub attach_view_data: AttachViewData,
+    pub exec_view_data: ExecViewData,
+    pub files_view_data: FilesViewData,
+    pub aggregated_logs_view_data: AggregatedLogsViewData,
+    pub compose_view_data: ComposeViewData,
 
     pub create_data: ContainerCreateData,
+    pub templates: TemplatesData,
+    /// A `docker run` command line to fill the create view with
+    pub docker_run_input: String,
     pub rename_window: RenameWindow,
+    pub commit_window: CommitWindow,
+    pub update_window: UpdateWindow,
     pub container_view: ContainerView,
     pub central_view: CentralView,
 }
@@ -339,17 +903,40 @@ pub struct ContainersTab {
 impl ContainersTab {
     pub fn clear(&mut self) {
         self.containers.clear();
+        self.aggregated_logs_view_data.clear();
         self.clear_container();
     }
 
     pub fn clear_container(&mut self) {
         self.current_container = None;
         self.current_stats = None;
-        self.logs_view_data.current_logs = None;
-        self.logs_view_data.page = 0;
+        self.logs_view_data.clear();
+        self.attach_view_data = AttachViewData::default();
+        self.exec_view_data.running = false;
+        self.exec_view_data.output.clear();
+        self.files_view_data = FilesViewData::default();
     }
 }
 
+/// Displays a scrollable monospace area that keeps the view at the newest output.
+fn terminal_output(ui: &mut egui::Ui, id: &str, output: &str) {
+    let color = if ui.visuals().dark_mode {
+        *color::D_BG_000
+    } else {
+        *color::L_BG_4
+    };
+    Frame::none().fill(color).show(ui, |ui| {
+        egui::ScrollArea::vertical()
+            .id_source(id)
+            .max_height(ui.available_height() - 50.)
+            .stick_to_bottom()
+            .show(ui, |ui| {
+                ui.allocate_space((ui.available_rect_before_wrap().width(), 0.).into());
+                ui.add(Label::new(RichText::new(output).monospace()).wrap(true));
+            });
+    });
+}
+
 impl App {
     pub fn link_container(&self, ui: &mut egui::Ui, id: ContainerIdRef, name: Option<&str>) {
         if ui
@@ -366,15 +953,20 @@ impl App {
             .on_hover_text("click to follow")
             .clicked()
         {
-            let _ = self.send_event(EventRequest::Container(ContainerEvent::TraceStart {
-                id: id.to_string(),
-            }));
-            let _ = self.send_event(EventRequest::NotifyGui(GuiEvent::SetTab(
-                crate::app::Tab::Containers,
-            )));
+            self.follow_container(id);
         }
     }
 
+    /// Displays the details of container `id` on the containers tab.
+    pub fn follow_container(&self, id: ContainerIdRef) {
+        let _ = self.send_event(EventRequest::Container(ContainerEvent::TraceStart {
+            id: id.to_string(),
+        }));
+        let _ = self.send_event(EventRequest::NotifyGui(GuiEvent::SetTab(
+            crate::app::Tab::Containers,
+        )));
+    }
+
     pub fn containers_side(&mut self, ui: &mut egui::Ui) {
         ui.vertical(|ui| {
             self.containers_menu(ui);
@@ -387,8 +979,14 @@ impl App {
             CentralView::None => {}
             CentralView::Container => self.container_details(ui),
             CentralView::Create => self.container_create(ui),
+            CentralView::Logs => self.aggregated_logs(ui),
+            CentralView::Compose => self.compose(ui),
+            CentralView::Ports => self.ports_overview(ui),
         }
         self.display_rename_window(ui);
+        self.display_commit_window(ui);
+        self.display_update_window(ui);
+        self.display_connect_window(ui);
     }
 
     fn containers_menu(&mut self, ui: &mut egui::Ui) {
@@ -403,6 +1001,20 @@ impl App {
                 CentralView::Create,
                 "create",
             );
+            ui.selectable_value(&mut self.containers.central_view, CentralView::Logs, "logs")
+                .on_hover_text("follow logs of multiple containers at once");
+            ui.selectable_value(
+                &mut self.containers.central_view,
+                CentralView::Compose,
+                "compose",
+            )
+            .on_hover_text("create containers from a docker compose file");
+            ui.selectable_value(
+                &mut self.containers.central_view,
+                CentralView::Ports,
+                "ports",
+            )
+            .on_hover_text("ports published by all containers");
         });
         egui::Grid::new("containers_button_menu").show(ui, |ui| {
             if ui.button("prune").clicked() {
@@ -417,160 +1029,474 @@ impl App {
     }
 
     fn containers_scroll(&mut self, ui: &mut egui::Ui) {
+        let mut error = None;
+        let mut popup = None;
+        let mut central_view = self.containers.central_view;
+
+        let mut projects = BTreeMap::<&str, BTreeMap<&str, Vec<&ContainerInfo>>>::new();
+        let mut standalone = vec![];
+        for container in &self.containers.containers {
+            match container.labels.get(COMPOSE_PROJECT_LABEL) {
+                Some(project) => projects
+                    .entry(project.as_str())
+                    .or_default()
+                    .entry(
+                        container
+                            .labels
+                            .get(COMPOSE_SERVICE_LABEL)
+                            .map(String::as_str)
+                            .unwrap_or_default(),
+                    )
+                    .or_default()
+                    .push(container),
+                None => standalone.push(container),
+            }
+        }
+
         egui::ScrollArea::vertical().show(ui, |ui| {
             ui.wrap_text();
+            for (project, services) in &projects {
+                let containers = services.values().flatten().copied().collect::<Vec<_>>();
+                CollapsingState::load_with_default_open(
+                    ui.ctx(),
+                    egui::Id::new(("compose_project", project)),
+                    true,
+                )
+                .show_header(ui, |ui| {
+                    self.compose_project_header(ui, project, &containers, &mut error, &mut popup);
+                })
+                .body(|ui| {
+                    for (service, containers) in services {
+                        egui::CollapsingHeader::new(format!(
+                            "{} ({}/{})",
+                            service,
+                            running_count(containers),
+                            containers.len()
+                        ))
+                        .id_source(("compose_service", project, service))
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            egui::Grid::new(("side_panel", project, service))
+                                .spacing((0., 0.))
+                                .max_col_width(self.side_panel_size())
+                                .show(ui, |ui| {
+                                    for container in containers {
+                                        self.container_side_entry(
+                                            ui,
+                                            container,
+                                            &mut central_view,
+                                            &mut error,
+                                            &mut popup,
+                                        );
+                                        ui.end_row();
+                                    }
+                                });
+                        });
+                    }
+                });
+            }
             egui::Grid::new("side_panel")
                 .spacing((0., 0.))
                 .max_col_width(self.side_panel_size())
                 .show(ui, |ui| {
-                    let mut error = None;
-                    let mut popup = None;
-                    let mut central_view = self.containers.central_view;
-                    for container in &self.containers.containers {
-                        let color = color_for_state(&container.state);
-                        let dot = state_icon(color);
-                        let frame_color = ui.visuals().widgets.open.bg_fill;
-                        let selected = self
-                            .containers
-                            .current_container
-                            .as_ref()
-                            .map(|c| c.id == container.id && central_view == CentralView::Container)
-                            .unwrap_or_default();
+                    for container in &standalone {
+                        self.container_side_entry(
+                            ui,
+                            container,
+                            &mut central_view,
+                            &mut error,
+                            &mut popup,
+                        );
+                        ui.end_row();
+                    }
+                });
+        });
 
-                        let frame = if selected {
-                            egui::Frame::none()
-                                .fill(frame_color)
-                                .inner_margin(Margin::symmetric(0., 0.))
-                        } else {
-                            egui::Frame::none().inner_margin(Margin::symmetric(0., 0.))
-                        };
-                        frame.show(ui, |ui| {
-                            egui::Grid::new(&container.id)
-                                .spacing((0., 5.))
-                                .show(ui, |ui| {
-                                    ui.add(ui::line_with_size(frame, (self.side_panel_size(), 1.)));
-                                    ui.end_row();
-                                    egui::Grid::new(&container.id[0..8])
-                                        .spacing((2.5, 5.))
-                                        .max_col_width(self.side_panel_size())
-                                        .show(ui, |ui| {
-                                            ui.add_space(5.);
-                                            ui.scope(|ui| {
-                                                ui.add(dot);
-                                                if let Some(name) = container.names.first() {
-                                                    ui.add(
-                                                        Label::new(
-                                                            RichText::new(
-                                                                name.trim_start_matches('/'),
-                                                            )
-                                                            .strong()
-                                                            .heading(),
-                                                        )
-                                                        .wrap(true),
-                                                    );
-                                                } else {
-                                                    ui.add(
-                                                        Label::new(
-                                                            RichText::new(&container.id[..12])
-                                                                .strong()
-                                                                .heading(),
-                                                        )
-                                                        .wrap(true),
-                                                    );
-                                                }
-                                            });
-                                            ui.end_row();
-                                            ui.add_space(5.);
-                                            self.link_image(ui, &container.image, None);
-                                            ui.end_row();
+        if let Some(error) = error {
+            self.add_error(error);
+        }
+        if let Some(popup) = popup {
+            self.popups.push_back(popup);
+        }
+        self.containers.central_view = central_view;
+    }
 
-                                            ui.add_space(5.);
-                                            ui.add(
-                                                Label::new(
-                                                    RichText::new(&container.status)
-                                                        .italics()
-                                                        .strong(),
-                                                )
-                                                .wrap(true),
-                                            );
-                                            ui.end_row();
+    /// Displays the name and the status of a compose project with actions that apply to all of
+    /// its containers.
+    fn compose_project_header(
+        &self,
+        ui: &mut egui::Ui,
+        project: &str,
+        containers: &[&ContainerInfo],
+        error: &mut Option<Box<anyhow::Error>>,
+        popup: &mut Option<ui::ActionPopup>,
+    ) {
+        let running = running_count(containers);
+        let color = if running == containers.len() {
+            egui::Color32::GREEN
+        } else if running > 0 {
+            egui::Color32::YELLOW
+        } else {
+            egui::Color32::RED
+        };
+        ui.add(state_icon(color));
+        ui.add(Label::new(RichText::new(project).strong().heading()).wrap(true));
+        ui.label(format!("{}/{} running", running, containers.len()));
 
-                                            ui.add_space(5.);
-                                            ui.scope(|ui| {
-                                                if ui
-                                                    .button(icon::INFO)
-                                                    .on_hover_text("Inspect this container")
-                                                    .clicked()
-                                                {
-                                                    central_view = CentralView::Container;
-                                                    if let Err(e) =
-                                                        self.send_event(EventRequest::Container(
-                                                            ContainerEvent::TraceStart {
-                                                                id: container.id.clone(),
-                                                            },
-                                                        ))
-                                                    {
-                                                        error = Some(Box::new(e));
-                                                    };
-                                                }
-                                                if ui
-                                                    .button(icon::DELETE)
-                                                    .on_hover_text("Delete this container")
-                                                    .clicked()
-                                                {
-                                                    popup = Some(
-                                                        ui::ActionPopup::builder(
-                                                            EventRequest::Container(
-                                                                ContainerEvent::Delete {
-                                                                    id: container.id.clone(),
-                                                                },
-                                                            ),
-                                                        )
-                                                        .title("Delete container")
-                                                        .text(format!(
-                                                    "are you sure you want to delete container {}?",
-                                                    &container.id
-                                                ))
-                                                        .build(),
-                                                    );
-                                                }
-                                                match container.state {
-                                                    ContainerStatus::Running => {
-                                                        btn!(stop => self, ui, container, error);
-                                                        btn!(pause => self, ui, container, error);
-                                                        btn!(restart => self, ui, container, error);
-                                                    }
-                                                    ContainerStatus::Paused => {
-                                                        btn!(stop => self, ui, container, error);
-                                                        btn!(unpause => self, ui, container, error);
-                                                        btn!(restart => self, ui, container, error);
-                                                    }
-                                                    _ => {
-                                                        btn!(start => self, ui, container, error);
-                                                    }
-                                                }
-                                            });
-                                            ui.end_row();
-                                        });
-                                    ui.end_row();
-                                    ui.scope(|_| {});
-                                    ui.end_row();
-                                });
+        let mut events = vec![];
+        if ui
+            .button(icon::PLAY)
+            .on_hover_text("start all containers of this project")
+            .clicked()
+        {
+            events = containers
+                .iter()
+                .filter(|c| !matches!(c.state, ContainerStatus::Running | ContainerStatus::Paused))
+                .map(|c| ContainerEvent::Start { id: c.id.clone() })
+                .collect();
+        }
+        if ui
+            .button(icon::STOP)
+            .on_hover_text("stop all containers of this project")
+            .clicked()
+        {
+            events = containers
+                .iter()
+                .filter(|c| matches!(c.state, ContainerStatus::Running | ContainerStatus::Paused))
+                .map(|c| ContainerEvent::Stop { id: c.id.clone() })
+                .collect();
+        }
+        if ui
+            .button(icon::RESTART)
+            .on_hover_text("restart all containers of this project")
+            .clicked()
+        {
+            events = containers
+                .iter()
+                .map(|c| ContainerEvent::Restart { id: c.id.clone() })
+                .collect();
+        }
+        if ui
+            .button(icon::DELETE)
+            .on_hover_text("delete all containers of this project")
+            .clicked()
+        {
+            let mut deletes = containers
+                .iter()
+                .map(|c| EventRequest::Container(ContainerEvent::Delete { id: c.id.clone() }));
+            if let Some(first) = deletes.next() {
+                *popup = Some(
+                    deletes
+                        .fold(ui::ActionPopup::builder(first), |builder, event| {
+                            builder.action(event)
+                        })
+                        .title("Delete compose project")
+                        .text(format!(
+                            "are you sure you want to delete all {} containers of project {}?",
+                            containers.len(),
+                            project
+                        ))
+                        .build(),
+                );
+            }
+        }
+
+        for event in events {
+            if let Err(e) = self.send_event(EventRequest::Container(event)) {
+                *error = Some(Box::new(e));
+            }
+        }
+    }
+
+    fn container_side_entry(
+        &self,
+        ui: &mut egui::Ui,
+        container: &ContainerInfo,
+        central_view: &mut CentralView,
+        error: &mut Option<Box<anyhow::Error>>,
+        popup: &mut Option<ui::ActionPopup>,
+    ) {
+        let color = color_for_state(&container.state);
+        let dot = state_icon(color);
+        let frame_color = ui.visuals().widgets.open.bg_fill;
+        let selected = self
+            .containers
+            .current_container
+            .as_ref()
+            .map(|c| c.id == container.id && *central_view == CentralView::Container)
+            .unwrap_or_default();
+
+        let frame = if selected {
+            egui::Frame::none()
+                .fill(frame_color)
+                .inner_margin(Margin::symmetric(0., 0.))
+        } else {
+            egui::Frame::none().inner_margin(Margin::symmetric(0., 0.))
+        };
+        frame.show(ui, |ui| {
+            egui::Grid::new(&container.id)
+                .spacing((0., 5.))
+                .show(ui, |ui| {
+                    ui.add(ui::line_with_size(frame, (self.side_panel_size(), 1.)));
+                    ui.end_row();
+                    egui::Grid::new(&container.id[0..8])
+                        .spacing((2.5, 5.))
+                        .max_col_width(self.side_panel_size())
+                        .show(ui, |ui| {
+                            ui.add_space(5.);
+                            ui.scope(|ui| {
+                                ui.add(dot);
+                                if let Some(name) = container.names.first() {
+                                    ui.add(
+                                        Label::new(
+                                            RichText::new(name.trim_start_matches('/'))
+                                                .strong()
+                                                .heading(),
+                                        )
+                                        .wrap(true),
+                                    );
+                                } else {
+                                    ui.add(
+                                        Label::new(
+                                            RichText::new(&container.id[..12]).strong().heading(),
+                                        )
+                                        .wrap(true),
+                                    );
+                                }
+                            });
+                            ui.end_row();
+                            ui.add_space(5.);
+                            self.link_image(ui, &container.image, None);
+                            ui.end_row();
+
+                            ui.add_space(5.);
+                            ui.add(
+                                Label::new(RichText::new(&container.status).italics().strong())
+                                    .wrap(true),
+                            );
+                            ui.end_row();
+
+                            ui.add_space(5.);
+                            ui.scope(|ui| {
+                                if ui
+                                    .button(icon::INFO)
+                                    .on_hover_text("Inspect this container")
+                                    .clicked()
+                                {
+                                    *central_view = CentralView::Container;
+                                    if let Err(e) = self.send_event(EventRequest::Container(
+                                        ContainerEvent::TraceStart {
+                                            id: container.id.clone(),
+                                        },
+                                    )) {
+                                        *error = Some(Box::new(e));
+                                    };
+                                }
+                                if ui
+                                    .button(icon::DELETE)
+                                    .on_hover_text("Delete this container")
+                                    .clicked()
+                                {
+                                    *popup = Some(
+                                        ui::ActionPopup::builder(EventRequest::Container(
+                                            ContainerEvent::Delete {
+                                                id: container.id.clone(),
+                                            },
+                                        ))
+                                        .title("Delete container")
+                                        .text(format!(
+                                            "are you sure you want to delete container {}?",
+                                            &container.id
+                                        ))
+                                        .build(),
+                                    );
+                                }
+                                match container.state {
+                                    ContainerStatus::Running => {
+                                        btn!(stop => self, ui, container, *error);
+                                        btn!(pause => self, ui, container, *error);
+                                        btn!(restart => self, ui, container, *error);
+                                    }
+                                    ContainerStatus::Paused => {
+                                        btn!(stop => self, ui, container, *error);
+                                        btn!(unpause => self, ui, container, *error);
+                                        btn!(restart => self, ui, container, *error);
+                                    }
+                                    _ => {
+                                        btn!(start => self, ui, container, *error);
+                                    }
+                                }
+                            });
+                            ui.end_row();
                         });
-                        ui.end_row();
-                    }
-                    if let Some(error) = error {
-                        self.add_error(error);
-                    }
-                    if let Some(popup) = popup {
-                        self.popups.push_back(popup);
-                    }
-                    self.containers.central_view = central_view;
+                    ui.end_row();
+                    ui.scope(|_| {});
+                    ui.end_row();
                 });
         });
     }
 
+    fn container_templates(&mut self, ui: &mut egui::Ui) {
+        let mut errors = vec![];
+        let mut changed = false;
+        egui::CollapsingHeader::new("Templates")
+            .default_open(false)
+            .show(ui, |ui| {
+                let data = &mut self.containers.templates;
+                Grid::new("container_templates").show(ui, |ui| {
+                    key!(ui, "Template:");
+                    egui::ComboBox::from_id_source("container_templates_selected")
+                        .selected_text(data.selected.as_deref().unwrap_or_default())
+                        .show_ui(ui, |ui| {
+                            for template in &data.templates {
+                                ui.selectable_value(
+                                    &mut data.selected,
+                                    Some(template.name.clone()),
+                                    &template.name,
+                                );
+                            }
+                        });
+                    ui.horizontal(|ui| {
+                        let selected = data.selected.as_deref().and_then(|name| data.get(name));
+                        if let Some(template) = selected {
+                            if ui
+                                .button("load")
+                                .on_hover_text("fill the form with this template")
+                                .clicked()
+                            {
+                                self.containers.create_data = template.data.clone();
+                            }
+                            if ui
+                                .button("export")
+                                .on_hover_text("save this template to a file to share it")
+                                .clicked()
+                            {
+                                let filename = format!("{}.yml", template.name);
+                                match native_dialog::FileDialog::new()
+                                    .add_filter("YAML file", &["yml", "yaml"])
+                                    .set_filename(&filename)
+                                    .show_save_single_file()
+                                {
+                                    Ok(Some(path)) => {
+                                        if let Err(e) =
+                                            templates::save(std::slice::from_ref(template), &path)
+                                        {
+                                            errors.push(e);
+                                        }
+                                    }
+                                    Ok(None) => {}
+                                    Err(e) => errors.push(Error::msg(format!(
+                                        "failed to spawn a file dialog - {}",
+                                        e
+                                    ))),
+                                }
+                            }
+                            if ui
+                                .button(icon::DELETE)
+                                .on_hover_text("delete this template")
+                                .clicked()
+                            {
+                                let name = template.name.clone();
+                                data.remove(&name);
+                                changed = true;
+                            }
+                        }
+                        if ui
+                            .button("import")
+                            .on_hover_text("add templates from a file")
+                            .clicked()
+                        {
+                            match native_dialog::FileDialog::new()
+                                .add_filter("YAML file", &["yml", "yaml"])
+                                .show_open_single_file()
+                            {
+                                Ok(Some(path)) => match templates::load(&path) {
+                                    Ok(imported) => {
+                                        for template in imported {
+                                            data.insert(template);
+                                        }
+                                        changed = true;
+                                    }
+                                    Err(e) => errors.push(e),
+                                },
+                                Ok(None) => {}
+                                Err(e) => errors.push(Error::msg(format!(
+                                    "failed to spawn a file dialog - {}",
+                                    e
+                                ))),
+                            }
+                        }
+                    });
+                    ui.end_row();
+
+                    key!(ui, "Save as:");
+                    ui.text_edit_singleline(&mut data.name);
+                    if ui
+                        .button(icon::SAVE)
+                        .on_hover_text(
+                            "save the current form as a template, a template with the same \
+                             name is replaced",
+                        )
+                        .clicked()
+                    {
+                        if data.name.is_empty() {
+                            errors.push(Error::msg("Name of the template can't be empty"));
+                        } else {
+                            let name = std::mem::take(&mut data.name);
+                            data.insert(Template {
+                                name: name.clone(),
+                                data: self.containers.create_data.clone(),
+                            });
+                            data.selected = Some(name);
+                            changed = true;
+                        }
+                    }
+                    ui.end_row();
+                });
+            });
+
+        if changed {
+            if let Err(e) = self.containers.templates.save() {
+                errors.push(e);
+            }
+        }
+        for error in errors {
+            self.add_error(error);
+        }
+    }
+
+    fn container_docker_run(&mut self, ui: &mut egui::Ui) {
+        let mut parsed = None;
+        egui::CollapsingHeader::new("Docker run")
+            .default_open(false)
+            .show(ui, |ui| {
+                ui.add(
+                    egui::TextEdit::multiline(&mut self.containers.docker_run_input)
+                        .code_editor()
+                        .desired_rows(3)
+                        .hint_text("docker run --name example -p 8080:80 nginx"),
+                );
+                if ui
+                    .button("parse")
+                    .on_hover_text("fill the form with the options of this command")
+                    .clicked()
+                {
+                    parsed = Some(ContainerCreateData::from_docker_run(
+                        self.containers.docker_run_input.trim(),
+                    ));
+                }
+            });
+        match parsed {
+            Some(Ok(data)) => self.containers.create_data = data,
+            Some(Err(e)) => self.add_error(e),
+            None => {}
+        }
+    }
+
     fn container_create(&mut self, ui: &mut egui::Ui) {
+        self.container_templates(ui);
+        self.container_docker_run(ui);
+        ui.add_space(10.);
         Grid::new("container_create").show(ui, |ui| {
             ui.scope(|_| {});
             ui.allocate_space((self.side_panel_size(), 0.).into());
@@ -579,11 +1505,18 @@ impl App {
             ui.text_edit_singleline(&mut self.containers.create_data.image);
             ui.end_row();
             key!(ui, "Command:");
-            ui.text_edit_singleline(&mut self.containers.create_data.command);
+            ui.text_edit_singleline(&mut self.containers.create_data.command)
+                .on_hover_text("arguments with spaces can be quoted like in a shell");
+            ui.end_row();
+            key!(ui, "Entrypoint:");
+            ui.text_edit_singleline(&mut self.containers.create_data.entrypoint);
             ui.end_row();
             key!(ui, "Name:");
             ui.text_edit_singleline(&mut self.containers.create_data.name);
             ui.end_row();
+            key!(ui, "Hostname:");
+            ui.text_edit_singleline(&mut self.containers.create_data.hostname);
+            ui.end_row();
             key!(ui, "Working directory:");
             ui.text_edit_singleline(&mut self.containers.create_data.working_dir);
             ui.end_row();
@@ -596,6 +1529,38 @@ impl App {
             key!(ui, "Network mode:");
             ui.text_edit_singleline(&mut self.containers.create_data.network_mode);
             ui.end_row();
+            ui.add(ui::EditableList::builder_key(&mut self.containers.create_data.networks)
+                .heading("Additional networks:")
+                .add_hover_text("Connect the container to another network once it's created")
+                .build()
+            );
+            ui.end_row();
+            key!(ui, "Restart policy:");
+            egui::ComboBox::from_id_source("container_create_restart_policy")
+                .selected_text(&self.containers.create_data.restart_policy)
+                .show_ui(ui, |ui| {
+                    for policy in RESTART_POLICIES {
+                        ui.selectable_value(
+                            &mut self.containers.create_data.restart_policy,
+                            policy.to_string(),
+                            *policy,
+                        );
+                    }
+                });
+            ui.end_row();
+            if self.containers.create_data.restart_policy == "on-failure" {
+                key!(ui, "Maximum retries:");
+                ui.add(egui::DragValue::new(&mut self.containers.create_data.maximum_retry_count));
+                ui.end_row();
+            }
+            key!(ui, "Stop signal:");
+            ui.text_edit_singleline(&mut self.containers.create_data.stop_signal)
+                .on_hover_text("for example `SIGTERM`");
+            ui.end_row();
+            key!(ui, "Stop timeout:");
+            ui.text_edit_singleline(&mut self.containers.create_data.stop_timeout)
+                .on_hover_text("seconds to wait before killing the container");
+            ui.end_row();
             key!(ui, "Logging driver:");
             ui.text_edit_singleline(&mut self.containers.create_data.log_driver);
             ui.end_row();
@@ -644,11 +1609,60 @@ impl App {
             ui.add(ui::EditableList::builder_key(&mut self.containers.create_data.volumes)
                 .heading("Volume mounts:")
                 .add_hover_text(
-                "Add a volume mount from host in the form of `/some/host/path:/some/container/path`)"
+                "Add a volume mount from host in the form of `/some/host/path:/some/container/path[:mode]` where mode is for example `ro` or `rw`"
                 ).build()
             );
             ui.end_row();
 
+            ui.add(ui::EditableList::builder_key_val(&mut self.containers.create_data.ports)
+                .heading("Ports:")
+                .id_source("container_create_ports")
+                .add_hover_text("Publish a port of the container like `80/tcp`, without a host port a free one is picked")
+                .key_heading("Container:")
+                .val_heading("Host:")
+                .build()
+            );
+            ui.end_row();
+
+            ui.add(ui::EditableList::builder_key(&mut self.containers.create_data.exposed)
+                .heading("Exposed ports:")
+                .id_source("container_create_exposed")
+                .add_hover_text("Expose a port of the container like `80/tcp` without publishing it")
+                .build()
+            );
+            ui.end_row();
+            let data = &self.containers.create_data;
+            // the ports of a container that is recreated are released before the new one starts
+            let replaced = data.original.as_ref().filter(|_| data.recreate).map(|(id, _)| id.as_str());
+            let in_use = ports::ports_in_use(&self.containers.containers, &data.ports, replaced);
+            if !in_use.is_empty() {
+                ui.label(RichText::new(format!("Host ports already in use: {}", in_use.join(", "))).color(egui::Color32::RED));
+                ui.end_row();
+            }
+            ui.checkbox(&mut self.containers.create_data.publish_all_ports, "Publish all exposed ports");
+            ui.end_row();
+
+            ui.add(ui::EditableList::builder_key(&mut self.containers.create_data.dns)
+                .heading("DNS servers:")
+                .add_hover_text("Add a DNS server")
+                .build()
+            );
+            ui.end_row();
+
+            ui.add(ui::EditableList::builder_key(&mut self.containers.create_data.extra_hosts)
+                .heading("Extra hosts:")
+                .add_hover_text("Add a host in the form of `hostname:ip`")
+                .build()
+            );
+            ui.end_row();
+
+            ui.add(ui::EditableList::builder_key(&mut self.containers.create_data.devices)
+                .heading("Devices:")
+                .add_hover_text("Add a device in the form of `/dev/host[:/dev/container[:permissions]]`")
+                .build()
+            );
+            ui.end_row();
+
             ui.add(ui::EditableList::builder_key(&mut self.containers.create_data.links)
                 .heading("Links:")
                 .add_hover_text(
@@ -674,20 +1688,70 @@ impl App {
             ui.text_edit_singleline(&mut self.containers.create_data.memory_swap);
             ui.end_row();
 
+            if let Some((_, original)) = &self.containers.create_data.original {
+                let warning = format!(
+                    "Settings this form doesn't support, like ulimits or the healthcheck, \
+                     aren't copied from `{}`",
+                    original
+                );
+                ui.label(RichText::new(warning).color(egui::Color32::YELLOW));
+                ui.end_row();
+                let text = format!("Replace `{}` with this container", original);
+                ui.checkbox(&mut self.containers.create_data.recreate, text)
+                    .on_hover_text(
+                        "the original container is only removed once the new one is created and \
+                         started",
+                    );
+                ui.end_row();
+            }
+
             ui.scope(|ui| {
-                if ui.button("create").clicked() {
-                    if self.containers.create_data.image.is_empty() {
+                let create = ui.button("create").clicked();
+                let create_and_start = ui.button("create and start").clicked();
+                if create || create_and_start {
+                    let data = &self.containers.create_data;
+                    let original = data.original.as_ref().filter(|_| data.recreate);
+                    if data.image.is_empty() {
                         self.add_error("Image name is required to create a container");
+                    } else if original.is_none()
+                        && data
+                            .original
+                            .as_ref()
+                            .map(|(_, name)| name == &data.name)
+                            .unwrap_or_default()
+                    {
+                        self.add_error(
+                            "A container with this name already exists, change the name or \
+                             recreate the original container",
+                        );
                     } else {
-                            match self.containers.create_data.as_opts() {
-                                Ok(opts) => {
-
-                        self.send_event_notify(EventRequest::Container(ContainerEvent::Create(
-                                    opts
-                        )));
-                                }
-                                Err(e) => self.add_error(e)
+                        match data.as_opts() {
+                            Ok(opts) => {
+                                let networks = data
+                                    .networks
+                                    .iter()
+                                    .filter(|network| !network.is_empty())
+                                    .cloned()
+                                    .collect();
+                                let event = match original {
+                                    Some((id, _)) => ContainerEvent::Recreate {
+                                        id: id.clone(),
+                                        image: data.image.clone(),
+                                        opts,
+                                        networks,
+                                        start: create_and_start,
+                                    },
+                                    None => ContainerEvent::Create {
+                                        opts,
+                                        networks,
+                                        aliases: vec![],
+                                        start: create_and_start,
+                                    },
+                                };
+                                self.send_event_notify(EventRequest::Container(event));
                             }
+                            Err(e) => self.add_error(e),
+                        }
                     }
                 }
                 ui.add_space(5.);
@@ -701,6 +1765,11 @@ impl App {
     fn container_details(&mut self, ui: &mut egui::Ui) {
         let mut error = None;
         let mut rename_id = None;
+        let mut commit_id = None;
+        let mut update = None;
+        let mut duplicate = None;
+        let mut docker_run = None;
+        let mut connect = None;
         if let Some(container) = &self.containers.current_container {
             let color = if is_running(container) {
                 egui::Color32::GREEN
@@ -723,6 +1792,71 @@ impl App {
                 if ui.button("rename").clicked() {
                     rename_id = Some(container.id.clone());
                 }
+                if ui
+                    .button("export")
+                    .on_hover_text("export the filesystem of the container as tar archive")
+                    .clicked()
+                {
+                    let tar_name = format!("container_{}", trim_id(&container.id));
+                    match native_dialog::FileDialog::new()
+                        .add_filter("tar archive", &["tar"])
+                        .set_filename(&tar_name[..])
+                        .show_save_single_file()
+                    {
+                        Ok(Some(output_path)) => {
+                            if let Err(e) =
+                                self.send_event(EventRequest::Container(ContainerEvent::Export {
+                                    id: container.id.clone(),
+                                    output_path,
+                                }))
+                            {
+                                error = Some(Box::new(e));
+                            }
+                        }
+                        Ok(None) => {}
+                        Err(e) => {
+                            error = Some(Box::new(Error::msg(format!(
+                                "failed to spawn a file dialog - {}",
+                                e
+                            ))))
+                        }
+                    }
+                }
+                if ui
+                    .button("commit")
+                    .on_hover_text("create a new image from the changes of the container")
+                    .clicked()
+                {
+                    commit_id = Some(container.id.clone());
+                }
+                if ui
+                    .button("update")
+                    .on_hover_text("change resource limits and restart policy of the container")
+                    .clicked()
+                {
+                    update = Some((container.id.clone(), container.host_config.clone()));
+                }
+                if ui
+                    .button("duplicate")
+                    .on_hover_text("create a new container with the configuration of this one")
+                    .clicked()
+                {
+                    duplicate = Some(ContainerCreateData::from_details(container));
+                }
+                if ui
+                    .button("copy as docker run")
+                    .on_hover_text("copy a command line that runs an equivalent container")
+                    .clicked()
+                {
+                    docker_run = Some(ContainerCreateData::from_details(container).to_docker_run());
+                }
+                if ui
+                    .button("networks")
+                    .on_hover_text("connect the container to a network or disconnect it")
+                    .clicked()
+                {
+                    connect = Some(container.id.clone());
+                }
             });
             ui.add_space(10.);
             ui.horizontal(|ui| {
@@ -754,7 +1888,17 @@ impl App {
                         ContainerView::Attach,
                         "attach",
                     );
+                    ui.selectable_value(
+                        &mut self.containers.container_view,
+                        ContainerView::Exec,
+                        "exec",
+                    );
                 }
+                ui.selectable_value(
+                    &mut self.containers.container_view,
+                    ContainerView::Files,
+                    "files",
+                );
             });
             ui.add_space(15.);
             match self.containers.container_view {
@@ -767,13 +1911,40 @@ impl App {
                 }
                 ContainerView::Processes => self.container_processes(ui),
                 ContainerView::Changes => self.container_changes(ui),
-                ContainerView::Attach => {}
+                ContainerView::Attach => self.container_attach(ui),
+                ContainerView::Exec => self.container_exec(ui),
+                ContainerView::Files => self.container_files(ui),
             }
         }
         if let Some(id) = rename_id {
             self.containers.rename_window.toggle();
             self.containers.rename_window.id = id;
         }
+        if let Some(id) = commit_id {
+            self.containers.commit_window.toggle();
+            self.containers.commit_window.id = id;
+        }
+        match docker_run {
+            Some(Ok(cmd)) => match crate::save_to_clipboard(cmd) {
+                Ok(_) => self.add_notification("copied the docker run command to clipboard"),
+                Err(e) => self.add_error(format!("failed to save content to clipboard - {}", e)),
+            },
+            Some(Err(e)) => self.add_error(e),
+            None => {}
+        }
+        if let Some(data) = duplicate {
+            self.containers.create_data = data;
+            self.containers.central_view = CentralView::Create;
+        }
+        if let Some(id) = connect {
+            self.networks.connect_window.open_for_container(id);
+            // networks are otherwise only listed on the networks tab
+            self.send_event_notify(EventRequest::Network(NetworkEvent::List(None)));
+        }
+        if let Some((id, config)) = update {
+            self.containers.update_window.load(id, &config);
+            self.containers.update_window.show = true;
+        }
         if let Some(error) = error {
             self.add_error(error);
         }
@@ -920,6 +2091,39 @@ impl App {
                 });
                 ui.end_row();
             }
+
+            let mut published = container
+                .network_settings
+                .ports
+                .iter()
+                .flatten()
+                .collect::<Vec<_>>();
+            if !published.is_empty() {
+                published.sort_by(|a, b| a.0.cmp(b.0));
+                key!(ui, "Ports:");
+                egui::CollapsingHeader::new("")
+                    .id_source("container_ports")
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        egui::Grid::new("ports_grid").show(ui, |ui| {
+                            for (port, bindings) in published {
+                                match bindings {
+                                    Some(bindings) if !bindings.is_empty() => {
+                                        for binding in bindings {
+                                            ports::port_binding(ui, port, binding);
+                                            ui.end_row();
+                                        }
+                                    }
+                                    _ => {
+                                        ui.label(format!("{} (not published)", port));
+                                        ui.end_row();
+                                    }
+                                }
+                            }
+                        });
+                    });
+                ui.end_row();
+            }
         });
     }
 
@@ -1178,71 +2382,6 @@ impl App {
         }
     }
 
-    fn container_logs(&mut self, ui: &mut egui::Ui) {
-        if let Some(logs) = &self.containers.logs_view_data.current_logs {
-            egui::CollapsingHeader::new("Logs")
-                .default_open(false)
-                .show(ui, |ui| {
-                    let color = if ui.visuals().dark_mode {
-                        *color::D_BG_000
-                    } else {
-                        *color::L_BG_4
-                    };
-
-                    let rope = ropey::Rope::from(logs.as_str());
-
-                    let len_lines = rope.len_lines();
-                    let max_page = len_lines / PAGE_SIZE;
-                    let cur_line = self.containers.logs_view_data.page * PAGE_SIZE;
-
-                    let mut slice = if self.containers.logs_view_data.follow {
-                        self.containers.logs_view_data.page = max_page;
-                        &logs[rope.line_to_byte(len_lines.saturating_sub(PAGE_SIZE))..]
-                    } else if cur_line + PAGE_SIZE > len_lines {
-                        let start_idx = rope.line_to_byte(
-                            cur_line.saturating_sub(cur_line + PAGE_SIZE - len_lines),
-                        );
-                        &logs[start_idx..]
-                    } else {
-                        &logs[rope.line_to_byte(cur_line)..rope.line_to_byte(cur_line + PAGE_SIZE)]
-                    };
-
-                    let mut page = self.containers.logs_view_data.page as f32;
-                    ui.horizontal(|ui| {
-                        if ui
-                            .button(icon::ARROW_LEFT)
-                            .on_hover_text("Decrease the page")
-                            .clicked()
-                            && self.containers.logs_view_data.page > 0
-                        {
-                            self.containers.logs_view_data.page -= 1;
-                        }
-                        ui.add(
-                            egui::DragValue::new(&mut page)
-                                .clamp_range(0..=max_page)
-                                .fixed_decimals(0)
-                                .speed(1.),
-                        );
-                        if ui
-                            .button(icon::ARROW_RIGHT)
-                            .on_hover_text("Increase the page")
-                            .clicked()
-                            && self.containers.logs_view_data.page < max_page
-                        {
-                            self.containers.logs_view_data.page += 1;
-                        }
-                        ui.checkbox(&mut self.containers.logs_view_data.follow, "Follow logs");
-                    });
-                    self.containers.logs_view_data.page = page as usize;
-
-                    Frame::none().fill(color).show(ui, |ui| {
-                        ui.allocate_space((ui.available_rect_before_wrap().width(), 0.).into());
-                        ui.text_edit_multiline(&mut slice);
-                    });
-                });
-        }
-    }
-
     fn display_rename_window(&mut self, ui: &mut egui::Ui) {
         if self.containers.rename_window.show {
             egui::Window::new("Rename a container").show(ui.ctx(), |ui| {
@@ -1273,6 +2412,333 @@ impl App {
         }
     }
 
+    fn display_update_window(&mut self, ui: &mut egui::Ui) {
+        if self.containers.update_window.show {
+            egui::Window::new("Update a container").show(ui.ctx(), |ui| {
+                let window = &mut self.containers.update_window;
+                Grid::new("update_window_grid").show(ui, |ui| {
+                    key!(ui, "CPUs:");
+                    ui.add(
+                        egui::DragValue::new(&mut window.cpus)
+                            .speed(0.1)
+                            .clamp_range(0.0..=f32::MAX),
+                    )
+                    .on_hover_text("0 means no limit");
+                    ui.end_row();
+                    key!(ui, "CPU shares:");
+                    ui.add(egui::DragValue::new(&mut window.cpu_shares).speed(1.));
+                    ui.end_row();
+                    key!(ui, "Memory:");
+                    ui.text_edit_singleline(&mut window.memory)
+                        .on_hover_text("examples between ``: `1G`, `100m`, `1000000`");
+                    ui.end_row();
+                    key!(ui, "Memory swap:");
+                    ui.text_edit_singleline(&mut window.memory_swap)
+                        .on_hover_text("memory limit plus swap, `-1` means unlimited swap");
+                    ui.end_row();
+                    key!(ui, "Restart policy:");
+                    egui::ComboBox::from_id_source("update_window_restart_policy")
+                        .selected_text(&window.restart_policy)
+                        .show_ui(ui, |ui| {
+                            for policy in RESTART_POLICIES {
+                                ui.selectable_value(
+                                    &mut window.restart_policy,
+                                    policy.to_string(),
+                                    *policy,
+                                );
+                            }
+                        });
+                    ui.end_row();
+                    if window.restart_policy == "on-failure" {
+                        key!(ui, "Maximum retries:");
+                        ui.add(egui::DragValue::new(&mut window.maximum_retry_count));
+                        ui.end_row();
+                    }
+                });
+
+                Grid::new("update_window_buttons").show(ui, |ui| {
+                    if ui.button("update").clicked() {
+                        match self.containers.update_window.as_opts() {
+                            Ok(opts) => {
+                                self.send_event_notify(EventRequest::Container(
+                                    ContainerEvent::Update {
+                                        id: self.containers.update_window.id.clone(),
+                                        opts,
+                                    },
+                                ));
+                                self.containers.update_window.toggle();
+                            }
+                            Err(e) => self.add_error(e),
+                        }
+                    }
+
+                    if ui.button("close").clicked() {
+                        self.containers.update_window.toggle();
+                    }
+                    ui.end_row();
+                });
+            });
+        }
+    }
+
+    fn display_commit_window(&mut self, ui: &mut egui::Ui) {
+        if self.containers.commit_window.show {
+            egui::Window::new("Commit a container").show(ui.ctx(), |ui| {
+                let window = &mut self.containers.commit_window;
+                Grid::new("commit_window_grid").show(ui, |ui| {
+                    key!(ui, "Repository:");
+                    ui.text_edit_singleline(&mut window.repo);
+                    ui.end_row();
+                    key!(ui, "Tag:");
+                    ui.text_edit_singleline(&mut window.tag);
+                    ui.end_row();
+                    key!(ui, "Message:");
+                    ui.text_edit_singleline(&mut window.comment);
+                    ui.end_row();
+                    key!(ui, "Author:");
+                    ui.text_edit_singleline(&mut window.author);
+                    ui.end_row();
+                    key!(ui, "Changes:");
+                    ui.add(
+                        egui::TextEdit::multiline(&mut window.changes)
+                            .hint_text("Dockerfile instructions, one per line"),
+                    );
+                    ui.end_row();
+                    key!(ui, "Pause:");
+                    ui.checkbox(&mut window.pause, "")
+                        .on_hover_text("pause the container while committing");
+                    ui.end_row();
+                });
+
+                Grid::new("commit_window_buttons").show(ui, |ui| {
+                    if ui.button("commit").clicked() {
+                        let window = &self.containers.commit_window;
+                        if window.repo.is_empty() && !window.tag.is_empty() {
+                            self.add_error("Repository is required when a tag is set");
+                        } else {
+                            self.send_event_notify(EventRequest::Container(
+                                ContainerEvent::Commit {
+                                    id: window.id.clone(),
+                                    opts: window.opts(),
+                                },
+                            ));
+                            self.containers.commit_window.toggle();
+                        }
+                    }
+
+                    if ui.button("close").clicked() {
+                        self.containers.commit_window.toggle();
+                    }
+                    ui.end_row();
+                });
+            });
+        }
+    }
+
+    fn container_files(&mut self, ui: &mut egui::Ui) {
+        let (id, running) = match &self.containers.current_container {
+            Some(container) => (container.id.clone(), is_running(container)),
+            None => return,
+        };
+        let data = &mut self.containers.files_view_data;
+        let mut errors = vec![];
+        let mut events = vec![];
+        let mut navigate = None;
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(data.parent().is_some(), egui::Button::new(icon::ARROW_UP))
+                .on_hover_text("go to the parent directory")
+                .clicked()
+            {
+                navigate = data.parent();
+            }
+            let input = ui.add(
+                egui::TextEdit::singleline(&mut data.path_input)
+                    .code_editor()
+                    .desired_width(300.),
+            );
+            if ui.button("go").clicked()
+                || (input.lost_focus() && ui.input().key_pressed(egui::Key::Enter))
+            {
+                navigate = Some(data.path_input.clone());
+            }
+            if ui
+                .button(icon::RESTART)
+                .on_hover_text("refresh the directory")
+                .clicked()
+            {
+                data.refresh();
+            }
+            if ui
+                .button("upload")
+                .on_hover_text("copy a file from the host to this directory")
+                .clicked()
+            {
+                match native_dialog::FileDialog::new().show_open_single_file() {
+                    Ok(Some(input_path)) => events.push(ContainerEvent::CopyTo {
+                        id: id.clone(),
+                        path: data.path.clone(),
+                        input_path,
+                    }),
+                    Ok(None) => {}
+                    Err(e) => errors.push(format!("failed to spawn a file dialog - {}", e)),
+                }
+            }
+            if data.loading {
+                ui.add(egui::Spinner::new());
+            }
+        });
+        ui.add_space(10.);
+
+        if data.entries.is_none() && !data.loading {
+            data.loading = true;
+            events.push(ContainerEvent::ListDir {
+                id: id.clone(),
+                path: data.path.clone(),
+                running,
+            });
+        }
+
+        if let Some(error) = &data.error {
+            ui.add(Label::new(RichText::new(error).color(egui::Color32::RED)));
+        }
+        if data.truncated {
+            ui.add(Label::new(
+                RichText::new(
+                    "The directory tree is too big to be read from a stopped container, only some \
+                     entries are listed.",
+                )
+                .color(egui::Color32::YELLOW),
+            ));
+        }
+
+        egui::ScrollArea::vertical()
+            .id_source("container_files_scroll")
+            .max_height(400.)
+            .show(ui, |ui| {
+                Grid::new("container_files").striped(true).show(ui, |ui| {
+                    for entry in data.entries.iter().flatten() {
+                        // links are opened, previewed and downloaded through their target
+                        let path = match &entry.link {
+                            Some(link) => resolve_link(&data.path, link),
+                            None => join_path(&data.path, &entry.name),
+                        };
+                        let kind = entry.target_kind();
+                        let is_dir = kind == FileKind::Directory;
+                        ui.label(match entry.kind {
+                            FileKind::Directory => icon::FOLDER,
+                            FileKind::Symlink => icon::LINK,
+                            _ => icon::FILE,
+                        });
+                        let name = match &entry.link {
+                            Some(link) => format!("{} -> {}", entry.name, link),
+                            None => entry.name.clone(),
+                        };
+                        if is_dir {
+                            if ui.link(RichText::new(name).monospace()).clicked() {
+                                navigate = Some(path.clone());
+                            }
+                        } else {
+                            ui.add(Label::new(RichText::new(name).monospace()));
+                        }
+                        if kind == FileKind::File {
+                            val!(ui, crate::conv_b(entry.size));
+                        } else {
+                            ui.scope(|_| {});
+                        }
+                        ui.horizontal(|ui| {
+                            if kind == FileKind::File
+                                && ui
+                                    .button(icon::SCROLL)
+                                    .on_hover_text("preview the file")
+                                    .clicked()
+                            {
+                                if entry.size > PREVIEW_LIMIT {
+                                    errors.push(format!(
+                                        "file `{}` is too big to preview, download it instead",
+                                        path
+                                    ));
+                                } else {
+                                    events.push(ContainerEvent::CopyFrom {
+                                        id: id.clone(),
+                                        path: path.clone(),
+                                        output_path: None,
+                                        archive: false,
+                                    });
+                                }
+                            }
+                            if ui
+                                .button(icon::SAVE)
+                                .on_hover_text(if kind == FileKind::File {
+                                    "download the file"
+                                } else {
+                                    "download as tar archive"
+                                })
+                                .clicked()
+                            {
+                                let mut dialog = native_dialog::FileDialog::new();
+                                let tar_name = format!("{}.tar", entry.name);
+                                dialog = if kind == FileKind::File {
+                                    dialog.set_filename(&entry.name)
+                                } else {
+                                    dialog
+                                        .add_filter("tar archive", &["tar"])
+                                        .set_filename(&tar_name)
+                                };
+                                match dialog.show_save_single_file() {
+                                    Ok(Some(output_path)) => {
+                                        events.push(ContainerEvent::CopyFrom {
+                                            id: id.clone(),
+                                            path: path.clone(),
+                                            output_path: Some(output_path),
+                                            archive: kind != FileKind::File,
+                                        })
+                                    }
+                                    Ok(None) => {}
+                                    Err(e) => errors
+                                        .push(format!("failed to spawn a file dialog - {}", e)),
+                                }
+                            }
+                        });
+                        ui.end_row();
+                    }
+                });
+            });
+
+        if let Some(preview) = &data.preview {
+            ui.add_space(10.);
+            ui.horizontal(|ui| {
+                ui.add(Label::new(RichText::new(&preview.path).strong()));
+                val!(ui, crate::conv_b(preview.size as u64));
+            });
+            match &preview.text {
+                Some(text) => {
+                    egui::ScrollArea::both()
+                        .id_source("container_files_preview")
+                        .max_height(400.)
+                        .show(ui, |ui| {
+                            ui.add(Label::new(RichText::new(text).monospace()).wrap(false));
+                        });
+                }
+                None => {
+                    ui.label("Binary file, download it to view the contents");
+                }
+            }
+        }
+
+        if let Some(path) = navigate {
+            data.set_path(path);
+            data.preview = None;
+        }
+        for event in events {
+            self.send_event_notify(EventRequest::Container(event));
+        }
+        for error in errors {
+            self.add_error(error);
+        }
+    }
+
     fn container_processes(&mut self, ui: &mut egui::Ui) {
         if let Some(top) = &self.containers.current_top {
             Grid::new("container_processes").show(ui, |ui| {
@@ -1291,6 +2757,222 @@ impl App {
         }
     }
 
+    fn container_attach(&mut self, ui: &mut egui::Ui) {
+        let id = match &self.containers.current_container {
+            Some(container) => container.id.clone(),
+            None => return,
+        };
+        let mut input = None;
+
+        ui.horizontal(|ui| {
+            if self.containers.attach_view_data.attached {
+                if ui
+                    .button("detach")
+                    .on_hover_text("detach from this container")
+                    .clicked()
+                {
+                    self.send_event_notify(EventRequest::Container(ContainerEvent::Detach));
+                }
+                if ui
+                    .button("^C")
+                    .on_hover_text("send an interrupt to the container")
+                    .clicked()
+                {
+                    input = Some(vec![0x03]);
+                }
+                if ui
+                    .button("^D")
+                    .on_hover_text("send an end of transmission to the container")
+                    .clicked()
+                {
+                    input = Some(vec![0x04]);
+                }
+            } else if ui
+                .button("attach")
+                .on_hover_text("attach to the stdin, stdout and stderr of this container")
+                .clicked()
+            {
+                self.containers.attach_view_data.output.clear();
+                self.containers.attach_view_data.attached = true;
+                self.send_event_notify(EventRequest::Container(ContainerEvent::Attach { id }));
+            }
+            if ui
+                .button("clear")
+                .on_hover_text("clear the output")
+                .clicked()
+            {
+                self.containers.attach_view_data.output.clear();
+            }
+        });
+        ui.add_space(5.);
+
+        terminal_output(
+            ui,
+            "container_attach_output",
+            &self.containers.attach_view_data.output,
+        );
+
+        if self.containers.attach_view_data.attached {
+            ui.add_space(5.);
+            let rsp = ui.add(
+                egui::TextEdit::singleline(&mut self.containers.attach_view_data.input)
+                    .code_editor()
+                    .desired_width(f32::INFINITY)
+                    .hint_text("input, press enter to send"),
+            );
+            if rsp.lost_focus() && ui.input().key_pressed(egui::Key::Enter) {
+                let mut line = std::mem::take(&mut self.containers.attach_view_data.input);
+                line.push('\n');
+                input = Some(line.into_bytes());
+                rsp.request_focus();
+            }
+            ui.ctx().request_repaint();
+        }
+
+        if let Some(input) = input {
+            self.send_event_notify(EventRequest::Container(ContainerEvent::AttachInput {
+                input,
+            }));
+        }
+    }
+
+    fn container_exec(&mut self, ui: &mut egui::Ui) {
+        let id = match &self.containers.current_container {
+            Some(container) => container.id.clone(),
+            None => return,
+        };
+        let mut run = false;
+
+        ui.label(
+            "Runs non-interactive commands that don't read any input, use the attach view for \
+             interactive programs like a shell.",
+        );
+        ui.add_space(5.);
+
+        egui::CollapsingHeader::new("Options")
+            .default_open(false)
+            .show(ui, |ui| {
+                Grid::new("container_exec_options").show(ui, |ui| {
+                    key!(ui, "User:");
+                    ui.text_edit_singleline(&mut self.containers.exec_view_data.user);
+                    ui.end_row();
+                    key!(ui, "Working directory:");
+                    ui.text_edit_singleline(&mut self.containers.exec_view_data.working_dir);
+                    ui.end_row();
+                    ui.add(
+                        ui::EditableList::builder_key_val(&mut self.containers.exec_view_data.env)
+                            .heading("Environment:")
+                            .id_source("container_exec_env")
+                            .add_hover_text("Add a key value pair")
+                            .key_heading("Key:")
+                            .val_heading("Val:")
+                            .build(),
+                    );
+                    ui.end_row();
+                });
+            });
+        ui.add_space(5.);
+
+        ui.horizontal(|ui| {
+            if self.containers.exec_view_data.running {
+                if ui
+                    .button(icon::STOP)
+                    .on_hover_text(
+                        "stop following the output of the command, the command can't be killed \
+                         and keeps running in the container",
+                    )
+                    .clicked()
+                {
+                    self.containers
+                        .exec_view_data
+                        .output
+                        .push_str("[stopped following the output, the command keeps running]\n");
+                    self.send_event_notify(EventRequest::Container(ContainerEvent::ExecDetach));
+                }
+            } else if ui
+                .button(icon::PLAY)
+                .on_hover_text("run the command inside of this container")
+                .clicked()
+            {
+                run = true;
+            }
+            if ui
+                .button("clear")
+                .on_hover_text("clear the output")
+                .clicked()
+            {
+                self.containers.exec_view_data.output.clear();
+            }
+        });
+        ui.add_space(5.);
+
+        terminal_output(
+            ui,
+            "container_exec_output",
+            &self.containers.exec_view_data.output,
+        );
+        ui.add_space(5.);
+
+        let rsp = ui
+            .add(
+                egui::TextEdit::singleline(&mut self.containers.exec_view_data.cmd)
+                    .code_editor()
+                    .desired_width(f32::INFINITY)
+                    .hint_text("command, press enter to run"),
+            )
+            .on_hover_text("arguments are split like in a shell");
+        if rsp.has_focus() {
+            if ui.input().key_pressed(egui::Key::ArrowUp) {
+                self.containers.exec_view_data.history_prev();
+            } else if ui.input().key_pressed(egui::Key::ArrowDown) {
+                self.containers.exec_view_data.history_next();
+            }
+        }
+        if rsp.lost_focus() && ui.input().key_pressed(egui::Key::Enter) {
+            run = true;
+            rsp.request_focus();
+        }
+        if self.containers.exec_view_data.running {
+            ui.ctx().request_repaint();
+        }
+
+        if !run || self.containers.exec_view_data.running {
+            return;
+        }
+        let data = &mut self.containers.exec_view_data;
+        let line = std::mem::take(&mut data.cmd);
+        let cmd = match crate::split_shell_words(&line) {
+            Ok(cmd) if !cmd.is_empty() => cmd,
+            Ok(_) => return,
+            Err(e) => {
+                data.cmd = line;
+                self.add_error(e);
+                return;
+            }
+        };
+        if data.history.last() != Some(&line) {
+            data.history.push(line.clone());
+        }
+        data.history_pos = None;
+        data.output.push_str(&format!("$ {}\n", line));
+        data.running = true;
+
+        let env = data
+            .env
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect::<Vec<_>>();
+        let user = Some(data.user.clone()).filter(|u| !u.is_empty());
+        let workdir = Some(data.working_dir.clone()).filter(|w| !w.is_empty());
+        self.send_event_notify(EventRequest::Container(ContainerEvent::Exec {
+            id,
+            cmd,
+            env,
+            user,
+            workdir,
+        }));
+    }
+
     fn container_changes(&mut self, ui: &mut egui::Ui) {
         if let Some(changes) = &self.containers.changes_view_data.current_changes {
             ui.allocate_space((f32::INFINITY, 0.).into());
@@ -1347,3 +3029,189 @@ impl App {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Output of `docker inspect` for a container started with
+    /// `docker run -d --name web -p 8080:80 -e MODE=prod nginx:1.21 nginx -g "daemon off;"`
+    const DEFAULT_BRIDGE_CONTAINER: &str = r#"{
+        "Id": "5d3f1b0e8a6c4f2b9e7d1a3c5b7e9f0a2c4e6b8d0f1a3c5e7b9d1f3a5c7e9b1d",
+        "Created": "2022-05-01T10:00:00.000000000Z",
+        "Path": "/docker-entrypoint.sh",
+        "Args": ["nginx", "-g", "daemon off;"],
+        "State": {
+            "Status": "running",
+            "Running": true,
+            "Paused": false,
+            "Restarting": false,
+            "OOMKilled": false,
+            "Dead": false,
+            "Pid": 4242,
+            "ExitCode": 0,
+            "Error": "",
+            "StartedAt": "2022-05-01T10:00:01.000000000Z",
+            "FinishedAt": "0001-01-01T00:00:00Z"
+        },
+        "Image": "sha256:fa5269854a5e615e51a72b17ad3fd1e01268f278a6684c8ed3c5f0cdce3f230b",
+        "ResolvConfPath": "/var/lib/docker/containers/5d3f/resolv.conf",
+        "HostnamePath": "/var/lib/docker/containers/5d3f/hostname",
+        "HostsPath": "/var/lib/docker/containers/5d3f/hosts",
+        "LogPath": "/var/lib/docker/containers/5d3f/5d3f-json.log",
+        "Name": "/web",
+        "RestartCount": 0,
+        "Driver": "overlay2",
+        "Platform": "linux",
+        "MountLabel": "",
+        "ProcessLabel": "",
+        "AppArmorProfile": "docker-default",
+        "ExecIDs": null,
+        "HostConfig": {
+            "Binds": null,
+            "ContainerIDFile": "",
+            "LogConfig": {"Type": "json-file", "Config": {}},
+            "NetworkMode": "default",
+            "PortBindings": {"80/tcp": [{"HostIp": "", "HostPort": "8080"}]},
+            "RestartPolicy": {"Name": "no", "MaximumRetryCount": 0},
+            "AutoRemove": false,
+            "VolumeDriver": "",
+            "VolumesFrom": null,
+            "CapAdd": null,
+            "CapDrop": null,
+            "CgroupnsMode": "private",
+            "Dns": [],
+            "DnsOptions": [],
+            "DnsSearch": [],
+            "ExtraHosts": null,
+            "GroupAdd": null,
+            "IpcMode": "private",
+            "Cgroup": "",
+            "Links": null,
+            "OomScoreAdj": 0,
+            "PidMode": "",
+            "Privileged": false,
+            "PublishAllPorts": false,
+            "ReadonlyRootfs": false,
+            "SecurityOpt": null,
+            "UTSMode": "",
+            "UsernsMode": "",
+            "ShmSize": 67108864,
+            "Runtime": "runc",
+            "ConsoleSize": [0, 0],
+            "Isolation": "",
+            "CpuShares": 0,
+            "Memory": 0,
+            "NanoCpus": 0,
+            "CgroupParent": "",
+            "BlkioWeight": 0,
+            "BlkioWeightDevice": [],
+            "BlkioDeviceReadBps": null,
+            "BlkioDeviceWriteBps": null,
+            "BlkioDeviceReadIOps": null,
+            "BlkioDeviceWriteIOps": null,
+            "CpuPeriod": 0,
+            "CpuQuota": 0,
+            "CpuRealtimePeriod": 0,
+            "CpuRealtimeRuntime": 0,
+            "CpusetCpus": "",
+            "CpusetMems": "",
+            "Devices": [],
+            "DeviceCgroupRules": null,
+            "DeviceRequests": null,
+            "KernelMemory": 0,
+            "KernelMemoryTCP": 0,
+            "MemoryReservation": 0,
+            "MemorySwap": 0,
+            "MemorySwappiness": null,
+            "OomKillDisable": false,
+            "PidsLimit": null,
+            "Ulimits": null,
+            "CpuCount": 0,
+            "CpuPercent": 0,
+            "IOMaximumIOps": 0,
+            "IOMaximumBandwidth": 0,
+            "MaskedPaths": ["/proc/asound"],
+            "ReadonlyPaths": ["/proc/bus"]
+        },
+        "GraphDriver": {"Data": null, "Name": "overlay2"},
+        "Mounts": [],
+        "Config": {
+            "Hostname": "5d3f1b0e8a6c",
+            "Domainname": "",
+            "User": "",
+            "AttachStdin": false,
+            "AttachStdout": false,
+            "AttachStderr": false,
+            "ExposedPorts": {"80/tcp": {}},
+            "Tty": false,
+            "OpenStdin": false,
+            "StdinOnce": false,
+            "Env": ["MODE=prod", "PATH=/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin"],
+            "Cmd": ["nginx", "-g", "daemon off;"],
+            "Image": "nginx:1.21",
+            "Volumes": null,
+            "WorkingDir": "",
+            "Entrypoint": ["/docker-entrypoint.sh"],
+            "OnBuild": null,
+            "Labels": {"maintainer": "NGINX Docker Maintainers"},
+            "StopSignal": "SIGQUIT"
+        },
+        "NetworkSettings": {
+            "Bridge": "",
+            "SandboxID": "8f6e0c1d2b3a",
+            "HairpinMode": false,
+            "LinkLocalIPv6Address": "",
+            "LinkLocalIPv6PrefixLen": 0,
+            "Ports": {"80/tcp": [{"HostIp": "0.0.0.0", "HostPort": "8080"}]},
+            "SandboxKey": "/var/run/docker/netns/8f6e0c1d2b3a",
+            "SecondaryIPAddresses": null,
+            "SecondaryIPv6Addresses": null,
+            "EndpointID": "3c2b1a0f9e8d",
+            "Gateway": "172.17.0.1",
+            "GlobalIPv6Address": "",
+            "GlobalIPv6PrefixLen": 0,
+            "IPAddress": "172.17.0.2",
+            "IPPrefixLen": 16,
+            "IPv6Gateway": "",
+            "MacAddress": "02:42:ac:11:00:02",
+            "Networks": {
+                "bridge": {
+                    "IPAMConfig": null,
+                    "Links": null,
+                    "Aliases": null,
+                    "NetworkID": "b1e2d3c4a5f6",
+                    "EndpointID": "3c2b1a0f9e8d",
+                    "Gateway": "172.17.0.1",
+                    "IPAddress": "172.17.0.2",
+                    "IPPrefixLen": 16,
+                    "IPv6Gateway": "",
+                    "GlobalIPv6Address": "",
+                    "GlobalIPv6PrefixLen": 0,
+                    "MacAddress": "02:42:ac:11:00:02",
+                    "DriverOpts": null
+                }
+            }
+        }
+    }"#;
+
+    #[test]
+    fn from_details_of_default_bridge_container() {
+        let details: ContainerDetails = serde_json::from_str(DEFAULT_BRIDGE_CONTAINER).unwrap();
+        let data = ContainerCreateData::from_details(&details);
+
+        assert_eq!(data.network_mode, "bridge");
+        // the bridge network is attached through the network mode and must not be connected again
+        assert!(data.networks.is_empty());
+        assert_eq!(data.image, "nginx:1.21");
+        assert_eq!(data.name, "web");
+        assert_eq!(data.command, "nginx -g 'daemon off;'");
+        assert_eq!(data.entrypoint, "/docker-entrypoint.sh");
+        assert_eq!(data.ports, [("80/tcp".to_string(), "8080".to_string())]);
+        assert_eq!(data.restart_policy, "no");
+        assert_eq!(data.stop_signal, "SIGQUIT");
+        assert!(data.hostname.is_empty());
+        assert!(data.env.contains(&("MODE".to_string(), "prod".to_string())));
+        assert_eq!(data.original, Some((details.id.clone(), "web".to_string())));
+    }
+}
@@ -0,0 +1,243 @@
+use anyhow::{Context, Error, Result};
+use docker_api::api::RegistryAuth;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Name of the file that stores the registry credentials next to the settings file. It is kept
+/// separate from the settings so that secrets never end up in `dockeye.yml`.
+pub const FILENAME: &str = "dockeye-credentials.yml";
+
+/// Username that credential helpers return when the secret is an identity token.
+const TOKEN_USERNAME: &str = "<token>";
+
+/// Returns the path of the credentials file that belongs to the settings file at `settings_path`.
+pub fn path(settings_path: impl AsRef<Path>) -> PathBuf {
+    settings_path.as_ref().with_file_name(FILENAME)
+}
+
+fn docker_config_path() -> Option<PathBuf> {
+    match std::env::var_os("DOCKER_CONFIG") {
+        Some(dir) => Some(PathBuf::from(dir).join("config.json")),
+        None => dirs::home_dir().map(|home| home.join(".docker").join("config.json")),
+    }
+}
+
+#[derive(Clone, Default, Deserialize, Serialize)]
+pub struct Credential {
+    pub name: String,
+    pub server: String,
+    pub username: String,
+    pub secret: String,
+}
+
+impl std::fmt::Debug for Credential {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Credential")
+            .field("name", &self.name)
+            .field("server", &self.server)
+            .field("username", &self.username)
+            .field("secret", &"<redacted>")
+            .finish()
+    }
+}
+
+impl Credential {
+    /// The label displayed when choosing a credential.
+    pub fn label(&self) -> String {
+        if self.server.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{} ({})", self.name, self.server)
+        }
+    }
+
+    pub fn auth(&self) -> RegistryAuth {
+        if self.username == TOKEN_USERNAME {
+            return RegistryAuth::token(&self.secret);
+        }
+        let mut auth = RegistryAuth::builder().username(&self.username);
+        if !self.secret.is_empty() {
+            auth = auth.password(&self.secret);
+        }
+        if !self.server.is_empty() {
+            auth = auth.server_address(&self.server);
+        }
+        auth.build()
+    }
+}
+
+/// Loads saved credentials from the file located at `path`. A missing file means that no
+/// credentials were saved yet.
+pub fn load(path: impl AsRef<Path>) -> Result<Vec<Credential>> {
+    let path = path.as_ref();
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let data = fs::read(path).context("failed to read credentials file")?;
+    serde_yaml::from_slice(&data).context("failed to deserialize credentials")
+}
+
+/// Saves the credentials to the file located at `path`. On unix systems the file is only
+/// readable and writable by the current user.
+pub fn save(credentials: &[Credential], path: impl AsRef<Path>) -> Result<()> {
+    let path = path.as_ref();
+    let data = serde_yaml::to_vec(credentials).context("failed to serialize credentials")?;
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        if path.exists() {
+            fs::set_permissions(path, fs::Permissions::from_mode(0o600))
+                .context("failed to restrict permissions of credentials file")?;
+        }
+    }
+    options
+        .open(path)
+        .and_then(|mut file| file.write_all(&data))
+        .context("failed to write credentials to file")
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DockerConfig {
+    #[serde(default)]
+    auths: HashMap<String, DockerAuth>,
+    creds_store: Option<String>,
+    #[serde(default)]
+    cred_helpers: HashMap<String, String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct DockerAuth {
+    auth: Option<String>,
+    identitytoken: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct HelperCredential {
+    username: String,
+    secret: String,
+}
+
+/// Credentials read from the docker CLI configuration along with errors of malformed entries and
+/// credential helpers that failed without stopping the rest of the credentials from being read.
+#[derive(Debug, Default)]
+pub struct DockerCredentials {
+    pub credentials: Vec<Credential>,
+    pub errors: Vec<Error>,
+}
+
+/// Reads the credentials from the docker CLI configuration file, asking credential helpers for the
+/// secrets of registries that don't store them in the file. Credential helpers can take a while
+/// to respond so this should not be called from the GUI thread.
+pub fn docker_config() -> Result<DockerCredentials> {
+    let path = docker_config_path().context("failed to find docker configuration directory")?;
+    if !path.exists() {
+        return Ok(DockerCredentials::default());
+    }
+    let data = fs::read(&path).context("failed to read docker configuration file")?;
+    let config: DockerConfig =
+        serde_json::from_slice(&data).context("failed to deserialize docker configuration")?;
+
+    let mut credentials = vec![];
+    let mut errors = vec![];
+    for (server, auth) in &config.auths {
+        let credential = if let Some(token) = &auth.identitytoken {
+            Credential {
+                name: server.clone(),
+                server: server.clone(),
+                username: TOKEN_USERNAME.to_string(),
+                secret: token.clone(),
+            }
+        } else if let Some(auth) = auth.auth.as_deref().filter(|auth| !auth.is_empty()) {
+            let decoded = match base64::decode(auth.trim())
+                .map_err(Error::from)
+                .and_then(|decoded| String::from_utf8(decoded).map_err(Error::from))
+                .with_context(|| format!("invalid auth of registry `{}`", server))
+            {
+                Ok(decoded) => decoded,
+                Err(e) => {
+                    errors.push(e);
+                    continue;
+                }
+            };
+            let (username, secret) = decoded.split_once(':').unwrap_or((&decoded, ""));
+            Credential {
+                name: server.clone(),
+                server: server.clone(),
+                username: username.to_string(),
+                secret: secret.to_string(),
+            }
+        } else if let Some(helper) = config
+            .cred_helpers
+            .get(server)
+            .or(config.creds_store.as_ref())
+        {
+            match helper_credential(helper, server) {
+                Ok(credential) => credential,
+                Err(e) => {
+                    errors.push(e);
+                    continue;
+                }
+            }
+        } else {
+            continue;
+        };
+        credentials.push(credential);
+    }
+    for (server, helper) in &config.cred_helpers {
+        if config.auths.contains_key(server) {
+            continue;
+        }
+        match helper_credential(helper, server) {
+            Ok(credential) => credentials.push(credential),
+            Err(e) => errors.push(e),
+        }
+    }
+    credentials.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(DockerCredentials {
+        credentials,
+        errors,
+    })
+}
+
+/// Runs `docker-credential-<helper> get` to retrieve the credential of `server`.
+fn helper_credential(helper: &str, server: &str) -> Result<Credential> {
+    let program = format!("docker-credential-{}", helper);
+    let mut child = Command::new(&program)
+        .arg("get")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("failed to run credential helper `{}`", program))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(server.as_bytes())
+            .with_context(|| format!("failed to write to credential helper `{}`", program))?;
+    }
+    let output = child
+        .wait_with_output()
+        .with_context(|| format!("credential helper `{}` failed", program))?;
+    if !output.status.success() {
+        return Err(Error::msg(format!(
+            "credential helper `{}` found no credentials for `{}`",
+            program, server
+        )));
+    }
+    let credential: HelperCredential = serde_json::from_slice(&output.stdout)
+        .with_context(|| format!("invalid output of credential helper `{}`", program))?;
+    Ok(Credential {
+        name: server.to_string(),
+        server: server.to_string(),
+        username: credential.username,
+        secret: credential.secret,
+    })
+}
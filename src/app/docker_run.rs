@@ -0,0 +1,486 @@
+//! Conversion between [`ContainerCreateData`](ContainerCreateData) and `docker run` command lines.
+
+use crate::app::containers::ContainerCreateData;
+
+use anyhow::{Error, Result};
+
+impl ContainerCreateData {
+    /// Returns a `docker run` command line that creates an equivalent container.
+    pub fn to_docker_run(&self) -> Result<String> {
+        let mut args = vec!["docker".to_string(), "run".to_string()];
+        let mut arg = |flag: &str, value: &str| {
+            if !value.is_empty() {
+                args.push(flag.to_string());
+                args.push(value.to_string());
+            }
+        };
+
+        arg("--name", &self.name);
+        arg("--hostname", &self.hostname);
+        arg("--user", &self.user);
+        arg("--workdir", &self.working_dir);
+        for (key, val) in &self.env {
+            arg("--env", &format!("{}={}", key, val));
+        }
+        for (key, val) in &self.labels {
+            arg("--label", &format!("{}={}", key, val));
+        }
+        for (port, host_port) in &self.ports {
+            if host_port.is_empty() {
+                arg("--publish", port);
+            } else {
+                arg("--publish", &format!("{}:{}", host_port, port));
+            }
+        }
+        for port in &self.exposed {
+            arg("--expose", port);
+        }
+        for volume in &self.volumes {
+            arg("--volume", volume);
+        }
+        arg("--network", &self.network_mode);
+        for network in &self.networks {
+            arg("--network", network);
+        }
+        for link in &self.links {
+            arg("--link", link);
+        }
+        for host in &self.extra_hosts {
+            arg("--add-host", host);
+        }
+        for dns in &self.dns {
+            arg("--dns", dns);
+        }
+        for device in &self.devices {
+            arg("--device", device);
+        }
+        for capability in &self.capabilities {
+            arg("--cap-add", capability);
+        }
+        for option in &self.sec_ops {
+            arg("--security-opt", option);
+        }
+        match self.restart_policy.as_str() {
+            "no" => {}
+            "on-failure" if self.maximum_retry_count > 0 => arg(
+                "--restart",
+                &format!("on-failure:{}", self.maximum_retry_count),
+            ),
+            policy => arg("--restart", policy),
+        }
+        if self.cpus > 0. {
+            arg("--cpus", &self.cpus.to_string());
+        }
+        if self.cpu_shares > 0. {
+            arg("--cpu-shares", &(self.cpu_shares as u32).to_string());
+        }
+        arg("--memory", &self.memory);
+        arg("--memory-swap", &self.memory_swap);
+        arg("--log-driver", &self.log_driver);
+        arg("--userns", &self.userns_mode);
+        arg("--stop-signal", &self.stop_signal);
+        arg("--stop-timeout", &self.stop_timeout);
+
+        // the entrypoint flag only accepts the executable, the rest is prepended to the command
+        let mut command = crate::split_shell_words(&self.command)?;
+        let entrypoint = crate::split_shell_words(&self.entrypoint)?;
+        if let Some((executable, rest)) = entrypoint.split_first() {
+            arg("--entrypoint", executable);
+            command.splice(0..0, rest.iter().cloned());
+        }
+
+        if !self.stdout && !self.stderr {
+            args.push("--detach".to_string());
+        }
+        if self.stdin {
+            args.push("--interactive".to_string());
+        }
+        if self.tty {
+            args.push("--tty".to_string());
+        }
+        if self.privileged {
+            args.push("--privileged".to_string());
+        }
+        if self.autoremove {
+            args.push("--rm".to_string());
+        }
+        if self.publish_all_ports {
+            args.push("--publish-all".to_string());
+        }
+
+        args.push(self.image.clone());
+        args.extend(command);
+
+        Ok(crate::join_shell_words(&args))
+    }
+
+    /// Parses a `docker run` command line. The leading `docker run` or `docker container run` is
+    /// optional.
+    pub fn from_docker_run(cmd: &str) -> Result<Self> {
+        let words = crate::split_shell_words(cmd)?;
+        let mut words = words.into_iter().peekable();
+        if words.peek().map(|w| w == "docker").unwrap_or_default() {
+            words.next();
+            if words.peek().map(|w| w == "container").unwrap_or_default() {
+                words.next();
+            }
+            if words.next().as_deref() != Some("run") {
+                return Err(Error::msg("expected a `docker run` command"));
+            }
+        }
+
+        let mut data = ContainerCreateData {
+            stdout: true,
+            stderr: true,
+            ..Default::default()
+        };
+        let mut network_mode = None;
+        let mut attach = false;
+
+        while let Some(word) = words.next() {
+            if word == "--" {
+                break;
+            }
+            if !word.starts_with('-') || word == "-" {
+                data.image = word;
+                break;
+            }
+
+            // flags are either `--flag value`, `--flag=value`, `-f value` or grouped like `-it`
+            let (flags, mut inline_value) = match word.strip_prefix("--") {
+                Some(flag) => match flag.split_once('=') {
+                    Some((flag, value)) => (vec![flag.to_string()], Some(value.to_string())),
+                    None => (vec![flag.to_string()], None),
+                },
+                None => {
+                    let short = &word[1..];
+                    let mut flags = vec![];
+                    let mut value = None;
+                    for (i, c) in short.char_indices() {
+                        // `-t=false` sets the value of the boolean flag before it
+                        if c == '=' {
+                            value = Some(short[i + 1..].to_string());
+                            break;
+                        }
+                        flags.push(c.to_string());
+                        if takes_value(&c.to_string()) {
+                            let rest = &short[i + c.len_utf8()..];
+                            let rest = rest.strip_prefix('=').unwrap_or(rest);
+                            if !rest.is_empty() {
+                                value = Some(rest.to_string());
+                            }
+                            break;
+                        }
+                    }
+                    (flags, value)
+                }
+            };
+
+            let count = flags.len();
+            for (i, flag) in flags.into_iter().enumerate() {
+                // an inline value always belongs to the last flag of a group
+                let inline_value = if i + 1 == count {
+                    inline_value.take()
+                } else {
+                    None
+                };
+                // boolean flags are enabled unless set like `--rm=false`
+                let mut enabled = true;
+                let value = if takes_value(&flag) {
+                    match inline_value.or_else(|| words.next()) {
+                        Some(value) => value,
+                        None => return Err(Error::msg(format!("missing value of `{}`", flag))),
+                    }
+                } else {
+                    if let Some(value) = inline_value {
+                        enabled = parse_bool(&value).ok_or_else(|| {
+                            Error::msg(format!("invalid boolean value of `{}`", word))
+                        })?;
+                    }
+                    String::new()
+                };
+
+                match flag.as_str() {
+                    "d" | "detach" => {
+                        data.stdout = !enabled;
+                        data.stderr = !enabled;
+                    }
+                    "i" | "interactive" => data.stdin = enabled,
+                    "t" | "tty" => data.tty = enabled,
+                    "privileged" => data.privileged = enabled,
+                    "rm" => data.autoremove = enabled,
+                    "P" | "publish-all" => data.publish_all_ports = enabled,
+                    "a" | "attach" => {
+                        // only the listed streams are attached
+                        if !attach {
+                            attach = true;
+                            data.stdout = false;
+                            data.stderr = false;
+                        }
+                        match value.to_lowercase().as_str() {
+                            "stdin" => data.stdin = true,
+                            "stdout" => data.stdout = true,
+                            "stderr" => data.stderr = true,
+                            _ => return Err(Error::msg(format!("invalid stream `{}`", value))),
+                        }
+                    }
+                    "name" => data.name = value,
+                    "h" | "hostname" => data.hostname = value,
+                    "u" | "user" => data.user = value,
+                    "w" | "workdir" => data.working_dir = value,
+                    "e" | "env" => data.env.push(match value.split_once('=') {
+                        Some((key, val)) => (key.to_string(), val.to_string()),
+                        // the value is taken from the environment of the client
+                        None => {
+                            let val = std::env::var(&value).unwrap_or_default();
+                            (value, val)
+                        }
+                    }),
+                    "l" | "label" => data.labels.push(match value.split_once('=') {
+                        Some((key, val)) => (key.to_string(), val.to_string()),
+                        None => (value, String::new()),
+                    }),
+                    "p" | "publish" => data.ports.push(parse_publish(&value)?),
+                    "expose" => data.exposed.push(value),
+                    "v" | "volume" => data.volumes.push(value),
+                    "network" | "net" => {
+                        if network_mode.is_none() {
+                            network_mode = Some(value);
+                        } else {
+                            data.networks.push(value);
+                        }
+                    }
+                    "link" => data.links.push(value),
+                    "add-host" => data.extra_hosts.push(value),
+                    "dns" => data.dns.push(value),
+                    "device" => data.devices.push(value),
+                    "cap-add" => data.capabilities.push(value),
+                    "security-opt" => data.sec_ops.push(value),
+                    "restart" => match value.split_once(':') {
+                        Some(("on-failure", count)) => {
+                            data.restart_policy = "on-failure".to_string();
+                            data.maximum_retry_count = count.parse().map_err(|_| {
+                                Error::msg(format!("invalid restart policy `{}`", value))
+                            })?;
+                        }
+                        _ => data.restart_policy = value,
+                    },
+                    "cpus" => {
+                        data.cpus = value.parse().map_err(|_| {
+                            Error::msg(format!("invalid number of CPUs `{}`", value))
+                        })?
+                    }
+                    "c" | "cpu-shares" => {
+                        data.cpu_shares = value
+                            .parse()
+                            .map_err(|_| Error::msg(format!("invalid CPU shares `{}`", value)))?
+                    }
+                    "m" | "memory" => data.memory = value,
+                    "memory-swap" => data.memory_swap = value,
+                    "log-driver" => data.log_driver = value,
+                    "userns" => data.userns_mode = value,
+                    "stop-signal" => data.stop_signal = value,
+                    "stop-timeout" => data.stop_timeout = value,
+                    "entrypoint" => data.entrypoint = crate::join_shell_words(&[value]),
+                    _ => return Err(Error::msg(format!("unsupported option `{}`", word))),
+                }
+            }
+            if inline_value.is_some() {
+                return Err(Error::msg(format!("unsupported option `{}`", word)));
+            }
+        }
+
+        if data.image.is_empty() {
+            data.image = words
+                .next()
+                .ok_or_else(|| Error::msg("missing image of the container"))?;
+        }
+        data.command = crate::join_shell_words(&words.collect::<Vec<_>>());
+        data.network_mode = network_mode.unwrap_or_default();
+
+        Ok(data)
+    }
+}
+
+/// Whether a flag of `docker run` is followed by a value.
+fn takes_value(flag: &str) -> bool {
+    !matches!(
+        flag,
+        "d" | "detach"
+            | "i"
+            | "interactive"
+            | "t"
+            | "tty"
+            | "privileged"
+            | "rm"
+            | "P"
+            | "publish-all"
+    )
+}
+
+/// Parses a boolean value the way `docker` does, like `true`, `False` or `0`.
+fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "1" | "t" | "T" | "true" | "TRUE" | "True" => Some(true),
+        "0" | "f" | "F" | "false" | "FALSE" | "False" => Some(false),
+        _ => None,
+    }
+}
+
+/// Parses a port like `8080:80/tcp` or `80` into a container port and a host port.
+fn parse_publish(publish: &str) -> Result<(String, String)> {
+    let parts: Vec<_> = publish.split(':').collect();
+    match parts.as_slice() {
+        [port] => Ok((port.to_string(), String::new())),
+        [host_port, port] => Ok((port.to_string(), host_port.to_string())),
+        _ => Err(Error::msg(format!(
+            "unsupported port `{}`, binding to a host IP is not supported",
+            publish
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair(key: &str, val: &str) -> (String, String) {
+        (key.to_string(), val.to_string())
+    }
+
+    #[test]
+    fn parses_grouped_and_inline_flags() {
+        let data = ContainerCreateData::from_docker_run(
+            "docker run -it --rm --name=web -e=MODE=prod -p8080:80 --network host nginx",
+        )
+        .unwrap();
+        assert!(data.stdin);
+        assert!(data.tty);
+        assert!(data.autoremove);
+        assert!(data.stdout && data.stderr);
+        assert_eq!(data.name, "web");
+        assert_eq!(data.env, [pair("MODE", "prod")]);
+        assert_eq!(data.ports, [pair("80", "8080")]);
+        assert_eq!(data.network_mode, "host");
+        assert_eq!(data.image, "nginx");
+        assert!(data.command.is_empty());
+    }
+
+    #[test]
+    fn parses_inline_boolean_values() {
+        let data =
+            ContainerCreateData::from_docker_run("docker run --rm=false -it=false -d=true busybox")
+                .unwrap();
+        assert!(!data.autoremove);
+        assert!(data.stdin);
+        assert!(!data.tty);
+        assert!(!data.stdout && !data.stderr);
+
+        let data =
+            ContainerCreateData::from_docker_run("docker run --privileged=1 busybox").unwrap();
+        assert!(data.privileged);
+
+        assert!(ContainerCreateData::from_docker_run("docker run --rm=maybe busybox").is_err());
+        assert!(ContainerCreateData::from_docker_run("docker run -=true busybox").is_err());
+    }
+
+    #[test]
+    fn parses_published_and_exposed_ports() {
+        let data = ContainerCreateData::from_docker_run(
+            "docker run -p 80 --publish 8443:443/tcp --expose 9000/udp nginx",
+        )
+        .unwrap();
+        assert_eq!(data.ports, [pair("80", ""), pair("443/tcp", "8443")]);
+        assert_eq!(data.exposed, ["9000/udp"]);
+    }
+
+    #[test]
+    fn converts_ports_to_docker_run() {
+        let data = ContainerCreateData {
+            image: "nginx".to_string(),
+            ports: vec![pair("80/tcp", ""), pair("443/tcp", "8443")],
+            exposed: vec!["9000/udp".to_string()],
+            stdout: true,
+            stderr: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            data.to_docker_run().unwrap(),
+            "docker run --publish 80/tcp --publish 8443:443/tcp --expose 9000/udp nginx"
+        );
+    }
+
+    #[test]
+    fn splits_entrypoint_into_executable_and_command() {
+        let data = ContainerCreateData {
+            image: "alpine".to_string(),
+            entrypoint: "sh -c".to_string(),
+            command: "'echo hello; sleep 1'".to_string(),
+            ..Default::default()
+        };
+        let cmd = data.to_docker_run().unwrap();
+        assert_eq!(
+            cmd,
+            "docker run --entrypoint sh --detach alpine -c 'echo hello; sleep 1'"
+        );
+
+        let parsed = ContainerCreateData::from_docker_run(&cmd).unwrap();
+        assert_eq!(parsed.entrypoint, "sh");
+        assert_eq!(
+            crate::split_shell_words(&parsed.command).unwrap(),
+            ["-c", "echo hello; sleep 1"]
+        );
+    }
+
+    #[test]
+    fn round_trips_through_docker_run() {
+        let data = ContainerCreateData {
+            image: "registry.local:5000/app:1.0".to_string(),
+            command: r#"serve --config '/etc/app/my config.toml' --greeting 'it'\''s here'"#
+                .to_string(),
+            entrypoint: "/usr/bin/app".to_string(),
+            name: "app".to_string(),
+            hostname: "app.local".to_string(),
+            working_dir: "/srv".to_string(),
+            user: "1000:1000".to_string(),
+            userns_mode: "host".to_string(),
+            network_mode: "backend".to_string(),
+            log_driver: "json-file".to_string(),
+            tty: true,
+            stdin: true,
+            stdout: true,
+            stderr: true,
+            privileged: true,
+            autoremove: true,
+            cpus: 1.5,
+            cpu_shares: 512.,
+            memory: "512m".to_string(),
+            memory_swap: "1g".to_string(),
+            env: vec![pair("MODE", "prod"), pair("GREETING", "hello world")],
+            labels: vec![pair("team", "core"), pair("empty", "")],
+            sec_ops: vec!["no-new-privileges".to_string()],
+            volumes: vec!["/srv/data:/data:ro".to_string()],
+            links: vec!["db:database".to_string()],
+            capabilities: vec!["NET_ADMIN".to_string()],
+            ports: vec![pair("80/tcp", "8080"), pair("443/tcp", "")],
+            exposed: vec!["9000/udp".to_string()],
+            publish_all_ports: true,
+            restart_policy: "on-failure".to_string(),
+            maximum_retry_count: 3,
+            dns: vec!["1.1.1.1".to_string()],
+            extra_hosts: vec!["db.local:10.0.0.2".to_string()],
+            devices: vec!["/dev/fuse".to_string()],
+            stop_signal: "SIGINT".to_string(),
+            stop_timeout: "30".to_string(),
+            networks: vec!["frontend".to_string()],
+            original: None,
+            recreate: false,
+        };
+
+        let parsed = ContainerCreateData::from_docker_run(&data.to_docker_run().unwrap()).unwrap();
+        assert_eq!(
+            serde_json::to_value(&parsed).unwrap(),
+            serde_json::to_value(&data).unwrap()
+        );
+    }
+}
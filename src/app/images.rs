@@ -1,4 +1,5 @@
 use crate::app::{
+    settings::Settings,
     ui,
     ui::icon,
     ui::{key, key_val, val},
@@ -6,13 +7,13 @@ use crate::app::{
 };
 use crate::event::{EventRequest, GuiEvent, ImageEvent, ImageInspectInfo};
 use crate::format_date;
+use crate::worker::JobKind;
 
-use docker_api::api::{
-    ImageBuildChunk, ImageIdRef, ImageInfo, RegistryAuth, SearchResult, TagOpts,
-};
+use docker_api::api::{BuildOpts, ImageIdRef, ImageInfo, RegistryAuth, SearchResult, TagOpts};
 
-use anyhow::Error;
+use anyhow::{Context, Error, Result};
 use egui::{style::Margin, Grid, Label, RichText, TextEdit};
+use std::path::Path;
 
 pub fn trim_id(id: &str) -> &str {
     if id.len() > 12 {
@@ -39,6 +40,61 @@ fn name(id: ImageIdRef, tags: Option<&Vec<String>>) -> String {
     }
 }
 
+fn registry_auth(user: &str, password: &str) -> Option<RegistryAuth> {
+    if user.is_empty() {
+        return None;
+    }
+    let auth = RegistryAuth::builder().username(user);
+    if password.is_empty() {
+        Some(auth.build())
+    } else {
+        Some(auth.password(password).build())
+    }
+}
+
+/// Displays a combo box to choose one of the registry credentials from the settings. When no
+/// credential is chosen the user and password have to be entered by hand.
+fn credential_combobox(
+    ui: &mut egui::Ui,
+    id: &str,
+    settings: &Settings,
+    credential: &mut Option<String>,
+) {
+    egui::ComboBox::from_id_source(id)
+        .selected_text(credential.as_deref().unwrap_or("none"))
+        .show_ui(ui, |ui| {
+            ui.selectable_value(credential, None, "none");
+            for label in settings.all_credentials().map(|c| c.label()) {
+                ui.selectable_value(credential, Some(label.clone()), label);
+            }
+        });
+}
+
+/// Splits an image reference like `registry:5000/name:tag` into the name and the tag.
+fn split_reference(reference: &str) -> (&str, Option<&str>) {
+    match reference.rsplit_once(':') {
+        Some((name, tag)) if !tag.contains('/') => (name, Some(tag)),
+        _ => (reference, None),
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct PushWindow {
+    pub show: bool,
+    pub reference: String,
+    pub user: String,
+    pub password: String,
+    pub credential: Option<String>,
+    /// The last push started from this window, its progress is read from the jobs
+    pub job: Option<JobKind>,
+}
+
+impl PushWindow {
+    pub fn toggle(&mut self) {
+        self.show = !self.show;
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct TagWindow {
     pub show: bool,
@@ -56,7 +112,7 @@ impl TagWindow {
 pub struct SearchViewData {
     pub image: String,
     pub images: Option<Vec<SearchResult>>,
-    pub pull_in_progress: bool,
+    pub credential: Option<String>,
 }
 
 #[derive(Debug, Default)]
@@ -64,7 +120,56 @@ pub struct PullViewData {
     pub image: String,
     pub user: String,
     pub password: String,
-    pub in_progress: bool,
+    pub credential: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct BuildViewData {
+    pub context_dir: String,
+    /// Path to the Dockerfile, relative paths are resolved against the context directory
+    pub dockerfile: String,
+    pub tag: String,
+    pub target: String,
+    pub build_args: Vec<(String, String)>,
+    pub labels: Vec<(String, String)>,
+    pub nocache: bool,
+    pub pull: bool,
+    /// The last build started from this view, its output is read from the jobs
+    pub job: Option<JobKind>,
+}
+
+impl BuildViewData {
+    pub fn as_opts(&self) -> Result<BuildOpts> {
+        if self.context_dir.is_empty() {
+            return Err(Error::msg("build context directory can't be empty"));
+        }
+        let mut opts = BuildOpts::builder(&self.context_dir);
+        if !self.dockerfile.is_empty() {
+            let dockerfile = Path::new(&self.dockerfile);
+            // the dockerfile is sent as part of the context so it must be referenced relative to it
+            let dockerfile = if dockerfile.is_absolute() {
+                dockerfile
+                    .strip_prefix(&self.context_dir)
+                    .context("the Dockerfile must be inside of the build context directory")?
+            } else {
+                dockerfile
+            };
+            opts = opts.dockerfile(dockerfile.to_string_lossy());
+        }
+        if !self.tag.is_empty() {
+            opts = opts.tag(&self.tag);
+        }
+        if !self.target.is_empty() {
+            opts = opts.target(&self.target);
+        }
+        if !self.build_args.is_empty() {
+            opts = opts.build_args(self.build_args.clone());
+        }
+        if !self.labels.is_empty() {
+            opts = opts.labels(self.labels.clone());
+        }
+        Ok(opts.nocache(self.nocache).pull(self.pull).build())
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -72,6 +177,7 @@ pub enum CentralView {
     Image,
     Pull,
     Search,
+    Build,
     None,
 }
 
@@ -85,19 +191,19 @@ impl Default for CentralView {
 pub struct ImagesTab {
     pub images: Vec<ImageInfo>,
     pub current_image: Option<Box<ImageInspectInfo>>,
-    pub current_pull_chunks: Option<Vec<ImageBuildChunk>>,
 
     pub tag_window: TagWindow,
+    pub push_window: PushWindow,
     pub central_view: CentralView,
     pub pull_view_data: PullViewData,
     pub search_view_data: SearchViewData,
+    pub build_view_data: BuildViewData,
 }
 
 impl ImagesTab {
     pub fn clear(&mut self) {
         self.images.clear();
         self.current_image = None;
-        self.current_pull_chunks = None;
         self.central_view = CentralView::None;
     }
 }
@@ -135,9 +241,11 @@ impl App {
             CentralView::Image => self.image_details(ui),
             CentralView::Pull => self.images_pull(ui),
             CentralView::Search => self.images_search(ui),
+            CentralView::Build => self.images_build(ui),
             CentralView::None => {}
         }
         self.display_tag_window(ui);
+        self.display_push_window(ui);
     }
 
     pub fn images_side(&mut self, ui: &mut egui::Ui) {
@@ -156,6 +264,7 @@ impl App {
             );
             ui.selectable_value(&mut self.images.central_view, CentralView::Pull, "pull");
             ui.selectable_value(&mut self.images.central_view, CentralView::Search, "search");
+            ui.selectable_value(&mut self.images.central_view, CentralView::Build, "build");
         });
         egui::Grid::new("images_button_grid").show(ui, |ui| {
             if ui.button("import").clicked() {
@@ -389,6 +498,14 @@ impl App {
                 if ui.button("tag").clicked() {
                     self.images.tag_window.toggle();
                 }
+                if ui.button("push").clicked() {
+                    if self.images.push_window.reference.is_empty() {
+                        if let Some(tag) = details.repo_tags.first() {
+                            self.images.push_window.reference = tag.clone();
+                        }
+                    }
+                    self.images.push_window.toggle();
+                }
             });
             ui.add_space(25.);
 
@@ -528,103 +645,58 @@ impl App {
             ui.add(Label::new(RichText::new("Image to pull:").strong()));
             ui.add(TextEdit::singleline(&mut self.images.pull_view_data.image).desired_width(150.));
             ui.end_row();
-            ui.add(Label::new(RichText::new("User:").strong()));
-            ui.add(TextEdit::singleline(&mut self.images.pull_view_data.user).desired_width(150.));
-            ui.end_row();
-            ui.add(Label::new(RichText::new("Password:").strong()));
-            ui.add(
-                TextEdit::singleline(&mut self.images.pull_view_data.password)
-                    .password(true)
-                    .desired_width(150.),
+            ui.add(Label::new(RichText::new("Credential:").strong()));
+            credential_combobox(
+                ui,
+                "image_pull_credential",
+                &self.settings_window.settings,
+                &mut self.images.pull_view_data.credential,
             );
             ui.end_row();
+            if self.images.pull_view_data.credential.is_none() {
+                ui.add(Label::new(RichText::new("User:").strong()));
+                ui.add(
+                    TextEdit::singleline(&mut self.images.pull_view_data.user).desired_width(150.),
+                );
+                ui.end_row();
+                ui.add(Label::new(RichText::new("Password:").strong()));
+                ui.add(
+                    TextEdit::singleline(&mut self.images.pull_view_data.password)
+                        .password(true)
+                        .desired_width(150.),
+                );
+                ui.end_row();
+            }
             if ui.button("pull").clicked() {
-                if self.images.pull_view_data.in_progress {
-                    self.add_notification("Image pull already in progress");
-                } else if self.images.pull_view_data.image.is_empty() {
+                if self.images.pull_view_data.image.is_empty() {
                     self.add_notification("Image name can't be empty");
                 } else {
-                    let auth = if !self.images.pull_view_data.user.is_empty() {
-                        let auth = RegistryAuth::builder();
-                        if !self.images.pull_view_data.password.is_empty() {
-                            Some(
-                                auth.username(&self.images.pull_view_data.user)
-                                    .password(&self.images.pull_view_data.password)
-                                    .build(),
-                            )
-                        } else {
-                            Some(auth.username(&self.images.pull_view_data.user).build())
-                        }
-                    } else {
-                        None
-                    };
-                    self.send_event_notify(EventRequest::Image(ImageEvent::Pull {
-                        image: self.images.pull_view_data.image.clone(),
-                        auth,
-                    }));
-                    self.images.pull_view_data.in_progress = true;
-                    self.images.current_pull_chunks = None;
+                    let auth = self.selected_auth(
+                        self.images.pull_view_data.credential.as_deref(),
+                        &self.images.pull_view_data.user,
+                        &self.images.pull_view_data.password,
+                    );
+                    self.queue_pull(self.images.pull_view_data.image.clone(), auth);
                 }
             }
         });
-        let mut text = String::new();
-        let mut progress_percent = 0.;
-        if let Some(chunks) = self.images.current_pull_chunks.as_ref() {
-            for chunk in chunks {
-                match chunk {
-                    ImageBuildChunk::Update { stream } => {
-                        text.push_str("Update: ");
-                        text.push_str(stream);
-                    }
-                    ImageBuildChunk::Error { error, .. } => {
-                        text.push_str("Error: ");
-                        text.push_str(error);
-                    }
-                    ImageBuildChunk::Digest { aux } => {
-                        text.push_str("Digest: ");
-                        text.push_str(&aux.id);
-                        progress_percent = 1.;
-                    }
-                    ImageBuildChunk::PullStatus {
-                        status,
-                        id: _,
-                        progress: _,
-                        progress_detail,
-                    } => {
-                        if status.starts_with("Digest") {
-                            progress_percent = 1.;
-                        }
-                        text.push_str("Status: ");
-                        text.push_str(status);
-                        if let Some(progress) = progress_detail {
-                            if let Some(current) = progress.current {
-                                if let Some(total) = progress.total {
-                                    progress_percent = current as f32 / total as f32;
-                                    text.push_str(&format!(
-                                        " ({} / {})",
-                                        crate::conv_b(current),
-                                        crate::conv_b(total)
-                                    ));
-                                }
-                            }
-                        }
-                    }
-                }
-                text.push('\n');
-            }
+        ui.add_space(15.);
+        if let Some(job) = self.jobs_window.find(&JobKind::Pull {
+            image: self.images.pull_view_data.image.clone(),
+        }) {
+            ui.add(&job.progress);
         }
-        if self.images.pull_view_data.in_progress || (progress_percent - 1.).abs() < f32::EPSILON {
-            ui.add(
-                egui::ProgressBar::new(progress_percent)
-                    .desired_width(200.)
-                    .animate(true),
-            );
+    }
+
+    /// Queues a pull of `image`, the progress of all pulls is displayed in the jobs window.
+    fn queue_pull(&mut self, image: String, auth: Option<RegistryAuth>) {
+        if self.jobs_window.is_active(&JobKind::Pull {
+            image: image.clone(),
+        }) {
+            self.add_notification(format!("Image {} is already being pulled", image));
+            return;
         }
-        ui.add(
-            TextEdit::multiline(&mut text.as_str())
-                .code_editor()
-                .desired_width(f32::INFINITY),
-        );
+        self.send_event_notify(EventRequest::Image(ImageEvent::Pull { image, auth }));
     }
 
     fn images_search(&mut self, ui: &mut egui::Ui) {
@@ -652,48 +724,20 @@ impl App {
                 }
             }
         });
+        ui.horizontal(|ui| {
+            key!(ui, "Credential to pull with:");
+            credential_combobox(
+                ui,
+                "image_search_credential",
+                &self.settings_window.settings,
+                &mut self.images.search_view_data.credential,
+            );
+        });
 
         ui.add_space(15.);
 
-        let mut progress_percent = 0.;
-        if let Some(chunks) = self.images.current_pull_chunks.as_ref() {
-            for chunk in chunks {
-                match chunk {
-                    ImageBuildChunk::Digest { aux: _ } => {
-                        progress_percent = 1.;
-                    }
-                    ImageBuildChunk::PullStatus {
-                        status,
-                        id: _,
-                        progress: _,
-                        progress_detail,
-                    } => {
-                        if status.starts_with("Digest") {
-                            progress_percent = 1.;
-                        }
-                        if let Some(progress) = progress_detail {
-                            if let Some(current) = progress.current {
-                                if let Some(total) = progress.total {
-                                    progress_percent = current as f32 / total as f32;
-                                }
-                            }
-                        }
-                    }
-                    _ => {}
-                }
-            }
-        }
-        if self.images.search_view_data.pull_in_progress
-            || (progress_percent - 1.).abs() < f32::EPSILON
-        {
-            ui.add(
-                egui::ProgressBar::new(progress_percent)
-                    .desired_width(200.)
-                    .animate(true),
-            );
-        }
-
-        let mut pull_events = vec![];
+        let mut pulls = vec![];
+        let auth = self.selected_auth(self.images.search_view_data.credential.as_deref(), "", "");
 
         if let Some(images) = self.images.search_view_data.images.as_ref() {
             Grid::new("images_search_grid")
@@ -712,14 +756,16 @@ impl App {
                     ui.end_row();
 
                     for image in images {
-                        if ui.button(icon::ARROW_DOWN).clicked() {
-                            // #TODO: handle auth
-                            pull_events.push(EventRequest::Image(ImageEvent::Pull {
-                                image: image.name.clone(),
-                                auth: None,
-                            }));
-                            self.images.current_pull_chunks = None;
-                            self.images.search_view_data.pull_in_progress = true;
+                        if self.jobs_window.is_active(&JobKind::Pull {
+                            image: image.name.clone(),
+                        }) {
+                            ui.add(egui::Spinner::new());
+                        } else if ui
+                            .button(icon::ARROW_DOWN)
+                            .on_hover_text("pull this image")
+                            .clicked()
+                        {
+                            pulls.push(image.name.clone());
                         }
                         ui.scope(|ui| {
                             ui.add(icon());
@@ -733,9 +779,147 @@ impl App {
                     }
                 });
         }
-        for event in pull_events {
-            self.send_event_notify(event);
+        for image in pulls {
+            self.queue_pull(image, auth.clone());
+        }
+    }
+
+    fn images_build(&mut self, ui: &mut egui::Ui) {
+        ui.add(Label::new(
+            RichText::new("Build an image from a Dockerfile")
+                .heading()
+                .strong(),
+        ));
+        ui.add_space(25.);
+
+        let mut build = false;
+        let mut errors = vec![];
+        Grid::new("image_build_grid").show(ui, |ui| {
+            let data = &mut self.images.build_view_data;
+            ui.scope(|_| {});
+            ui.allocate_space((200., 0.).into());
+            ui.end_row();
+            key!(ui, "Context directory:");
+            ui.horizontal(|ui| {
+                ui.add(TextEdit::singleline(&mut data.context_dir).desired_width(250.));
+                if ui
+                    .button(icon::DISK)
+                    .on_hover_text("choose the build context directory")
+                    .clicked()
+                {
+                    match native_dialog::FileDialog::new().show_open_single_dir() {
+                        Ok(Some(path)) => data.context_dir = path.to_string_lossy().to_string(),
+                        Ok(None) => {}
+                        Err(e) => errors.push(format!("failed to spawn a file dialog - {}", e)),
+                    }
+                }
+            });
+            ui.end_row();
+            key!(ui, "Dockerfile:");
+            ui.horizontal(|ui| {
+                ui.add(
+                    TextEdit::singleline(&mut data.dockerfile)
+                        .hint_text("Dockerfile")
+                        .desired_width(250.),
+                );
+                if ui
+                    .button(icon::DISK)
+                    .on_hover_text("choose the Dockerfile")
+                    .clicked()
+                {
+                    match native_dialog::FileDialog::new()
+                        .set_location(&data.context_dir)
+                        .show_open_single_file()
+                    {
+                        Ok(Some(path)) => data.dockerfile = path.to_string_lossy().to_string(),
+                        Ok(None) => {}
+                        Err(e) => errors.push(format!("failed to spawn a file dialog - {}", e)),
+                    }
+                }
+            });
+            ui.end_row();
+            key!(ui, "Tag:");
+            ui.add(
+                TextEdit::singleline(&mut data.tag)
+                    .hint_text("name:tag")
+                    .desired_width(250.),
+            );
+            ui.end_row();
+            key!(ui, "Target stage:");
+            ui.add(TextEdit::singleline(&mut data.target).desired_width(250.));
+            ui.end_row();
+            ui.checkbox(&mut data.nocache, "No cache")
+                .on_hover_text("do not use the cache when building the image");
+            ui.end_row();
+            ui.checkbox(&mut data.pull, "Pull")
+                .on_hover_text("always attempt to pull a newer version of the base images");
+            ui.end_row();
+            ui.add(
+                ui::EditableList::builder_key_val(&mut data.build_args)
+                    .heading("Build arguments:")
+                    .build(),
+            );
+            ui.end_row();
+            ui.add(
+                ui::EditableList::builder_key_val(&mut data.labels)
+                    .heading("Labels:")
+                    .build(),
+            );
+            ui.end_row();
+            if ui.button("build").clicked() {
+                build = true;
+            }
+            ui.end_row();
+        });
+
+        for error in errors {
+            self.add_error(error);
+        }
+        if build {
+            let data = &self.images.build_view_data;
+            match data.as_opts() {
+                Ok(opts) => {
+                    let kind = JobKind::Build {
+                        context: data.context_dir.clone(),
+                        tag: data.tag.clone(),
+                    };
+                    if self.jobs_window.is_active(&kind) {
+                        self.add_notification("Image build already in progress");
+                    } else {
+                        let event = ImageEvent::Build {
+                            context: data.context_dir.clone(),
+                            tag: data.tag.clone(),
+                            opts,
+                        };
+                        self.images.build_view_data.job = Some(kind);
+                        self.send_event_notify(EventRequest::Image(event));
+                    }
+                }
+                Err(e) => self.add_error(e),
+            }
+        }
+
+        ui.add_space(10.);
+        let job = self
+            .images
+            .build_view_data
+            .job
+            .as_ref()
+            .and_then(|kind| self.jobs_window.find(kind));
+        let text = job.map(|job| job.output.as_str()).unwrap_or_default();
+        if job.map(|job| job.status.is_active()).unwrap_or_default() {
+            ui.add(egui::Spinner::new());
         }
+        egui::ScrollArea::vertical()
+            .id_source("image_build_output")
+            .stick_to_bottom()
+            .show(ui, |ui| {
+                ui.add(
+                    TextEdit::multiline(&mut &*text)
+                        .code_editor()
+                        .desired_width(f32::INFINITY),
+                );
+            });
     }
 
     fn display_tag_window(&mut self, ui: &mut egui::Ui) {
@@ -780,4 +964,120 @@ impl App {
             });
         }
     }
+
+    fn display_push_window(&mut self, ui: &mut egui::Ui) {
+        if !self.images.push_window.show {
+            return;
+        }
+        let tags = self
+            .images
+            .current_image
+            .as_ref()
+            .map(|i| i.details.repo_tags.clone())
+            .unwrap_or_default();
+        let mut push = false;
+        egui::Window::new("Push this image").show(ui.ctx(), |ui| {
+            let window = &mut self.images.push_window;
+            Grid::new("push_window_grid").show(ui, |ui| {
+                key!(ui, "Image:");
+                ui.horizontal(|ui| {
+                    ui.add(TextEdit::singleline(&mut window.reference).desired_width(200.));
+                    egui::ComboBox::from_id_source("push_window_tags")
+                        .selected_text("tags")
+                        .show_ui(ui, |ui| {
+                            for tag in &tags {
+                                ui.selectable_value(&mut window.reference, tag.clone(), tag);
+                            }
+                        });
+                });
+                ui.end_row();
+                key!(ui, "Credential:");
+                credential_combobox(
+                    ui,
+                    "push_window_credential",
+                    &self.settings_window.settings,
+                    &mut window.credential,
+                );
+                ui.end_row();
+                if window.credential.is_none() {
+                    key!(ui, "User:");
+                    ui.add(TextEdit::singleline(&mut window.user).desired_width(200.));
+                    ui.end_row();
+                    key!(ui, "Password:");
+                    ui.add(
+                        TextEdit::singleline(&mut window.password)
+                            .password(true)
+                            .desired_width(200.),
+                    );
+                    ui.end_row();
+                }
+            });
+
+            Grid::new("push_window_buttons").show(ui, |ui| {
+                if ui.button("push").clicked() {
+                    push = true;
+                }
+                if ui.button("close").clicked() {
+                    window.toggle();
+                }
+                ui.end_row();
+            });
+
+            if let Some(job) = window
+                .job
+                .as_ref()
+                .and_then(|kind| self.jobs_window.find(kind))
+            {
+                egui::ScrollArea::vertical()
+                    .max_height(300.)
+                    .show(ui, |ui| {
+                        ui.add(&job.progress);
+                    });
+            }
+        });
+
+        if push {
+            let window = &self.images.push_window;
+            let (image, tag) = split_reference(&window.reference);
+            let kind = JobKind::Push {
+                image: image.to_string(),
+                tag: tag.map(str::to_string),
+            };
+            if window.reference.is_empty() {
+                self.add_notification("Image name can't be empty");
+            } else if self.jobs_window.is_active(&kind) {
+                self.add_notification("Image push already in progress");
+            } else {
+                let event = ImageEvent::Push {
+                    image: image.to_string(),
+                    tag: tag.map(str::to_string),
+                    auth: self.selected_auth(
+                        window.credential.as_deref(),
+                        &window.user,
+                        &window.password,
+                    ),
+                };
+                self.images.push_window.job = Some(kind);
+                self.send_event_notify(EventRequest::Image(event));
+            }
+        }
+    }
+
+    /// Returns the auth of the chosen saved credential or of the user and password entered by
+    /// hand if no credential is chosen.
+    fn selected_auth(
+        &self,
+        credential: Option<&str>,
+        user: &str,
+        password: &str,
+    ) -> Option<RegistryAuth> {
+        match credential {
+            Some(label) => self
+                .settings_window
+                .settings
+                .credential(label)
+                .map(|c| c.auth()),
+            None => registry_auth(user, password),
+        }
+    }
 }
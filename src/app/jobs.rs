@@ -0,0 +1,178 @@
+use crate::app::{ui, ui::icon, App};
+use crate::event::{EventRequest, JobsEvent};
+use crate::worker::{JobId, JobKind, JobStatus, JobUpdate};
+
+use docker_api::api::ImageBuildChunk;
+use egui::{Label, RichText};
+
+#[derive(Debug)]
+pub struct JobView {
+    pub id: JobId,
+    pub kind: JobKind,
+    pub status: JobStatus,
+    pub progress: ui::TransferProgress,
+    /// Output of image builds in the form printed by the docker CLI
+    pub output: String,
+}
+
+/// Appends the text of `chunk` to `output` the same way the docker CLI prints it.
+fn push_output(output: &mut String, chunk: &ImageBuildChunk) {
+    match chunk {
+        ImageBuildChunk::Update { stream } => output.push_str(stream),
+        ImageBuildChunk::Error { error, .. } => {
+            output.push_str("Error: ");
+            output.push_str(error);
+            output.push('\n');
+        }
+        ImageBuildChunk::Digest { aux } => {
+            output.push_str("Digest: ");
+            output.push_str(&aux.id);
+            output.push('\n');
+        }
+        ImageBuildChunk::PullStatus {
+            status,
+            id,
+            progress,
+            ..
+        } => {
+            if let Some(id) = id {
+                output.push_str(id);
+                output.push_str(": ");
+            }
+            output.push_str(status);
+            if let Some(progress) = progress {
+                output.push(' ');
+                output.push_str(progress);
+            }
+            output.push('\n');
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct JobsWindow {
+    pub show: bool,
+    pub jobs: Vec<JobView>,
+}
+
+impl JobsWindow {
+    pub fn toggle(&mut self) {
+        self.show = !self.show;
+    }
+
+    /// Replaces the state of all jobs with the `updates` received from the worker.
+    pub fn update(&mut self, updates: Vec<JobUpdate>) {
+        let mut jobs = Vec::with_capacity(updates.len());
+        for update in updates {
+            let mut job = match self.jobs.iter().position(|j| j.id == update.id) {
+                Some(i) => self.jobs.swap_remove(i),
+                None => JobView {
+                    id: update.id,
+                    kind: update.kind,
+                    status: JobStatus::Queued,
+                    progress: ui::TransferProgress::default(),
+                    output: String::new(),
+                },
+            };
+            if matches!(job.kind, JobKind::Build { .. }) {
+                for chunk in &update.chunks {
+                    push_output(&mut job.output, chunk);
+                }
+            }
+            job.progress.extend(update.chunks);
+            if matches!(update.status, JobStatus::Finished(_)) {
+                job.progress.finished = true;
+            }
+            job.status = update.status;
+            jobs.push(job);
+        }
+        self.jobs = jobs;
+    }
+
+    pub fn active_count(&self) -> usize {
+        self.jobs.iter().filter(|j| j.status.is_active()).count()
+    }
+
+    /// Returns the most recent job of this kind.
+    pub fn find(&self, kind: &JobKind) -> Option<&JobView> {
+        self.jobs.iter().rev().find(|j| &j.kind == kind)
+    }
+
+    pub fn is_active(&self, kind: &JobKind) -> bool {
+        self.find(kind)
+            .map(|j| j.status.is_active())
+            .unwrap_or_default()
+    }
+}
+
+fn status_text(status: &JobStatus) -> RichText {
+    match status {
+        JobStatus::Queued => RichText::new("queued").color(egui::Color32::GRAY),
+        JobStatus::Running => RichText::new("running").color(egui::Color32::YELLOW),
+        JobStatus::Finished(_) => RichText::new("finished").color(egui::Color32::GREEN),
+        JobStatus::Failed(_) => RichText::new("failed").color(egui::Color32::RED),
+        JobStatus::Cancelled => RichText::new("cancelled").color(egui::Color32::RED),
+    }
+}
+
+impl App {
+    pub fn display_jobs_window(&mut self, ctx: &egui::Context) {
+        let mut show = self.jobs_window.show;
+        let mut cancel = None;
+        let mut clear = false;
+        egui::Window::new("jobs").open(&mut show).show(ctx, |ui| {
+            if ui.button("clear finished").clicked() {
+                clear = true;
+            }
+            ui.add_space(10.);
+            if self.jobs_window.jobs.is_empty() {
+                ui.label("No jobs");
+            }
+            egui::ScrollArea::vertical()
+                .max_height(500.)
+                .show(ui, |ui| {
+                    for job in &self.jobs_window.jobs {
+                        ui.push_id(job.id, |ui| {
+                            ui.horizontal(|ui| {
+                                ui.add(Label::new(RichText::new(job.kind.to_string()).strong()));
+                                ui.add(Label::new(status_text(&job.status)));
+                                if job.status.is_active()
+                                    && ui
+                                        .button(icon::STOP)
+                                        .on_hover_text("cancel this job")
+                                        .clicked()
+                                {
+                                    cancel = Some(job.id);
+                                }
+                            });
+                            match &job.status {
+                                JobStatus::Finished(msg) => {
+                                    ui.label(msg);
+                                }
+                                JobStatus::Failed(msg) => {
+                                    ui.add(Label::new(
+                                        RichText::new(msg).color(egui::Color32::RED),
+                                    ));
+                                }
+                                _ => {}
+                            }
+                            if !job.progress.is_empty() {
+                                ui.add(&job.progress);
+                            }
+                        });
+                        ui.separator();
+                    }
+                });
+        });
+        self.jobs_window.show = show;
+
+        if let Some(id) = cancel {
+            self.send_event_notify(EventRequest::Jobs(JobsEvent::Cancel { id }));
+            self.send_event_notify(EventRequest::Jobs(JobsEvent::List));
+        }
+        if clear {
+            self.jobs_window.jobs.retain(|j| j.status.is_active());
+            self.send_event_notify(EventRequest::Jobs(JobsEvent::Clear));
+        }
+    }
+}
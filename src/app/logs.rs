@@ -0,0 +1,985 @@
+use crate::app::{
+    containers::COMPOSE_PROJECT_LABEL,
+    ui::{self, color, icon},
+    App,
+};
+use crate::event::{ContainerEvent, EventRequest};
+use crate::worker::{LogsConfig, LogsExportFormat};
+
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use docker_api::{
+    api::{ContainerId, ContainerInfo},
+    conn::TtyChunk,
+};
+use egui::containers::Frame;
+use egui::{Color32, Label, RichText, TextFormat, TextStyle};
+use regex::Regex;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+const PAGE_SIZE: usize = 1024;
+
+/// Colors of the prefixes that tell apart lines of different containers in the aggregated logs
+const CONTAINER_COLORS: [Color32; 8] = [
+    Color32::from_rgb(0x29, 0xb8, 0xdb),
+    Color32::from_rgb(0xd6, 0x70, 0xd6),
+    Color32::from_rgb(0x23, 0xd1, 0x8b),
+    Color32::from_rgb(0xe5, 0xa5, 0x10),
+    Color32::from_rgb(0x3b, 0x8e, 0xea),
+    Color32::from_rgb(0xf1, 0x4c, 0x4c),
+    Color32::from_rgb(0x8b, 0xc3, 0x4a),
+    Color32::from_rgb(0xff, 0x8a, 0x65),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LogStream {
+    StdOut,
+    StdErr,
+}
+
+#[derive(Debug)]
+pub struct LogLine {
+    pub stream: LogStream,
+    /// Raw content of the line including ANSI escape sequences
+    pub text: String,
+    /// Content of the line with escape sequences removed, used for searching
+    pub plain: String,
+    /// Whether the line was terminated with a newline or is still waiting for more output
+    pub complete: bool,
+    /// Start of an escape sequence split between chunks, stripped once it's complete
+    pending_escape: String,
+}
+
+impl LogLine {
+    fn new(stream: LogStream) -> Self {
+        Self {
+            stream,
+            text: String::new(),
+            plain: String::new(),
+            complete: false,
+            pending_escape: String::new(),
+        }
+    }
+
+    fn push_str(&mut self, text: &str) {
+        self.text.push_str(text);
+        // only the new output is stripped so that appending to long lines stays linear
+        let mut new = std::mem::take(&mut self.pending_escape);
+        new.push_str(text);
+        let end = incomplete_escape_start(&new).unwrap_or(new.len());
+        match strip_ansi_escapes::strip(&new.as_bytes()[..end]) {
+            Ok(stripped) => self.plain.push_str(&String::from_utf8_lossy(&stripped)),
+            Err(_) => self.plain.push_str(&new[..end]),
+        }
+        self.pending_escape = new[end..].to_string();
+    }
+}
+
+/// Returns the position of an escape sequence at the end of `text` that isn't terminated yet.
+fn incomplete_escape_start(text: &str) -> Option<usize> {
+    let start = text.rfind('\x1b')?;
+    let sequence = &text[start + 1..];
+    let complete = match sequence.chars().next() {
+        None => false,
+        // control sequences end with a character in the range `@` to `~`
+        Some('[') => sequence[1..].chars().any(|c| ('@'..='~').contains(&c)),
+        // operating system commands end with BEL or ST whose ESC is found by `rfind`
+        Some(']') => sequence.contains('\x07'),
+        Some(_) => true,
+    };
+    if complete {
+        None
+    } else {
+        Some(start)
+    }
+}
+
+#[derive(Debug)]
+pub struct LogsViewData {
+    /// Ring buffer holding at most `config.max_lines` of the newest lines
+    pub lines: VecDeque<LogLine>,
+    /// Number of the first line in the buffer, increases as old lines are dropped
+    pub first_line: usize,
+    pub page: usize,
+    pub follow: bool,
+    pub config: LogsConfig,
+    pub tail: usize,
+    pub tail_all: bool,
+    pub since: String,
+    /// Maximum count of lines entered by the user, applied with the rest of the configuration
+    pub max_lines: usize,
+
+    pub search: String,
+    pub regex: Option<Regex>,
+    pub regex_error: Option<String>,
+    /// Numbers of lines matching the current search
+    pub matching: VecDeque<usize>,
+    /// Index into `matching` of the currently selected match
+    pub current_match: Option<usize>,
+    pub matching_only: bool,
+    scroll_to_match: bool,
+
+    pub export_format: LogsExportFormat,
+    /// Count of lines written so far when an export is in progress
+    pub export_progress: Option<usize>,
+}
+
+impl Default for LogsViewData {
+    fn default() -> Self {
+        let config = LogsConfig::default();
+        Self {
+            lines: VecDeque::new(),
+            first_line: 0,
+            page: 0,
+            follow: false,
+            tail: config.tail.unwrap_or_default(),
+            tail_all: config.tail.is_none(),
+            since: String::new(),
+            max_lines: config.max_lines,
+            config,
+            search: String::new(),
+            regex: None,
+            regex_error: None,
+            matching: VecDeque::new(),
+            current_match: None,
+            matching_only: false,
+            scroll_to_match: false,
+            export_format: LogsExportFormat::default(),
+            export_progress: None,
+        }
+    }
+}
+
+impl LogsViewData {
+    /// Clears the lines of the logs leaving the configuration and search intact.
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.first_line = 0;
+        self.matching.clear();
+        self.current_match = None;
+        self.page = 0;
+    }
+
+    pub fn push_chunks(&mut self, chunks: Vec<TtyChunk>) {
+        for chunk in chunks {
+            let (stream, data) = match chunk {
+                TtyChunk::StdOut(data) => (LogStream::StdOut, data),
+                TtyChunk::StdErr(data) => (LogStream::StdErr, data),
+                TtyChunk::StdIn(_) => continue,
+            };
+            self.push_text(stream, &String::from_utf8_lossy(&data));
+        }
+        self.truncate();
+    }
+
+    /// Drops the oldest lines so that at most `config.max_lines` are kept.
+    fn truncate(&mut self) {
+        while self.lines.len() > self.config.max_lines {
+            self.lines.pop_front();
+            self.first_line += 1;
+        }
+        while self
+            .matching
+            .front()
+            .map(|n| *n < self.first_line)
+            .unwrap_or_default()
+        {
+            self.matching.pop_front();
+            self.current_match = match self.current_match {
+                Some(0) | None => None,
+                Some(idx) => Some(idx - 1),
+            };
+        }
+    }
+
+    fn push_text(&mut self, stream: LogStream, text: &str) {
+        for part in text.split_inclusive('\n') {
+            let continued = self
+                .lines
+                .back()
+                .map(|line| !line.complete && line.stream == stream)
+                .unwrap_or_default();
+            if continued {
+                // the last line will be matched again with the new content
+                if self.matching.back() == Some(&(self.first_line + self.lines.len() - 1)) {
+                    self.matching.pop_back();
+                }
+            } else {
+                self.lines.push_back(LogLine::new(stream));
+            }
+
+            let idx = self.lines.len() - 1;
+            let line = &mut self.lines[idx];
+            if let Some(part) = part.strip_suffix('\n') {
+                line.push_str(part.strip_suffix('\r').unwrap_or(part));
+                line.complete = true;
+            } else {
+                line.push_str(part);
+            }
+
+            if let Some(regex) = &self.regex {
+                if regex.is_match(&line.plain) {
+                    self.matching.push_back(self.first_line + idx);
+                }
+            }
+        }
+    }
+
+    /// Compiles the current search and finds all matching lines.
+    pub fn update_search(&mut self) {
+        self.matching.clear();
+        self.current_match = None;
+        self.regex_error = None;
+        self.regex = None;
+
+        if self.search.is_empty() {
+            return;
+        }
+        match Regex::new(&self.search) {
+            Ok(regex) => {
+                self.matching = self
+                    .lines
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, line)| regex.is_match(&line.plain))
+                    .map(|(i, _)| self.first_line + i)
+                    .collect();
+                self.regex = Some(regex);
+            }
+            Err(e) => self.regex_error = Some(e.to_string()),
+        }
+    }
+
+    fn select_match(&mut self, idx: usize) {
+        self.current_match = Some(idx);
+        let position = if self.matching_only {
+            idx
+        } else {
+            self.matching[idx] - self.first_line
+        };
+        self.page = position / PAGE_SIZE;
+        self.follow = false;
+        self.scroll_to_match = true;
+    }
+
+    pub fn next_match(&mut self) {
+        if self.matching.is_empty() {
+            return;
+        }
+        let idx = match self.current_match {
+            Some(idx) if idx + 1 < self.matching.len() => idx + 1,
+            _ => 0,
+        };
+        self.select_match(idx);
+    }
+
+    pub fn prev_match(&mut self) {
+        if self.matching.is_empty() {
+            return;
+        }
+        let idx = match self.current_match {
+            Some(idx) if idx > 0 => idx - 1,
+            _ => self.matching.len() - 1,
+        };
+        self.select_match(idx);
+    }
+
+    /// Returns the count of lines that are displayed with current filters.
+    fn visible_len(&self) -> usize {
+        if self.matching_only {
+            self.matching.len()
+        } else {
+            self.lines.len()
+        }
+    }
+
+    /// Maps a position in the displayed lines to an index of a line in the buffer.
+    fn line_idx(&self, position: usize) -> usize {
+        if self.matching_only {
+            self.matching[position] - self.first_line
+        } else {
+            position
+        }
+    }
+
+    /// Creates a configuration of the logs stream from the current input.
+    fn logs_config(&self) -> anyhow::Result<LogsConfig> {
+        let since = if self.since.trim().is_empty() {
+            None
+        } else {
+            Some(
+                DateTime::parse_from_rfc3339(self.since.trim())
+                    .context("invalid `since` date, expected RFC3339 format")?
+                    .with_timezone(&Utc),
+            )
+        };
+        Ok(LogsConfig {
+            timestamps: self.config.timestamps,
+            tail: if self.tail_all { None } else { Some(self.tail) },
+            since,
+            max_lines: self.max_lines,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct AggregatedLogLine {
+    pub container: ContainerId,
+    pub timestamp: DateTime<Utc>,
+    pub stream: LogStream,
+    /// Content of the line without the timestamp
+    pub text: String,
+}
+
+#[derive(Debug)]
+pub struct AggregatedLogsViewData {
+    /// Ids and names of followed containers, the position decides the color of the prefix
+    pub containers: Vec<(ContainerId, String)>,
+    /// Lines of all followed containers ordered by their timestamps
+    pub lines: VecDeque<AggregatedLogLine>,
+    /// Incomplete lines of each container and stream waiting for more output
+    pending: HashMap<(ContainerId, LogStream), String>,
+    pub max_lines: usize,
+    pub page: usize,
+    pub follow: bool,
+    pub timestamps: bool,
+    pub label_selector: String,
+}
+
+impl Default for AggregatedLogsViewData {
+    fn default() -> Self {
+        Self {
+            containers: vec![],
+            lines: VecDeque::new(),
+            pending: HashMap::new(),
+            max_lines: LogsConfig::default().max_lines,
+            page: 0,
+            follow: true,
+            timestamps: false,
+            label_selector: String::new(),
+        }
+    }
+}
+
+impl AggregatedLogsViewData {
+    /// Clears the lines of the logs leaving the followed containers intact, the worker reads
+    /// their logs again after a change of the Docker uri.
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.pending.clear();
+        self.page = 0;
+    }
+
+    pub fn is_selected(&self, id: &str) -> bool {
+        self.containers.iter().any(|(c, _)| c == id)
+    }
+
+    pub fn ids(&self) -> Vec<ContainerId> {
+        self.containers.iter().map(|(id, _)| id.clone()).collect()
+    }
+
+    /// Sets the followed containers dropping lines of the removed ones.
+    pub fn select(&mut self, containers: Vec<(ContainerId, String)>) {
+        self.containers = containers;
+        let containers = &self.containers;
+        let is_selected = |id: &ContainerId| containers.iter().any(|(c, _)| c == id);
+        self.lines.retain(|line| is_selected(&line.container));
+        self.pending.retain(|(id, _), _| is_selected(id));
+    }
+
+    fn color(&self, id: &str) -> Color32 {
+        let idx = self
+            .containers
+            .iter()
+            .position(|(c, _)| c == id)
+            .unwrap_or_default();
+        CONTAINER_COLORS[idx % CONTAINER_COLORS.len()]
+    }
+
+    /// Splits the output of each container into lines and merges them by their timestamps.
+    pub fn push_logs(&mut self, logs: Vec<(ContainerId, Box<crate::worker::Logs>)>) {
+        let mut new_lines = vec![];
+        for (id, logs) in logs {
+            if !self.is_selected(&id) {
+                continue;
+            }
+            for chunk in logs.0 {
+                let (stream, data) = match chunk {
+                    TtyChunk::StdOut(data) => (LogStream::StdOut, data),
+                    TtyChunk::StdErr(data) => (LogStream::StdErr, data),
+                    TtyChunk::StdIn(_) => continue,
+                };
+                let pending = self.pending.entry((id.clone(), stream)).or_default();
+                pending.push_str(&String::from_utf8_lossy(&data));
+                while let Some(pos) = pending.find('\n') {
+                    let line = pending.drain(..=pos).collect::<String>();
+                    let line = line.trim_end_matches(&['\r', '\n'][..]);
+                    // the logs are always read with timestamps that prefix each line
+                    let (timestamp, text) = match line.split_once(' ').and_then(|(ts, text)| {
+                        DateTime::parse_from_rfc3339(ts)
+                            .ok()
+                            .map(|ts| (ts.with_timezone(&Utc), text))
+                    }) {
+                        Some((ts, text)) => (ts, text),
+                        None => (Utc::now(), line),
+                    };
+                    new_lines.push(AggregatedLogLine {
+                        container: id.clone(),
+                        timestamp,
+                        stream,
+                        text: text.to_string(),
+                    });
+                }
+            }
+        }
+
+        new_lines.sort_by_key(|line| line.timestamp);
+        for line in new_lines {
+            let is_newest = self
+                .lines
+                .back()
+                .map(|last| last.timestamp <= line.timestamp)
+                .unwrap_or(true);
+            if is_newest {
+                self.lines.push_back(line);
+            } else {
+                let idx = self
+                    .lines
+                    .partition_point(|other| other.timestamp <= line.timestamp);
+                self.lines.insert(idx, line);
+            }
+        }
+        while self.lines.len() > self.max_lines {
+            self.lines.pop_front();
+        }
+    }
+}
+
+/// Returns true if `container` has a label matching `selector` in the form of `key` or
+/// `key=value`.
+fn matches_label(container: &ContainerInfo, selector: &str) -> bool {
+    let selector = selector.trim();
+    match selector.split_once('=') {
+        Some((key, value)) => container
+            .labels
+            .get(key.trim())
+            .map(|v| v == value.trim())
+            .unwrap_or_default(),
+        None => container.labels.contains_key(selector),
+    }
+}
+
+fn container_name(container: &ContainerInfo) -> String {
+    container
+        .names
+        .first()
+        .map(|n| n.trim_start_matches('/').to_string())
+        .unwrap_or_else(|| container.id[..12].to_string())
+}
+
+impl App {
+    pub fn container_logs(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Logs")
+            .default_open(false)
+            .show(ui, |ui| {
+                self.container_logs_controls(ui);
+                ui.add_space(5.);
+                self.container_logs_lines(ui);
+            });
+    }
+
+    fn container_logs_controls(&mut self, ui: &mut egui::Ui) {
+        let data = &mut self.containers.logs_view_data;
+        let max_page = data.visible_len().saturating_sub(1) / PAGE_SIZE;
+        let mut config_changed = false;
+
+        ui.horizontal(|ui| {
+            if ui
+                .button(icon::ARROW_LEFT)
+                .on_hover_text("Decrease the page")
+                .clicked()
+                && data.page > 0
+            {
+                data.page -= 1;
+            }
+            ui.add(
+                egui::DragValue::new(&mut data.page)
+                    .clamp_range(0..=max_page)
+                    .fixed_decimals(0)
+                    .speed(1.),
+            );
+            if ui
+                .button(icon::ARROW_RIGHT)
+                .on_hover_text("Increase the page")
+                .clicked()
+                && data.page < max_page
+            {
+                data.page += 1;
+            }
+            ui.checkbox(&mut data.follow, "Follow logs");
+            if ui
+                .checkbox(&mut data.config.timestamps, "Timestamps")
+                .changed()
+            {
+                config_changed = true;
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Tail:");
+            ui.add_enabled(
+                !data.tail_all,
+                egui::DragValue::new(&mut data.tail)
+                    .fixed_decimals(0)
+                    .speed(10.),
+            );
+            ui.checkbox(&mut data.tail_all, "all")
+                .on_hover_text("read the whole history of the logs");
+            ui.label("Since:");
+            ui.add(
+                egui::TextEdit::singleline(&mut data.since)
+                    .hint_text("2022-01-01T00:00:00Z")
+                    .desired_width(150.),
+            );
+            ui.label("Max lines:");
+            ui.add(
+                egui::DragValue::new(&mut data.max_lines)
+                    .clamp_range(100..=1_000_000)
+                    .fixed_decimals(0)
+                    .speed(100.),
+            )
+            .on_hover_text("maximum count of lines kept in memory, oldest lines are dropped first");
+            if ui
+                .button("apply")
+                .on_hover_text("reload the logs with these options")
+                .clicked()
+            {
+                config_changed = true;
+            }
+        });
+        ui.horizontal(|ui| {
+            let rsp = ui.add(
+                egui::TextEdit::singleline(&mut data.search)
+                    .hint_text("search with a regex")
+                    .desired_width(200.),
+            );
+            if rsp.changed() {
+                data.update_search();
+            }
+            if rsp.lost_focus() && ui.input().key_pressed(egui::Key::Enter) {
+                data.next_match();
+                rsp.request_focus();
+            }
+            if ui
+                .button(icon::ARROW_LEFT)
+                .on_hover_text("Previous match")
+                .clicked()
+            {
+                data.prev_match();
+            }
+            if ui
+                .button(icon::ARROW_RIGHT)
+                .on_hover_text("Next match")
+                .clicked()
+            {
+                data.next_match();
+            }
+            if data.regex.is_some() {
+                ui.label(format!(
+                    "{}/{}",
+                    data.current_match.map(|i| i + 1).unwrap_or_default(),
+                    data.matching.len()
+                ));
+            }
+            if ui
+                .checkbox(&mut data.matching_only, "Matching lines only")
+                .changed()
+            {
+                data.page = 0;
+                if let Some(idx) = data.current_match {
+                    data.select_match(idx);
+                }
+            }
+            if let Some(error) = &data.regex_error {
+                ui.add(
+                    Label::new(
+                        RichText::new(error.lines().last().unwrap_or_default())
+                            .color(egui::Color32::RED),
+                    )
+                    .wrap(true),
+                );
+            }
+        });
+
+        let mut export = false;
+        let mut cancel_export = false;
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_source("container_logs_export_format")
+                .selected_text(data.export_format.as_ref())
+                .show_ui(ui, |ui| {
+                    for format in [LogsExportFormat::PlainText, LogsExportFormat::JsonLines] {
+                        ui.selectable_value(&mut data.export_format, format, format.as_ref());
+                    }
+                });
+            if let Some(lines) = data.export_progress {
+                ui.label(format!("saving logs, {} lines written", lines));
+                cancel_export = ui.button("cancel").clicked();
+            } else if ui
+                .button(icon::SAVE)
+                .on_hover_text("save the whole history of the logs to a file")
+                .clicked()
+            {
+                export = true;
+            }
+        });
+
+        if data.follow {
+            data.page = max_page;
+        }
+        data.page = data.page.min(max_page);
+
+        if config_changed {
+            match data.logs_config() {
+                Ok(config) => {
+                    data.config = config.clone();
+                    data.truncate();
+                    self.send_event_notify(EventRequest::Container(ContainerEvent::ConfigureLogs(
+                        config,
+                    )));
+                }
+                Err(e) => self.add_error(e),
+            }
+        }
+        if export {
+            self.export_logs();
+        }
+        if cancel_export {
+            self.send_event_notify(EventRequest::Container(ContainerEvent::CancelLogsExport));
+        }
+    }
+
+    fn export_logs(&mut self) {
+        let id = match &self.containers.current_container {
+            Some(container) => container.id.clone(),
+            None => return,
+        };
+        let format = self.containers.logs_view_data.export_format;
+        let (filter, extension) = match format {
+            LogsExportFormat::PlainText => ("text file", "log"),
+            LogsExportFormat::JsonLines => ("JSON lines", "jsonl"),
+        };
+        let name = format!(
+            "{}.{}",
+            self.containers
+                .current_container
+                .as_ref()
+                .map(|c| c.name.trim_start_matches('/'))
+                .unwrap_or_default(),
+            extension
+        );
+        match native_dialog::FileDialog::new()
+            .add_filter(filter, &[extension])
+            .set_filename(&name)
+            .show_save_single_file()
+        {
+            Ok(Some(output_path)) => {
+                let timestamps = self.containers.logs_view_data.config.timestamps;
+                self.send_event_notify(EventRequest::Container(ContainerEvent::ExportLogs {
+                    id,
+                    output_path,
+                    format,
+                    timestamps,
+                }));
+            }
+            Ok(None) => {}
+            Err(e) => self.add_error(format!("failed to spawn a file dialog - {}", e)),
+        }
+    }
+
+    fn container_logs_lines(&mut self, ui: &mut egui::Ui) {
+        let dark_mode = ui.visuals().dark_mode;
+        let (bg, match_bg, current_match_bg, stderr_color) = if dark_mode {
+            (
+                *color::D_BG_000,
+                *color::D_BG_2,
+                *color::D_BG_3,
+                egui::Color32::from_rgb(0xf1, 0x4c, 0x4c),
+            )
+        } else {
+            (
+                *color::L_BG_4,
+                *color::L_BG_1,
+                *color::L_BG_0,
+                egui::Color32::from_rgb(0xcd, 0x31, 0x31),
+            )
+        };
+        let text_color = ui.visuals().text_color();
+        let weak_color = ui.visuals().weak_text_color();
+        let font_id = TextStyle::Monospace.resolve(ui.style());
+
+        let data = &mut self.containers.logs_view_data;
+        let current_line = data
+            .current_match
+            .and_then(|idx| data.matching.get(idx).copied());
+        let start = data.page * PAGE_SIZE;
+        let end = (start + PAGE_SIZE).min(data.visible_len());
+        let mut scroll_to_match = data.scroll_to_match;
+
+        Frame::none().fill(bg).show(ui, |ui| {
+            ui.allocate_space((ui.available_rect_before_wrap().width(), 0.).into());
+            ui.spacing_mut().item_spacing.y = 0.;
+            for position in start..end {
+                let idx = data.line_idx(position);
+                let line = &data.lines[idx];
+                let number = data.first_line + idx;
+                let is_current = current_line == Some(number);
+                let background = if is_current {
+                    current_match_bg
+                } else if !data.matching_only && data.matching.binary_search(&number).is_ok() {
+                    match_bg
+                } else {
+                    egui::Color32::TRANSPARENT
+                };
+                let base = TextFormat {
+                    font_id: font_id.clone(),
+                    color: match line.stream {
+                        LogStream::StdOut => text_color,
+                        LogStream::StdErr => stderr_color,
+                    },
+                    background,
+                    ..Default::default()
+                };
+
+                let mut job = egui::text::LayoutJob::default();
+                let mut text = line.text.as_str();
+                if data.config.timestamps {
+                    // docker prefixes each line with a RFC3339 timestamp followed by a space
+                    if let Some((timestamp, rest)) = text.split_once(' ') {
+                        job.append(
+                            timestamp,
+                            0.,
+                            TextFormat {
+                                color: weak_color,
+                                ..base.clone()
+                            },
+                        );
+                        job.append(" ", 0., base.clone());
+                        text = rest;
+                    }
+                }
+                let rest = ui::ansi::layout_job(text, base);
+                for section in rest.sections {
+                    job.append(&rest.text[section.byte_range], 0., section.format);
+                }
+
+                let rsp = ui.add(Label::new(job).wrap(true));
+                if is_current && scroll_to_match {
+                    rsp.scroll_to_me(Some(egui::Align::Center));
+                    scroll_to_match = false;
+                }
+            }
+        });
+        data.scroll_to_match = scroll_to_match;
+    }
+
+    pub fn aggregated_logs(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Aggregated logs");
+        ui.add_space(10.);
+        self.aggregated_logs_selection(ui);
+        ui.add_space(5.);
+        self.aggregated_logs_controls(ui);
+        ui.add_space(5.);
+        self.aggregated_logs_lines(ui);
+    }
+
+    fn aggregated_logs_selection(&mut self, ui: &mut egui::Ui) {
+        let data = &mut self.containers.aggregated_logs_view_data;
+        let mut selection = None;
+
+        egui::CollapsingHeader::new("Containers")
+            .default_open(data.containers.is_empty())
+            .show(ui, |ui| {
+                ui.horizontal_wrapped(|ui| {
+                    for container in &self.containers.containers {
+                        let mut selected = data.is_selected(&container.id);
+                        let name = container_name(container);
+                        if ui.checkbox(&mut selected, &name).changed() {
+                            let mut containers = data.containers.clone();
+                            if selected {
+                                containers.push((container.id.clone(), name));
+                            } else {
+                                containers.retain(|(id, _)| id != &container.id);
+                            }
+                            selection = Some(containers);
+                        }
+                    }
+                });
+                ui.add_space(5.);
+                ui.horizontal(|ui| {
+                    let projects = self
+                        .containers
+                        .containers
+                        .iter()
+                        .filter_map(|c| c.labels.get(COMPOSE_PROJECT_LABEL))
+                        .collect::<BTreeSet<_>>();
+                    if !projects.is_empty() {
+                        egui::ComboBox::from_id_source("aggregated_logs_project")
+                            .selected_text("compose project")
+                            .show_ui(ui, |ui| {
+                                for project in projects {
+                                    if ui.selectable_label(false, project).clicked() {
+                                        data.label_selector =
+                                            format!("{}={}", COMPOSE_PROJECT_LABEL, project);
+                                    }
+                                }
+                            });
+                    }
+                    ui.add(
+                        egui::TextEdit::singleline(&mut data.label_selector)
+                            .hint_text("label or label=value")
+                            .desired_width(250.),
+                    );
+                    if ui
+                        .button("select")
+                        .on_hover_text("follow all containers with a matching label")
+                        .clicked()
+                        && !data.label_selector.trim().is_empty()
+                    {
+                        selection = Some(
+                            self.containers
+                                .containers
+                                .iter()
+                                .filter(|c| matches_label(c, &data.label_selector))
+                                .map(|c| (c.id.clone(), container_name(c)))
+                                .collect(),
+                        );
+                    }
+                    if ui.button("clear").clicked() {
+                        selection = Some(vec![]);
+                    }
+                });
+            });
+
+        if let Some(containers) = selection {
+            data.select(containers);
+            let ids = data.ids();
+            self.send_event_notify(EventRequest::Container(ContainerEvent::AggregateLogs {
+                ids,
+            }));
+        }
+    }
+
+    fn aggregated_logs_controls(&mut self, ui: &mut egui::Ui) {
+        let data = &mut self.containers.aggregated_logs_view_data;
+        let max_page = data.lines.len().saturating_sub(1) / PAGE_SIZE;
+
+        ui.horizontal(|ui| {
+            if ui
+                .button(icon::ARROW_LEFT)
+                .on_hover_text("Decrease the page")
+                .clicked()
+                && data.page > 0
+            {
+                data.page -= 1;
+            }
+            ui.add(
+                egui::DragValue::new(&mut data.page)
+                    .clamp_range(0..=max_page)
+                    .fixed_decimals(0)
+                    .speed(1.),
+            );
+            if ui
+                .button(icon::ARROW_RIGHT)
+                .on_hover_text("Increase the page")
+                .clicked()
+                && data.page < max_page
+            {
+                data.page += 1;
+            }
+            ui.checkbox(&mut data.follow, "Follow logs");
+            ui.checkbox(&mut data.timestamps, "Timestamps");
+            ui.label("Max lines:");
+            ui.add(
+                egui::DragValue::new(&mut data.max_lines)
+                    .clamp_range(100..=1_000_000)
+                    .fixed_decimals(0)
+                    .speed(100.),
+            )
+            .on_hover_text("maximum count of lines kept in memory, oldest lines are dropped first");
+        });
+
+        if data.follow {
+            data.page = max_page;
+        }
+        data.page = data.page.min(max_page);
+    }
+
+    fn aggregated_logs_lines(&mut self, ui: &mut egui::Ui) {
+        let (bg, stderr_color) = if ui.visuals().dark_mode {
+            (*color::D_BG_000, Color32::from_rgb(0xf1, 0x4c, 0x4c))
+        } else {
+            (*color::L_BG_4, Color32::from_rgb(0xcd, 0x31, 0x31))
+        };
+        let text_color = ui.visuals().text_color();
+        let weak_color = ui.visuals().weak_text_color();
+        let font_id = TextStyle::Monospace.resolve(ui.style());
+
+        let data = &self.containers.aggregated_logs_view_data;
+        let width = data
+            .containers
+            .iter()
+            .map(|(_, name)| name.len())
+            .max()
+            .unwrap_or_default();
+        let names = data
+            .containers
+            .iter()
+            .map(|(id, name)| (id.as_str(), format!("{:width$} | ", name, width = width)))
+            .collect::<HashMap<_, _>>();
+        let start = data.page * PAGE_SIZE;
+        let end = (start + PAGE_SIZE).min(data.lines.len());
+
+        Frame::none().fill(bg).show(ui, |ui| {
+            ui.allocate_space((ui.available_rect_before_wrap().width(), 0.).into());
+            ui.spacing_mut().item_spacing.y = 0.;
+            for line in data.lines.range(start..end) {
+                let base = TextFormat {
+                    font_id: font_id.clone(),
+                    color: match line.stream {
+                        LogStream::StdOut => text_color,
+                        LogStream::StdErr => stderr_color,
+                    },
+                    ..Default::default()
+                };
+
+                let mut job = egui::text::LayoutJob::default();
+                job.append(
+                    names
+                        .get(line.container.as_str())
+                        .map(String::as_str)
+                        .unwrap_or_default(),
+                    0.,
+                    TextFormat {
+                        color: data.color(&line.container),
+                        ..base.clone()
+                    },
+                );
+                if data.timestamps {
+                    job.append(
+                        &format!("{} ", line.timestamp.to_rfc3339()),
+                        0.,
+                        TextFormat {
+                            color: weak_color,
+                            ..base.clone()
+                        },
+                    );
+                }
+                let rest = ui::ansi::layout_job(&line.text, base);
+                for section in rest.sections {
+                    job.append(&rest.text[section.byte_range], 0., section.format);
+                }
+
+                ui.add(Label::new(job).wrap(true));
+            }
+        });
+    }
+}
@@ -1,19 +1,29 @@
+mod compose;
 mod containers;
+pub mod credentials;
+mod docker_run;
 mod fonts;
 mod images;
+mod jobs;
+mod logs;
 mod networks;
+mod ports;
 pub mod settings;
 mod system;
+mod templates;
+mod topology;
 mod ui;
 mod volumes;
 
 use crate::event::{
     ContainerEvent, ContainerEventResponse, EventRequest, EventResponse, GuiEventResponse,
-    ImageEvent, ImageEventResponse, NetworkEvent, NetworkEventResponse, VolumeEvent,
-    VolumeEventResponse,
+    ImageEvent, ImageEventResponse, JobsEvent, JobsEventResponse, LogsExportStatus, NetworkEvent,
+    NetworkEventResponse, VolumeEvent, VolumeEventResponse,
 };
+use crate::worker::Logs;
 use containers::ContainersTab;
 use images::ImagesTab;
+use jobs::JobsWindow;
 use networks::NetworksTab;
 use settings::{Settings, SettingsWindow};
 use system::SystemTab;
@@ -30,6 +40,21 @@ use tokio::sync::mpsc;
 
 pub const SIDE_PANEL_MIN_WIDTH: f32 = 150.;
 
+/// Joins stdout and stderr of the chunks into a single string with ANSI escape codes removed.
+fn stripped_output(output: Logs) -> String {
+    let raw_bytes = output
+        .0
+        .into_iter()
+        .flat_map(|x| match x {
+            TtyChunk::StdOut(data) => data,
+            TtyChunk::StdErr(data) => data,
+            TtyChunk::StdIn(_) => vec![],
+        })
+        .collect::<Vec<_>>();
+    let escaped_bytes = strip_ansi_escapes::strip(&raw_bytes).unwrap_or(raw_bytes);
+    String::from_utf8_lossy(&escaped_bytes).to_string()
+}
+
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub enum Tab {
     System,
@@ -63,6 +88,10 @@ pub struct Timers {
     pub data_usage: SystemTime,
     pub system_inspect: SystemTime,
     pub events: SystemTime,
+    pub attach: SystemTime,
+    pub exec: SystemTime,
+    pub jobs: SystemTime,
+    pub topology: SystemTime,
 }
 
 impl Timers {
@@ -78,6 +107,10 @@ impl Default for Timers {
             data_usage: SystemTime::UNIX_EPOCH,
             system_inspect: SystemTime::UNIX_EPOCH,
             events: SystemTime::UNIX_EPOCH,
+            attach: SystemTime::UNIX_EPOCH,
+            exec: SystemTime::UNIX_EPOCH,
+            jobs: SystemTime::UNIX_EPOCH,
+            topology: SystemTime::UNIX_EPOCH,
         }
     }
 }
@@ -99,6 +132,7 @@ pub struct App {
     system: SystemTab,
 
     settings_window: SettingsWindow,
+    jobs_window: JobsWindow,
     popups: VecDeque<ui::ActionPopup>,
     timers: Timers,
 }
@@ -135,6 +169,10 @@ impl App {
 
     fn display_windows(&mut self, ctx: &egui::Context) {
         self.settings_window.display(ctx);
+        if std::mem::take(&mut self.settings_window.reload_docker_credentials) {
+            self.send_event_notify(EventRequest::DockerCredentials);
+        }
+        self.display_jobs_window(ctx);
     }
 
     fn top_panel(&mut self, ctx: &egui::Context) {
@@ -170,6 +208,15 @@ impl App {
                         if ui.button(ui::icon::SETTINGS).clicked() {
                             self.settings_window.toggle();
                         }
+                        let active = self.jobs_window.active_count();
+                        let jobs = if active > 0 {
+                            format!("jobs ({})", active)
+                        } else {
+                            "jobs".to_string()
+                        };
+                        if ui.button(jobs).clicked() {
+                            self.jobs_window.toggle();
+                        }
                     });
                 });
             });
@@ -310,6 +357,7 @@ impl App {
                 settings,
                 ..Default::default()
             },
+            jobs_window: JobsWindow::default(),
             popups: VecDeque::new(),
             timers: Timers::default(),
         };
@@ -318,6 +366,21 @@ impl App {
         ))));
         app.send_event_notify(EventRequest::Image(ImageEvent::List(None)));
         app.send_event_notify(EventRequest::Volume(VolumeEvent::List(None)));
+        let templates_path = app
+            .settings_window
+            .settings_path
+            .as_ref()
+            .map(templates::path);
+        match templates::TemplatesData::load(templates_path) {
+            Ok(templates) => app.containers.templates = templates,
+            Err(e) => app.add_error(e),
+        }
+        if let Err(e) = app.settings_window.load_credentials() {
+            app.add_error(e);
+        }
+        if app.settings_window.settings.use_docker_config {
+            app.send_event_notify(EventRequest::DockerCredentials);
+        }
         app
     }
 
@@ -378,11 +441,60 @@ impl App {
             self.timers.data_usage = SystemTime::now();
         }
 
+        if self.jobs_window.active_count() > 0
+            && self.timers.jobs.elapsed().unwrap_or_default().as_millis() > 1000
+        {
+            self.send_event_notify(EventRequest::Jobs(JobsEvent::List));
+            self.timers.jobs = SystemTime::now();
+        }
+
+        if self.containers.attach_view_data.attached
+            && self.timers.attach.elapsed().unwrap_or_default().as_millis() > 100
+        {
+            self.send_event_notify(EventRequest::Container(ContainerEvent::AttachOutput));
+            self.timers.attach = SystemTime::now();
+        }
+
+        if self.containers.exec_view_data.running
+            && self.timers.exec.elapsed().unwrap_or_default().as_millis() > 100
+        {
+            self.send_event_notify(EventRequest::Container(ContainerEvent::ExecOutput));
+            self.timers.exec = SystemTime::now();
+        }
+
+        // the networks to inspect are taken from the periodically refreshed list of networks
+        if self.current_tab == Tab::Networks
+            && self.networks.central_view == networks::CentralView::Topology
+            && !self.networks.networks.is_empty()
+            && self.timers.topology.elapsed().unwrap_or_default().as_secs() > 5
+        {
+            let ids = self
+                .networks
+                .networks
+                .iter()
+                .map(|network| network.id.clone())
+                .collect();
+            self.send_event_notify(EventRequest::Network(NetworkEvent::Topology { ids }));
+            // the state of containers is displayed in the graph
+            self.send_event_notify(EventRequest::Container(ContainerEvent::List(Some(
+                ContainerListOpts::builder().all(true).build(),
+            ))));
+            self.timers.topology = SystemTime::now();
+        }
+
         match self.current_tab {
             Tab::Containers if elapsed > 1000 => {
                 self.send_event_notify(EventRequest::Container(ContainerEvent::List(Some(
                     ContainerListOpts::builder().all(true).build(),
                 ))));
+                if !self
+                    .containers
+                    .aggregated_logs_view_data
+                    .containers
+                    .is_empty()
+                {
+                    self.send_event_notify(EventRequest::Container(ContainerEvent::AggregatedLogs));
+                }
                 if self.containers.current_container.is_some() {
                     self.send_event_notify(EventRequest::Container(ContainerEvent::Details));
                     self.send_event_notify(EventRequest::Container(ContainerEvent::Logs));
@@ -403,11 +515,6 @@ impl App {
             }
             Tab::Images if elapsed > 1000 => {
                 self.send_event_notify(EventRequest::Image(ImageEvent::List(None)));
-                if self.images.pull_view_data.in_progress
-                    || self.images.search_view_data.pull_in_progress
-                {
-                    self.send_event_notify(EventRequest::Image(ImageEvent::PullChunks));
-                }
                 let id = self
                     .images
                     .current_image
@@ -420,6 +527,15 @@ impl App {
             }
             Tab::Networks if elapsed > 1000 => {
                 self.send_event_notify(EventRequest::Network(NetworkEvent::List(None)));
+                let id = self
+                    .networks
+                    .current_network
+                    .as_ref()
+                    .filter(|_| self.networks.central_view == networks::CentralView::Network)
+                    .map(|n| n.id.clone());
+                if let Some(id) = id {
+                    self.send_event_notify(EventRequest::Network(NetworkEvent::Inspect { id }));
+                }
                 self.timers.update_time = SystemTime::now();
             }
             Tab::Volumes if elapsed > 1000 => {
@@ -438,6 +554,20 @@ impl App {
                 EventResponse::Image(event) => self.handle_image_event_response(event),
                 EventResponse::Network(event) => self.handle_network_event_response(event),
                 EventResponse::Volume(event) => self.handle_volume_event_response(event),
+                EventResponse::Jobs(JobsEventResponse::List(jobs)) => self.jobs_window.update(jobs),
+                EventResponse::DockerCredentials(res) => match res {
+                    Ok(docker_credentials) => {
+                        // the option could have been disabled while the credentials were read
+                        if self.settings_window.settings.use_docker_config {
+                            self.settings_window.settings.docker_credentials =
+                                docker_credentials.credentials;
+                        }
+                        for error in docker_credentials.errors {
+                            self.add_error(error);
+                        }
+                    }
+                    Err(e) => self.add_error(e),
+                },
                 EventResponse::DockerUriChange(res) => match res {
                     Ok(()) => {
                         self.clear_all();
@@ -528,25 +658,29 @@ impl App {
                     self.containers.current_stats = Some(new_stats)
                 }
             }
-            Logs(logs) => {
-                let raw_bytes = logs
-                    .0
-                    .clone()
-                    .into_iter()
-                    .flat_map(|x| match x {
-                        TtyChunk::StdOut(data) => data,
-                        TtyChunk::StdErr(data) => data,
-                        TtyChunk::StdIn(_) => unreachable!(),
-                    })
-                    .collect::<Vec<_>>();
-                let escaped_bytes = strip_ansi_escapes::strip(&raw_bytes).unwrap_or(raw_bytes);
-                let logs = String::from_utf8_lossy(&escaped_bytes);
-                if let Some(current_logs) = &mut self.containers.logs_view_data.current_logs {
-                    current_logs.push_str(&logs);
-                } else {
-                    self.containers.logs_view_data.current_logs = Some(logs.to_string());
+            Logs(logs) => self.containers.logs_view_data.push_chunks(logs.0),
+            LogsConfigured => self.containers.logs_view_data.clear(),
+            AggregatedLogs(logs) => self.containers.aggregated_logs_view_data.push_logs(logs),
+            ExportLogs(status) => match status {
+                LogsExportStatus::InProgress { lines } => {
+                    self.containers.logs_view_data.export_progress = Some(lines)
                 }
-            }
+                LogsExportStatus::Cancelled => {
+                    self.containers.logs_view_data.export_progress = None;
+                    self.add_notification("export of logs cancelled");
+                }
+                LogsExportStatus::Finished(res) => {
+                    self.containers.logs_view_data.export_progress = None;
+                    match res {
+                        Ok((id, path)) => self.add_notification(format!(
+                            "successfully saved logs of container {} to `{}`",
+                            id,
+                            path.display()
+                        )),
+                        Err(e) => self.add_error(e),
+                    }
+                }
+            },
             Start(res) | Stop(res) | Pause(res) | Unpause(res) => {
                 if let Err(e) = res {
                     self.add_error(e);
@@ -560,6 +694,82 @@ impl App {
                 Ok(_) => self.add_notification("successfully renamed the container"),
                 Err(e) => self.add_error(e),
             },
+            Export(res) => match res {
+                Ok((id, path)) => self.add_notification(format!(
+                    "successfully exported container {} to tar archive in `{}`",
+                    id,
+                    path.display()
+                )),
+                Err(e) => self.add_error(e),
+            },
+            ListDir { path, result } => {
+                let data = &mut self.containers.files_view_data;
+                data.loading = false;
+                // the directory could change while it was listed
+                if data.path == path {
+                    match result {
+                        Ok(listing) => {
+                            data.entries = Some(listing.entries);
+                            data.truncated = listing.truncated;
+                        }
+                        Err(e) => {
+                            data.entries = Some(vec![]);
+                            data.error = Some(format!("{:#}", e));
+                        }
+                    }
+                }
+            }
+            FilePreview { path, result } => match result {
+                Ok(bytes) => {
+                    self.containers.files_view_data.preview =
+                        Some(containers::FilePreview::new(path, bytes))
+                }
+                Err(e) => self.add_error(e),
+            },
+            CopyFrom(res) => match res {
+                Ok(path) => self.add_notification(format!(
+                    "successfully copied from the container to `{}`",
+                    path.display()
+                )),
+                Err(e) => self.add_error(e),
+            },
+            CopyTo(res) => match res {
+                Ok(path) => {
+                    self.add_notification(format!(
+                        "successfully copied the file to `{}` in the container",
+                        path
+                    ));
+                    self.containers.files_view_data.refresh();
+                }
+                Err(e) => self.add_error(e),
+            },
+            Recreate(res) => match res {
+                Ok(id) => {
+                    self.add_notification(format!("successfully recreated container {}", id));
+                    self.send_event_notify(EventRequest::Container(ContainerEvent::TraceStart {
+                        id,
+                    }));
+                    self.containers.central_view = containers::CentralView::Container;
+                }
+                Err(e) => self.add_error(e),
+            },
+            Update(res) => match res {
+                Ok(id) => {
+                    self.add_notification(format!("successfully updated container {}", id));
+                    self.send_event_notify(EventRequest::Container(ContainerEvent::Details));
+                }
+                Err(e) => self.add_error(e),
+            },
+            Commit(res) => match res {
+                Ok(id) => {
+                    self.add_notification(format!(
+                        "successfully committed the container to image {}",
+                        id
+                    ));
+                    self.send_event_notify(EventRequest::Image(ImageEvent::List(None)));
+                }
+                Err(e) => self.add_error(e),
+            },
             Prune(res) => match res {
                 Ok(info) => {
                     if let Some(deleted) = info.containers_deleted {
@@ -608,6 +818,32 @@ impl App {
                 }
                 Err(e) => self.add_error(e),
             },
+            AttachOutput(res) => match res {
+                Ok(output) => self
+                    .containers
+                    .attach_view_data
+                    .output
+                    .push_str(&stripped_output(*output)),
+                Err(e) => {
+                    self.containers.attach_view_data.attached = false;
+                    self.add_error(e);
+                }
+            },
+            Detached => {
+                if self.containers.attach_view_data.attached {
+                    self.containers.attach_view_data.attached = false;
+                    self.add_notification("detached from the container");
+                }
+            }
+            ExecOutput(res) => match res {
+                Ok(output) => self
+                    .containers
+                    .exec_view_data
+                    .output
+                    .push_str(&stripped_output(*output)),
+                Err(e) => self.add_error(e),
+            },
+            ExecFinished => self.containers.exec_view_data.running = false,
         }
     }
 
@@ -662,20 +898,20 @@ impl App {
                 )),
                 Err(e) => self.add_error(e),
             },
-            Pull(res) => match res {
-                Ok(id) => {
-                    self.images.pull_view_data.in_progress = false;
-                    self.add_notification(format!("successfully pulled image {}", id,))
-                }
+            Pull { image, result } => match result {
+                Ok(_) => self.add_notification(format!("successfully pulled image {}", image)),
                 Err(e) => self.add_error(e),
             },
-            PullChunks(new_chunks) => {
-                if let Some(chunks) = &mut self.images.current_pull_chunks {
-                    chunks.extend(new_chunks);
-                } else {
-                    self.images.current_pull_chunks = Some(new_chunks);
+            Build(res) => match res {
+                Ok(id) => self.add_notification(format!("successfully built image {}", id)),
+                Err(e) => self.add_error(e),
+            },
+            Push(res) => match res {
+                Ok(reference) => {
+                    self.add_notification(format!("successfully pushed image {}", reference))
                 }
-            }
+                Err(e) => self.add_error(e),
+            },
             Search(res) => match res {
                 Ok(mut results) => {
                     results.sort_by(|a, b| b.star_count.cmp(&a.star_count));
@@ -767,6 +1003,37 @@ impl App {
                 });
                 self.networks.networks = networks;
             }
+            Topology(res) => match res {
+                Ok(mut networks) => {
+                    networks.sort_by(|a, b| a.name.cmp(&b.name));
+                    self.networks.topology = networks;
+                    self.networks.topology_error = None;
+                }
+                Err(e) => {
+                    // the topology is polled so the same error is only reported once
+                    let error = format!("{:#}", e);
+                    if self.networks.topology_error.as_ref() != Some(&error) {
+                        self.add_error(e);
+                        self.networks.topology_error = Some(error);
+                    }
+                }
+            },
+            Inspect(res) => match res {
+                // a response of a network that is no longer displayed is ignored
+                Ok(network) => {
+                    if let Some(current) = self.networks.current_network.as_mut() {
+                        if current.id == network.id {
+                            *current = *network;
+                        }
+                    }
+                }
+                Err(e) => {
+                    // the network was most likely deleted, stop inspecting it
+                    self.networks.current_network = None;
+                    self.networks.central_view = networks::CentralView::None;
+                    self.add_error(e);
+                }
+            },
             Prune(res) => match res {
                 Ok(info) => {
                     let status = info.networks_deleted.into_iter().fold(
@@ -786,6 +1053,26 @@ impl App {
                 Ok(id) => self.add_notification(format!("successfully created network {}", id)),
                 Err(e) => self.add_error(e),
             },
+            Connect(res) => match res {
+                Ok((network, container)) => {
+                    self.add_notification(format!(
+                        "successfully connected container {} to network {}",
+                        container, network
+                    ));
+                    self.refresh_network_connection(&container);
+                }
+                Err(e) => self.add_error(e),
+            },
+            Disconnect(res) => match res {
+                Ok((network, container)) => {
+                    self.add_notification(format!(
+                        "successfully disconnected container {} from network {}",
+                        container, network
+                    ));
+                    self.refresh_network_connection(&container);
+                }
+                Err(e) => self.add_error(e),
+            },
         }
     }
 
@@ -913,7 +1200,9 @@ impl App {
             if !popup.is_finished() {
                 self.popups.push_back(popup);
             } else if popup.is_confirmed() {
-                self.send_event_notify(popup.action());
+                for action in popup.actions() {
+                    self.send_event_notify(action);
+                }
             }
         }
     }
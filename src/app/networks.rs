@@ -4,10 +4,12 @@ use crate::app::{
     ui::{key, key_val, val},
     App,
 };
-use crate::event::{EventRequest, NetworkEvent};
+use crate::event::{ContainerEvent, EventRequest, NetworkEvent};
 use crate::format_date;
 
-use docker_api::api::{Ipam, NetworkCreateOpts, NetworkInfo};
+use anyhow::{Error, Result};
+use docker_api::api::{ContainerIdRef, Ipam, NetworkCreateOpts, NetworkInfo};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
 use egui::{style::Margin, Grid, Label, RichText};
 
@@ -15,10 +17,80 @@ pub fn icon() -> Label {
     Label::new(RichText::new(icon::NETWORK).heading().strong())
 }
 
+/// Parses an address in CIDR notation like `172.18.0.2/16`, without a prefix length the address
+/// is treated as a single host.
+fn parse_cidr(cidr: &str) -> Option<(IpAddr, u32)> {
+    let (addr, len) = match cidr.trim().split_once('/') {
+        Some((addr, len)) => (addr, Some(len)),
+        None => (cidr.trim(), None),
+    };
+    let addr = addr.parse::<IpAddr>().ok()?;
+    let max = if addr.is_ipv4() { 32 } else { 128 };
+    let len = match len {
+        Some(len) => len.parse().ok().filter(|len| *len <= max)?,
+        None => max,
+    };
+    Some((addr, len))
+}
+
+fn in_subnet(addr: IpAddr, (subnet, len): (IpAddr, u32)) -> bool {
+    match (addr, subnet) {
+        (IpAddr::V4(addr), IpAddr::V4(subnet)) => {
+            let mask = u32::MAX.checked_shl(32 - len).unwrap_or_default();
+            u32::from(addr) & mask == u32::from(subnet) & mask
+        }
+        (IpAddr::V6(addr), IpAddr::V6(subnet)) => {
+            let mask = u128::MAX.checked_shl(128 - len).unwrap_or_default();
+            u128::from(addr) & mask == u128::from(subnet) & mask
+        }
+        _ => false,
+    }
+}
+
+/// Returns the number of addresses of a subnet that can be assigned, the network and broadcast
+/// addresses of IPv4 subnets are reserved. `None` if the number doesn't fit.
+fn subnet_capacity((subnet, len): (IpAddr, u32)) -> Option<u128> {
+    match subnet {
+        IpAddr::V4(_) => Some((1u128 << (32 - len)).saturating_sub(2)),
+        IpAddr::V6(_) => 1u128.checked_shl(128 - len).filter(|_| len > 0),
+    }
+}
+
+/// Displays which addresses of `subnet` are assigned to the gateway and containers.
+fn subnet_usage(
+    ui: &mut egui::Ui,
+    subnet: (IpAddr, u32),
+    gateway: Option<IpAddr>,
+    addresses: &[(IpAddr, String)],
+) {
+    let gateway = gateway.map(|addr| (addr, "gateway".to_string()));
+    let used = gateway
+        .iter()
+        .chain(addresses)
+        .filter(|(addr, _)| in_subnet(*addr, subnet))
+        .collect::<Vec<_>>();
+    val!(ui, "In use");
+    match subnet_capacity(subnet) {
+        Some(capacity) => {
+            val!(ui, format!("{} of {}", used.len(), capacity));
+        }
+        None => {
+            val!(ui, used.len().to_string());
+        }
+    }
+    ui.end_row();
+    for (addr, name) in used {
+        ui.scope(|_| {});
+        val!(ui, format!("{} - {}", addr, name));
+        ui.end_row();
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum CentralView {
     Network,
     Create,
+    Topology,
     None,
 }
 
@@ -61,12 +133,99 @@ impl Default for CreateViewData {
     }
 }
 
+/// Window that connects a container to a network or disconnects it.
+#[derive(Debug, Default)]
+pub struct ConnectWindow {
+    pub show: bool,
+    pub network: String,
+    pub container: String,
+    /// Whether the network is picked in the window, otherwise the container is
+    pub pick_network: bool,
+    pub aliases: String,
+    pub ipv4: String,
+    pub ipv6: String,
+    pub force: bool,
+}
+
+impl ConnectWindow {
+    pub fn toggle(&mut self) {
+        self.show = !self.show;
+    }
+
+    /// Opens the window for network `id` letting the user pick a container.
+    pub fn open_for_network(&mut self, id: String) {
+        *self = Self {
+            show: true,
+            network: id,
+            ..Default::default()
+        };
+    }
+
+    /// Opens the window for container `id` letting the user pick a network.
+    pub fn open_for_container(&mut self, id: String) {
+        *self = Self {
+            show: true,
+            container: id,
+            pick_network: true,
+            ..Default::default()
+        };
+    }
+
+    fn check_selection(&self) -> Result<()> {
+        if self.network.is_empty() {
+            return Err(Error::msg("no network selected"));
+        }
+        if self.container.is_empty() {
+            return Err(Error::msg("no container selected"));
+        }
+        Ok(())
+    }
+
+    fn connect_event(&self) -> Result<NetworkEvent> {
+        self.check_selection()?;
+        let ipv4 = self.ipv4.trim();
+        if !ipv4.is_empty() && ipv4.parse::<Ipv4Addr>().is_err() {
+            return Err(Error::msg(format!("invalid IPv4 address `{}`", ipv4)));
+        }
+        let ipv6 = self.ipv6.trim();
+        if !ipv6.is_empty() && ipv6.parse::<Ipv6Addr>().is_err() {
+            return Err(Error::msg(format!("invalid IPv6 address `{}`", ipv6)));
+        }
+        Ok(NetworkEvent::Connect {
+            network: self.network.clone(),
+            container: self.container.clone(),
+            aliases: self
+                .aliases
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|alias| !alias.is_empty())
+                .map(str::to_string)
+                .collect(),
+            ipv4: Some(ipv4.to_string()).filter(|ip| !ip.is_empty()),
+            ipv6: Some(ipv6.to_string()).filter(|ip| !ip.is_empty()),
+        })
+    }
+
+    fn disconnect_event(&self) -> Result<NetworkEvent> {
+        self.check_selection()?;
+        Ok(NetworkEvent::Disconnect {
+            network: self.network.clone(),
+            container: self.container.clone(),
+            force: self.force,
+        })
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct NetworksTab {
     pub networks: Vec<NetworkInfo>,
     pub current_network: Option<NetworkInfo>,
     pub central_view: CentralView,
     pub create_view_data: CreateViewData,
+    pub connect_window: ConnectWindow,
+    /// Inspected networks displayed in the topology view
+    pub topology: Vec<NetworkInfo>,
+    /// Error of the last inspection of the networks of the topology view
+    pub topology_error: Option<String>,
 }
 
 impl App {
@@ -74,8 +233,10 @@ impl App {
         match self.networks.central_view {
             CentralView::Network => self.network_details(ui),
             CentralView::Create => self.network_create(ui),
+            CentralView::Topology => self.network_topology(ui),
             CentralView::None => {}
         }
+        self.display_connect_window(ui);
     }
 
     pub fn networks_side(&mut self, ui: &mut egui::Ui) {
@@ -97,6 +258,12 @@ impl App {
                 CentralView::Create,
                 "create",
             );
+            ui.selectable_value(
+                &mut self.networks.central_view,
+                CentralView::Topology,
+                "topology",
+            )
+            .on_hover_text("display a graph of networks and attached containers");
         });
         egui::Grid::new("networks_button_grid").show(ui, |ui| {
             if ui.button("prune").clicked() {
@@ -111,6 +278,7 @@ impl App {
 
     fn networks_scroll(&mut self, ui: &mut egui::Ui) {
         let mut view = self.networks.central_view;
+        let mut inspect = None;
         egui::ScrollArea::vertical().show(ui, |ui| {
             ui.wrap_text();
             egui::Grid::new("side_panel")
@@ -178,6 +346,7 @@ impl App {
                                             {
                                                 self.networks.current_network = Some(network.clone());
                                                 view = CentralView::Network;
+                                                inspect = Some(network.id.clone());
                                             }
                                             if ui
                                                 .button(icon::DELETE)
@@ -211,6 +380,9 @@ impl App {
                 });
         });
         self.networks.central_view = view;
+        if let Some(id) = inspect {
+            self.send_event_notify(EventRequest::Network(NetworkEvent::Inspect { id }));
+        }
     }
 
     fn network_details(&mut self, ui: &mut egui::Ui) {
@@ -222,9 +394,40 @@ impl App {
                 &network.id
             };
 
+            let mut connect = None;
+            let mut popup = None;
+            // addresses of attached containers along with their names
+            let mut addresses = vec![];
+            let mut containers = network
+                .containers
+                .iter()
+                .flatten()
+                .map(|(id, container)| {
+                    let name = container.name.clone().unwrap_or_else(|| id.clone());
+                    for addr in [&container.ipv4_address, &container.ipv6_address]
+                        .into_iter()
+                        .flatten()
+                    {
+                        if let Some((addr, _)) = parse_cidr(addr) {
+                            addresses.push((addr, name.clone()));
+                        }
+                    }
+                    (id, name, container)
+                })
+                .collect::<Vec<_>>();
+            containers.sort_by(|a, b| a.1.cmp(&b.1));
+            addresses.sort();
+
             ui.horizontal(|ui| {
                 ui.add(icon());
                 ui.add(Label::new(RichText::new(name).heading().strong()).wrap(true));
+                if ui
+                    .button("connect")
+                    .on_hover_text("connect a container to the network or disconnect it")
+                    .clicked()
+                {
+                    connect = Some(network.id.clone());
+                }
             });
             ui.add_space(25.);
 
@@ -262,6 +465,53 @@ impl App {
                 if let Some(attachable) = &network.attachable {
                     key_val!(ui, "Attachable:", attachable);
                 }
+                key!(ui, "Containers:");
+                ui.end_row();
+                if !containers.is_empty() {
+                    ui.label("          ");
+                    Grid::new("network_containers_grid")
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("Name");
+                            ui.strong("IPv4");
+                            ui.strong("IPv6");
+                            ui.strong("MAC address");
+                            ui.end_row();
+                            for (id, container_name, container) in &containers {
+                                self.link_container(ui, id, Some(container_name));
+                                for field in [
+                                    &container.ipv4_address,
+                                    &container.ipv6_address,
+                                    &container.mac_address,
+                                ] {
+                                    val!(ui, field.as_deref().unwrap_or_default());
+                                }
+                                if ui
+                                    .button("disconnect")
+                                    .on_hover_text("disconnect the container from the network")
+                                    .clicked()
+                                {
+                                    popup = Some(
+                                        ui::ActionPopup::builder(EventRequest::Network(
+                                            NetworkEvent::Disconnect {
+                                                network: network.id.clone(),
+                                                container: id.to_string(),
+                                                force: false,
+                                            },
+                                        ))
+                                        .title("Disconnect container")
+                                        .text(format!(
+                                            "Are you sure you want to disconnect container {} from network {}?",
+                                            container_name, name
+                                        ))
+                                        .build(),
+                                    );
+                                }
+                                ui.end_row();
+                            }
+                        });
+                    ui.end_row();
+                }
                 if let Some(ipam) = &network.ipam {
                     key!(ui, "IPAM:");
                     egui::CollapsingHeader::new("")
@@ -279,6 +529,12 @@ impl App {
                                         ui.label("          ");
                                         Grid::new("ipam_config_grid").show(ui, |ui| {
                                             for config in configs {
+                                                let subnet =
+                                                    config.get("Subnet").and_then(|s| parse_cidr(s));
+                                                let gateway = config
+                                                    .get("Gateway")
+                                                    .and_then(|g| parse_cidr(g))
+                                                    .map(|(addr, _)| addr);
                                                 let mut config = config.iter().collect::<Vec<_>>();
                                                 config.sort();
                                                 for (k, v) in config {
@@ -286,6 +542,11 @@ impl App {
                                                     val!(ui, &v);
                                                     ui.end_row();
                                                 }
+                                                if let Some(subnet) = subnet {
+                                                    subnet_usage(ui, subnet, gateway, &addresses);
+                                                }
+                                                ui.separator();
+                                                ui.end_row();
                                             }
                                         });
                                         ui.end_row();
@@ -330,6 +591,142 @@ impl App {
                     }
                 }
             });
+
+            if let Some(id) = connect {
+                self.networks.connect_window.open_for_network(id);
+            }
+            if let Some(popup) = popup {
+                self.popups.push_back(popup);
+            }
+        }
+    }
+
+    /// Displays the window that connects containers to networks, it's shared by the network and
+    /// container details.
+    pub fn display_connect_window(&mut self, ui: &mut egui::Ui) {
+        if !self.networks.connect_window.show {
+            return;
+        }
+        let mut event = None;
+        egui::Window::new("Network connection").show(ui.ctx(), |ui| {
+            let window = &mut self.networks.connect_window;
+            Grid::new("connect_window_grid").show(ui, |ui| {
+                key!(ui, "Network:");
+                let network_name = |id: &str| {
+                    self.networks
+                        .networks
+                        .iter()
+                        .find(|n| n.id == id)
+                        .and_then(|n| n.name.clone())
+                        .unwrap_or_else(|| id.to_string())
+                };
+                if window.pick_network {
+                    egui::ComboBox::from_id_source("connect_window_network")
+                        .selected_text(network_name(&window.network))
+                        .show_ui(ui, |ui| {
+                            for network in &self.networks.networks {
+                                ui.selectable_value(
+                                    &mut window.network,
+                                    network.id.clone(),
+                                    network.name.as_deref().unwrap_or(&network.id),
+                                );
+                            }
+                        });
+                } else {
+                    val!(ui, network_name(&window.network));
+                }
+                ui.end_row();
+
+                key!(ui, "Container:");
+                let container_name = |id: &str| {
+                    self.containers
+                        .containers
+                        .iter()
+                        .find(|c| c.id == id)
+                        .and_then(|c| c.names.first())
+                        .map(|name| name.trim_start_matches('/').to_string())
+                        .unwrap_or_else(|| id.to_string())
+                };
+                if window.pick_network {
+                    val!(ui, container_name(&window.container));
+                } else {
+                    egui::ComboBox::from_id_source("connect_window_container")
+                        .selected_text(container_name(&window.container))
+                        .show_ui(ui, |ui| {
+                            for container in &self.containers.containers {
+                                ui.selectable_value(
+                                    &mut window.container,
+                                    container.id.clone(),
+                                    container_name(&container.id),
+                                );
+                            }
+                        });
+                }
+                ui.end_row();
+            });
+
+            ui.add_space(5.);
+            ui.separator();
+            Grid::new("connect_window_connect_grid").show(ui, |ui| {
+                key!(ui, "Aliases:");
+                ui.text_edit_singleline(&mut window.aliases)
+                    .on_hover_text("network scoped aliases separated by commas or spaces");
+                ui.end_row();
+                key!(ui, "IPv4 address:");
+                ui.text_edit_singleline(&mut window.ipv4)
+                    .on_hover_text("leave empty to assign an address automatically");
+                ui.end_row();
+                key!(ui, "IPv6 address:");
+                ui.text_edit_singleline(&mut window.ipv6)
+                    .on_hover_text("leave empty to assign an address automatically");
+                ui.end_row();
+            });
+            if ui.button("connect").clicked() {
+                event = Some(window.connect_event());
+            }
+
+            ui.add_space(5.);
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button("disconnect").clicked() {
+                    event = Some(window.disconnect_event());
+                }
+                ui.checkbox(&mut window.force, "Force")
+                    .on_hover_text("force the container to disconnect from the network");
+            });
+
+            ui.add_space(5.);
+            ui.separator();
+            if ui.button("close").clicked() {
+                window.toggle();
+            }
+        });
+
+        match event {
+            Some(Ok(event)) => {
+                self.send_event_notify(EventRequest::Network(event));
+                self.networks.connect_window.toggle();
+            }
+            Some(Err(e)) => self.add_error(e),
+            None => {}
+        }
+    }
+
+    /// Refreshes the views that display connections of container `id` to networks.
+    pub fn refresh_network_connection(&mut self, id: ContainerIdRef) {
+        self.send_event_notify(EventRequest::Network(NetworkEvent::List(None)));
+        if let Some(network) = &self.networks.current_network {
+            let id = network.id.clone();
+            self.send_event_notify(EventRequest::Network(NetworkEvent::Inspect { id }));
+        }
+        if self
+            .containers
+            .current_container
+            .as_ref()
+            .map(|c| c.id == id)
+            .unwrap_or_default()
+        {
+            self.send_event_notify(EventRequest::Container(ContainerEvent::Details));
         }
     }
 
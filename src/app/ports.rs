@@ -0,0 +1,202 @@
+//! Overview of the ports published by containers.
+
+use crate::app::App;
+
+use docker_api::api::{ContainerInfo, PortBinding};
+use egui::{Color32, RichText};
+use std::collections::BTreeMap;
+
+/// Whether a host IP binds all addresses of the host.
+fn is_unspecified(host_ip: &str) -> bool {
+    matches!(host_ip, "" | "0.0.0.0" | "::")
+}
+
+/// Whether two bindings of the same host port would clash.
+fn ips_overlap(a: &str, b: &str) -> bool {
+    a == b || is_unspecified(a) || is_unspecified(b)
+}
+
+/// Returns the address a port bound on `host_ip` is reachable at from this machine.
+pub fn port_url(host_ip: &str, host_port: &str, port: u64) -> String {
+    let host = if is_unspecified(host_ip) {
+        "localhost".to_string()
+    } else if host_ip.contains(':') {
+        format!("[{}]", host_ip)
+    } else {
+        host_ip.to_string()
+    };
+    let scheme = if matches!(port, 443 | 8443) {
+        "https"
+    } else {
+        "http"
+    };
+    format!("{}://{}:{}", scheme, host, host_port)
+}
+
+/// Displays a `host_ip:host_port -> port/proto` row with a link opening TCP ports in the browser.
+pub fn port_binding(ui: &mut egui::Ui, port: &str, binding: &PortBinding) {
+    let host_ip = if binding.host_ip.is_empty() {
+        "0.0.0.0"
+    } else {
+        binding.host_ip.as_str()
+    };
+    ui.label(format!("{}:{} -> {}", host_ip, binding.host_port, port));
+    match port.split_once('/') {
+        Some((private_port, "tcp")) => {
+            let url = port_url(
+                &binding.host_ip,
+                &binding.host_port,
+                private_port.parse().unwrap_or_default(),
+            );
+            ui.hyperlink_to("open", &url)
+                .on_hover_text(format!("open {} in the browser", url));
+        }
+        _ => {
+            ui.scope(|_| {});
+        }
+    }
+}
+
+/// A host port published by a container.
+struct PublishedPort<'a> {
+    host_ip: &'a str,
+    private_port: u64,
+    container: &'a ContainerInfo,
+}
+
+/// Groups published ports of all containers by host port and protocol.
+fn published_ports(containers: &[ContainerInfo]) -> BTreeMap<(u64, &str), Vec<PublishedPort<'_>>> {
+    let mut ports = BTreeMap::<_, Vec<PublishedPort>>::new();
+    for container in containers {
+        for port in &container.ports {
+            let public_port = match port.public_port {
+                Some(public_port) => public_port,
+                None => continue,
+            };
+            let host_ip = port.ip.as_deref().unwrap_or_default();
+            let published = ports.entry((public_port, port.typ.as_str())).or_default();
+            // ports bound to all IPv4 and IPv6 addresses are listed twice
+            if published.iter().any(|p| {
+                p.container.id == container.id
+                    && p.private_port == port.private_port
+                    && is_unspecified(p.host_ip)
+                    && is_unspecified(host_ip)
+            }) {
+                continue;
+            }
+            published.push(PublishedPort {
+                host_ip,
+                private_port: port.private_port,
+                container,
+            });
+        }
+    }
+    ports
+}
+
+/// Whether the port is also published by a different container on an overlapping address.
+fn is_conflict(port: &PublishedPort, others: &[PublishedPort]) -> bool {
+    others
+        .iter()
+        .any(|p| p.container.id != port.container.id && ips_overlap(p.host_ip, port.host_ip))
+}
+
+/// Returns the host ports of `ports` in the form `(container, host)` that are already published
+/// by a container other than `exclude`.
+pub fn ports_in_use<'a>(
+    containers: &[ContainerInfo],
+    ports: &'a [(String, String)],
+    exclude: Option<&str>,
+) -> Vec<&'a str> {
+    let mut published = published_ports(containers);
+    published.retain(|_, ports| {
+        ports.retain(|p| Some(p.container.id.as_str()) != exclude);
+        !ports.is_empty()
+    });
+    ports
+        .iter()
+        .filter_map(|(port, host_port)| {
+            let proto = port
+                .split_once('/')
+                .map(|(_, proto)| proto)
+                .unwrap_or("tcp");
+            let host_port = host_port.trim();
+            let in_use = host_port
+                .parse::<u64>()
+                .map(|p| published.contains_key(&(p, proto)))
+                .unwrap_or_default();
+            if in_use {
+                Some(host_port)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+impl App {
+    pub fn ports_overview(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Ports in use");
+        ui.add_space(10.);
+
+        let published = published_ports(&self.containers.containers);
+        if published.is_empty() {
+            ui.label("No container publishes any ports.");
+            return;
+        }
+        let conflicts = published
+            .values()
+            .flat_map(|ports| ports.iter().filter(|p| is_conflict(p, ports)))
+            .count();
+        if conflicts > 0 {
+            ui.label(
+                RichText::new(format!("{} conflicting bindings", conflicts)).color(Color32::RED),
+            );
+            ui.add_space(5.);
+        }
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("ports_overview_grid")
+                .striped(true)
+                .spacing((15., 5.))
+                .show(ui, |ui| {
+                    ui.strong("Host port");
+                    ui.strong("Host IP");
+                    ui.strong("Container port");
+                    ui.strong("Container");
+                    ui.end_row();
+
+                    for ((public_port, proto), ports) in &published {
+                        for port in ports {
+                            let mut host_port = RichText::new(format!("{}/{}", public_port, proto));
+                            if is_conflict(port, ports) {
+                                host_port = host_port.color(Color32::RED);
+                            }
+                            ui.label(host_port);
+                            ui.label(if is_unspecified(port.host_ip) {
+                                "all"
+                            } else {
+                                port.host_ip
+                            });
+                            ui.label(format!("{}/{}", port.private_port, proto));
+                            self.link_container(
+                                ui,
+                                &port.container.id,
+                                port.container.names.first().map(|n| n.as_str()),
+                            );
+                            if *proto == "tcp" {
+                                let url = port_url(
+                                    port.host_ip,
+                                    &public_port.to_string(),
+                                    port.private_port,
+                                );
+                                ui.hyperlink_to("open", &url)
+                                    .on_hover_text(format!("open {} in the browser", url));
+                            }
+                            ui.end_row();
+                        }
+                    }
+                });
+        });
+    }
+}
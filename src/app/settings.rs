@@ -1,3 +1,4 @@
+use crate::app::credentials::{self, Credential};
 use crate::app::fonts::FontSizes;
 
 use anyhow::{Context, Result};
@@ -18,6 +19,19 @@ pub struct Settings {
     pub docker_addr: String,
     pub fonts: FontSizes,
     pub use_docker_host_env: bool,
+    /// Whether to also use the credentials from the docker CLI configuration file
+    #[serde(default)]
+    pub use_docker_config: bool,
+    /// Registry credentials saved in a separate file next to the settings
+    #[serde(skip)]
+    pub credentials: Vec<Credential>,
+    /// Set when the saved credentials failed to load, they aren't saved until they are edited so
+    /// that the credentials file isn't overwritten with an empty list
+    #[serde(skip)]
+    pub credentials_load_failed: bool,
+    /// Registry credentials read from the docker CLI configuration, these are never saved
+    #[serde(skip)]
+    pub docker_credentials: Vec<Credential>,
 }
 
 impl Default for Settings {
@@ -26,22 +40,45 @@ impl Default for Settings {
             docker_addr: crate::DEFAULT_DOCKER_ADDR.to_string(),
             fonts: FontSizes::default(),
             use_docker_host_env: false,
+            use_docker_config: false,
+            credentials: vec![],
+            credentials_load_failed: false,
+            docker_credentials: vec![],
         }
     }
 }
 
 impl Settings {
     /// Loads the settings from the configuration file located at `path`. The configuration file is
-    /// expected to be a valid YAML file.
+    /// expected to be a valid YAML file. Registry credentials are stored in a separate file and
+    /// loaded with [`SettingsWindow::load_credentials`].
     pub fn load(path: impl AsRef<Path>) -> Result<Self> {
         let data = fs::read(path).context("failed to read configuration file")?;
         serde_yaml::from_slice(&data).context("failed to deserialize configuration")
     }
 
-    /// Saves this settings as YAML file in the provided `path`.
+    /// Saves this settings as YAML file in the provided `path`. Registry credentials are saved
+    /// to a separate file only readable by the current user unless they failed to load.
     pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
         let data = serde_yaml::to_vec(&self).context("failed to serialize settings")?;
-        fs::write(path, &data).context("failed to write settings to file")
+        fs::write(path, &data).context("failed to write settings to file")?;
+        if self.credentials_load_failed {
+            return Ok(());
+        }
+        credentials::save(&self.credentials, credentials::path(path))
+    }
+
+    /// All available registry credentials, saved ones first.
+    pub fn all_credentials(&self) -> impl Iterator<Item = &Credential> {
+        self.credentials
+            .iter()
+            .chain(self.docker_credentials.iter())
+    }
+
+    /// Returns the credential with the label `label`.
+    pub fn credential(&self, label: &str) -> Option<&Credential> {
+        self.all_credentials().find(|c| c.label() == label)
     }
 }
 
@@ -57,6 +94,8 @@ pub struct SettingsWindow {
     pub settings: Settings,
     pub settings_path: Option<PathBuf>,
     pub msg: Option<Message>,
+    /// Set when the credentials from the docker CLI configuration have to be read again
+    pub reload_docker_credentials: bool,
 }
 
 impl Default for SettingsWindow {
@@ -66,6 +105,7 @@ impl Default for SettingsWindow {
             settings: Settings::default(),
             settings_path: dir().map(|d| d.join(FILENAME)),
             msg: None,
+            reload_docker_credentials: false,
         }
     }
 }
@@ -75,6 +115,16 @@ impl SettingsWindow {
         self.show = !self.show;
     }
 
+    /// Loads the saved registry credentials from the file next to the settings file.
+    pub fn load_credentials(&mut self) -> Result<()> {
+        if let Some(settings_path) = &self.settings_path {
+            let loaded = credentials::load(credentials::path(settings_path));
+            self.settings.credentials_load_failed = loaded.is_err();
+            self.settings.credentials = loaded.context("failed to load registry credentials")?;
+        }
+        Ok(())
+    }
+
     pub fn save_settings(&mut self) -> Result<()> {
         if let Some(settings_path) = &self.settings_path {
             log::trace!("saving settings");
@@ -121,6 +171,9 @@ impl SettingsWindow {
                     self.fonts_ui(ui);
                     ui.end_row();
 
+                    self.credentials_ui(ui);
+                    ui.end_row();
+
                     ui.scope(|_| {});
                     ui.allocate_space((150., 0.).into());
                     ui.end_row();
@@ -143,6 +196,102 @@ impl SettingsWindow {
         self.show = show;
         self.msg = msg;
     }
+    fn credentials_ui(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("registry credentials")
+            .default_open(false)
+            .show(ui, |ui| {
+                ui.add(egui::Label::new(
+                    RichText::new(format!(
+                        "Secrets are stored in plain text in {}, only readable by the current user.",
+                        self.settings_path
+                            .as_deref()
+                            .map(|p| credentials::path(p).display().to_string())
+                            .unwrap_or_else(|| credentials::FILENAME.to_string()),
+                    ))
+                    .color(egui::Color32::YELLOW),
+                ));
+                if self.settings.credentials_load_failed {
+                    ui.label(
+                        RichText::new(
+                            "The credentials file failed to load, it's left untouched until the \
+                             credentials are edited.",
+                        )
+                        .color(egui::Color32::RED),
+                    );
+                }
+                let mut to_delete = None;
+                let mut edited = false;
+                egui::Grid::new("credentials_grid").show(ui, |ui| {
+                    ui.label("name");
+                    ui.label("server");
+                    ui.label("username");
+                    ui.label("secret");
+                    ui.end_row();
+                    for (i, credential) in self.settings.credentials.iter_mut().enumerate() {
+                        edited |= ui
+                            .add(egui::TextEdit::singleline(&mut credential.name).desired_width(100.))
+                            .changed();
+                        edited |= ui
+                            .add(
+                                egui::TextEdit::singleline(&mut credential.server)
+                                    .desired_width(150.),
+                            )
+                            .changed();
+                        edited |= ui
+                            .add(
+                                egui::TextEdit::singleline(&mut credential.username)
+                                    .desired_width(100.),
+                            )
+                            .changed();
+                        edited |= ui
+                            .add(
+                                egui::TextEdit::singleline(&mut credential.secret)
+                                    .password(true)
+                                    .desired_width(100.),
+                            )
+                            .changed();
+                        if ui.button("delete").clicked() {
+                            to_delete = Some(i);
+                        }
+                        ui.end_row();
+                    }
+                });
+                if let Some(i) = to_delete {
+                    self.settings.credentials.remove(i);
+                    edited = true;
+                }
+                if ui.button("add").clicked() {
+                    self.settings.credentials.push(Credential::default());
+                    edited = true;
+                }
+                if edited {
+                    self.settings.credentials_load_failed = false;
+                }
+
+                ui.add_space(10.);
+                if ui
+                    .checkbox(
+                        &mut self.settings.use_docker_config,
+                        "Use credentials from ~/.docker/config.json",
+                    )
+                    .on_hover_text("Secrets of credential helpers are requested when enabled")
+                    .changed()
+                {
+                    self.settings.docker_credentials.clear();
+                    self.reload_docker_credentials = self.settings.use_docker_config;
+                }
+                if self.settings.use_docker_config {
+                    egui::Grid::new("docker_credentials_grid").show(ui, |ui| {
+                        for credential in &self.settings.docker_credentials {
+                            ui.label(&credential.server);
+                            ui.label(&credential.username);
+                            ui.end_row();
+                        }
+                    });
+                }
+            });
+    }
+
     fn fonts_ui(&mut self, ui: &mut egui::Ui) {
         egui::CollapsingHeader::new("fonts")
             .default_open(false)
@@ -0,0 +1,91 @@
+use crate::app::containers::ContainerCreateData;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the file that stores the container create templates next to the settings file.
+pub const FILENAME: &str = "dockeye-templates.yml";
+
+/// Returns the path of the templates file that belongs to the settings file at `settings_path`.
+pub fn path(settings_path: impl AsRef<Path>) -> PathBuf {
+    settings_path.as_ref().with_file_name(FILENAME)
+}
+
+/// Named container create data that can be reused.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Template {
+    pub name: String,
+    pub data: ContainerCreateData,
+}
+
+/// Loads templates from the file located at `path`. A missing file means that no templates were
+/// saved yet.
+pub fn load(path: impl AsRef<Path>) -> Result<Vec<Template>> {
+    let path = path.as_ref();
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let data = fs::read(path).context("failed to read templates file")?;
+    serde_yaml::from_slice(&data).context("failed to deserialize templates")
+}
+
+/// Saves the templates to the file located at `path`. The same format is used for the templates
+/// of the application and for exported templates.
+pub fn save(templates: &[Template], path: impl AsRef<Path>) -> Result<()> {
+    let data = serde_yaml::to_vec(templates).context("failed to serialize templates")?;
+    fs::write(path, data).context("failed to write templates to file")
+}
+
+#[derive(Debug, Default)]
+pub struct TemplatesData {
+    pub templates: Vec<Template>,
+    /// Path of the file the templates are saved to
+    pub path: Option<PathBuf>,
+    pub selected: Option<String>,
+    pub name: String,
+}
+
+impl TemplatesData {
+    pub fn load(path: Option<PathBuf>) -> Result<Self> {
+        let templates = match &path {
+            Some(path) => load(path)?,
+            None => vec![],
+        };
+        Ok(Self {
+            templates,
+            path,
+            ..Default::default()
+        })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        match &self.path {
+            Some(path) => save(&self.templates, path),
+            None => Ok(()),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Template> {
+        self.templates.iter().find(|t| t.name == name)
+    }
+
+    /// Adds a template replacing the one with the same name.
+    pub fn insert(&mut self, template: Template) {
+        match self.templates.iter_mut().find(|t| t.name == template.name) {
+            Some(existing) => *existing = template,
+            None => {
+                self.templates.push(template);
+                self.templates.sort_by(|a, b| a.name.cmp(&b.name));
+            }
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.templates.retain(|t| t.name != name);
+        if self.selected.as_deref() == Some(name) {
+            self.selected = None;
+        }
+    }
+}
@@ -0,0 +1,254 @@
+//! Graph of networks and the containers attached to them.
+
+use crate::app::{containers::color_for_state, networks::CentralView, App};
+use crate::event::{EventRequest, NetworkEvent};
+
+use egui::{Align2, Color32, FontId, Label, Pos2, Rect, RichText, Sense, Stroke};
+use std::collections::HashMap;
+
+/// Horizontal distance between nodes of the same row
+const NODE_SPACING: f32 = 150.;
+/// Vertical distance between the row of networks and the row of containers
+const ROW_GAP: f32 = 220.;
+const MARGIN: f32 = 40.;
+const NETWORK_SIZE: (f32, f32) = (130., 36.);
+const CONTAINER_RADIUS: f32 = 12.;
+/// Names longer than this are shortened so that nodes don't overlap
+const MAX_NAME_LEN: usize = 18;
+
+fn short_name(name: &str) -> String {
+    let name = name.trim_start_matches('/');
+    if name.chars().count() > MAX_NAME_LEN {
+        format!(
+            "{}…",
+            name.chars().take(MAX_NAME_LEN - 1).collect::<String>()
+        )
+    } else {
+        name.to_string()
+    }
+}
+
+/// A container attached to one or more networks.
+struct ContainerNode {
+    id: String,
+    name: String,
+    /// Indices of the networks the container is attached to along with its addresses in them
+    networks: Vec<(usize, String)>,
+}
+
+impl ContainerNode {
+    /// Average position of the networks of the container, used to order containers so that
+    /// edges cross as little as possible.
+    fn barycenter(&self) -> f32 {
+        self.networks.iter().map(|(i, _)| *i as f32).sum::<f32>() / self.networks.len() as f32
+    }
+}
+
+enum Clicked {
+    Network(usize),
+    Container(String),
+}
+
+/// Returns the x coordinate of node `i` of a row with `count` nodes centered in `width`.
+fn node_x(i: usize, count: usize, width: f32) -> f32 {
+    (width - count as f32 * NODE_SPACING) / 2. + (i as f32 + 0.5) * NODE_SPACING
+}
+
+impl App {
+    pub fn network_topology(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Network topology");
+        ui.add_space(10.);
+
+        if let Some(error) = &self.networks.topology_error {
+            ui.add(Label::new(RichText::new(error).color(Color32::RED)));
+        }
+        let networks = &self.networks.topology;
+        if networks.is_empty() {
+            if self.networks.topology_error.is_none() {
+                ui.label("Loading networks...");
+            }
+            return;
+        }
+
+        let mut nodes = HashMap::<&str, ContainerNode>::new();
+        for (i, network) in networks.iter().enumerate() {
+            for (id, container) in network.containers.iter().flatten() {
+                let node = nodes.entry(id).or_insert_with(|| ContainerNode {
+                    id: id.clone(),
+                    name: container.name.clone().unwrap_or_else(|| id.clone()),
+                    networks: vec![],
+                });
+                let addresses = [&container.ipv4_address, &container.ipv6_address]
+                    .into_iter()
+                    .flatten()
+                    .filter(|addr| !addr.is_empty())
+                    .cloned()
+                    .collect::<Vec<_>>();
+                node.networks.push((i, addresses.join(", ")));
+            }
+        }
+        let mut containers = nodes.into_values().collect::<Vec<_>>();
+        containers.sort_by(|a, b| {
+            a.barycenter()
+                .partial_cmp(&b.barycenter())
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        ui.label("Click a network or a container to display its details.");
+        ui.add_space(5.);
+
+        let mut clicked = None;
+        egui::ScrollArea::both().show(ui, |ui| {
+            let width = (networks.len().max(containers.len()) as f32 * NODE_SPACING)
+                .max(ui.available_width());
+            let (response, painter) = ui.allocate_painter(
+                egui::vec2(width, ROW_GAP + 2. * MARGIN + NETWORK_SIZE.1),
+                Sense::hover(),
+            );
+            let origin = response.rect.min;
+            let visuals = ui.visuals().clone();
+            let font = FontId::proportional(14.);
+
+            let network_rects = (0..networks.len())
+                .map(|i| {
+                    Rect::from_center_size(
+                        origin
+                            + egui::vec2(
+                                node_x(i, networks.len(), width),
+                                MARGIN + NETWORK_SIZE.1 / 2.,
+                            ),
+                        NETWORK_SIZE.into(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            let container_centers = (0..containers.len())
+                .map(|i| {
+                    origin
+                        + egui::vec2(
+                            node_x(i, containers.len(), width),
+                            MARGIN + NETWORK_SIZE.1 + ROW_GAP - MARGIN,
+                        )
+                })
+                .collect::<Vec<Pos2>>();
+
+            // nodes are checked for hovering first so that their edges can be highlighted
+            let network_responses = networks
+                .iter()
+                .zip(&network_rects)
+                .map(|(network, rect)| {
+                    let response = ui.interact(*rect, ui.id().with(&network.id), Sense::click());
+                    let driver = network.driver.as_deref().unwrap_or_default();
+                    response.on_hover_text(format!(
+                        "{}\ndriver: {}",
+                        network.name.as_deref().unwrap_or(&network.id),
+                        driver
+                    ))
+                })
+                .collect::<Vec<_>>();
+            let container_responses = containers
+                .iter()
+                .zip(&container_centers)
+                .map(|(container, center)| {
+                    let rect = Rect::from_center_size(
+                        *center,
+                        egui::vec2(CONTAINER_RADIUS, CONTAINER_RADIUS) * 2.,
+                    );
+                    let response = ui.interact(rect, ui.id().with(&container.id), Sense::click());
+                    let mut text = container.name.trim_start_matches('/').to_string();
+                    for (i, addresses) in &container.networks {
+                        let network = &networks[*i];
+                        text.push_str(&format!(
+                            "\n{}: {}",
+                            network.name.as_deref().unwrap_or(&network.id),
+                            addresses
+                        ));
+                    }
+                    response.on_hover_text(text)
+                })
+                .collect::<Vec<_>>();
+
+            for ((container, center), container_response) in containers
+                .iter()
+                .zip(&container_centers)
+                .zip(&container_responses)
+            {
+                for (i, _) in &container.networks {
+                    let stroke = if container_response.hovered() || network_responses[*i].hovered()
+                    {
+                        Stroke::new(2.5, visuals.selection.bg_fill)
+                    } else {
+                        Stroke::new(1.5, visuals.widgets.noninteractive.fg_stroke.color)
+                    };
+                    painter.line_segment([network_rects[*i].center_bottom(), *center], stroke);
+                }
+            }
+
+            for (i, ((network, rect), response)) in networks
+                .iter()
+                .zip(&network_rects)
+                .zip(&network_responses)
+                .enumerate()
+            {
+                let widget = if response.hovered() {
+                    &visuals.widgets.hovered
+                } else {
+                    &visuals.widgets.inactive
+                };
+                painter.rect(*rect, 5., widget.bg_fill, widget.bg_stroke);
+                painter.text(
+                    rect.center(),
+                    Align2::CENTER_CENTER,
+                    short_name(network.name.as_deref().unwrap_or(&network.id)),
+                    font.clone(),
+                    widget.fg_stroke.color,
+                );
+                if response.clicked() {
+                    clicked = Some(Clicked::Network(i));
+                }
+            }
+
+            for ((container, center), response) in containers
+                .iter()
+                .zip(&container_centers)
+                .zip(&container_responses)
+            {
+                let color = self
+                    .containers
+                    .containers
+                    .iter()
+                    .find(|c| c.id == container.id)
+                    .map(|c| color_for_state(&c.state))
+                    .unwrap_or(Color32::GRAY);
+                let stroke = if response.hovered() {
+                    Stroke::new(2., visuals.text_color())
+                } else {
+                    Stroke::none()
+                };
+                painter.circle(*center, CONTAINER_RADIUS, color, stroke);
+                painter.text(
+                    *center + egui::vec2(0., CONTAINER_RADIUS + 4.),
+                    Align2::CENTER_TOP,
+                    short_name(&container.name),
+                    font.clone(),
+                    visuals.text_color(),
+                );
+                if response.clicked() {
+                    clicked = Some(Clicked::Container(container.id.clone()));
+                }
+            }
+        });
+
+        match clicked {
+            Some(Clicked::Network(i)) => {
+                let network = self.networks.topology[i].clone();
+                let id = network.id.clone();
+                self.networks.current_network = Some(network);
+                self.networks.central_view = CentralView::Network;
+                self.send_event_notify(EventRequest::Network(NetworkEvent::Inspect { id }));
+            }
+            Some(Clicked::Container(id)) => self.follow_container(&id),
+            None => {}
+        }
+    }
+}
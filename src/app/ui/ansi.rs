@@ -0,0 +1,120 @@
+//! Minimal support for SGR escape sequences so that colored output can be displayed as is.
+use egui::{text::LayoutJob, Color32, Stroke, TextFormat};
+
+const COLORS: [Color32; 8] = [
+    Color32::from_rgb(0x00, 0x00, 0x00),
+    Color32::from_rgb(0xcd, 0x31, 0x31),
+    Color32::from_rgb(0x0d, 0xbc, 0x79),
+    Color32::from_rgb(0xe5, 0xe5, 0x10),
+    Color32::from_rgb(0x24, 0x72, 0xc8),
+    Color32::from_rgb(0xbc, 0x3f, 0xbc),
+    Color32::from_rgb(0x11, 0xa8, 0xcd),
+    Color32::from_rgb(0xe5, 0xe5, 0xe5),
+];
+
+const BRIGHT_COLORS: [Color32; 8] = [
+    Color32::from_rgb(0x66, 0x66, 0x66),
+    Color32::from_rgb(0xf1, 0x4c, 0x4c),
+    Color32::from_rgb(0x23, 0xd1, 0x8b),
+    Color32::from_rgb(0xf5, 0xf5, 0x43),
+    Color32::from_rgb(0x3b, 0x8e, 0xea),
+    Color32::from_rgb(0xd6, 0x70, 0xd6),
+    Color32::from_rgb(0x29, 0xb8, 0xdb),
+    Color32::from_rgb(0xff, 0xff, 0xff),
+];
+
+/// Returns a color from the xterm 256 color palette.
+fn color_256(n: u8) -> Color32 {
+    match n {
+        0..=7 => COLORS[n as usize],
+        8..=15 => BRIGHT_COLORS[n as usize - 8],
+        16..=231 => {
+            let n = n - 16;
+            let level = |c: u8| if c == 0 { 0 } else { c * 40 + 55 };
+            Color32::from_rgb(level(n / 36), level((n / 6) % 6), level(n % 6))
+        }
+        _ => {
+            let level = (n - 232) * 10 + 8;
+            Color32::from_rgb(level, level, level)
+        }
+    }
+}
+
+/// Parses an extended color definition like `5;n` or `2;r;g;b` that follows `38` or `48`.
+fn extended_color<'a>(params: &mut impl Iterator<Item = &'a str>) -> Option<Color32> {
+    let mut next = || params.next().and_then(|p| p.parse::<u8>().ok());
+    match next()? {
+        5 => next().map(color_256),
+        2 => Some(Color32::from_rgb(next()?, next()?, next()?)),
+        _ => None,
+    }
+}
+
+/// Applies the parameters of a `ESC [ ... m` sequence to `format`.
+fn apply_sgr(params: &str, format: &mut TextFormat, base: &TextFormat) {
+    let mut params = params.split(';');
+    while let Some(param) = params.next() {
+        match param.parse::<u8>().unwrap_or_default() {
+            0 => *format = base.clone(),
+            3 => format.italics = true,
+            4 => format.underline = Stroke::new(1., format.color),
+            9 => format.strikethrough = Stroke::new(1., format.color),
+            23 => format.italics = false,
+            24 => format.underline = Stroke::none(),
+            29 => format.strikethrough = Stroke::none(),
+            n @ 30..=37 => format.color = COLORS[(n - 30) as usize],
+            38 => {
+                if let Some(color) = extended_color(&mut params) {
+                    format.color = color;
+                }
+            }
+            39 => format.color = base.color,
+            n @ 40..=47 => format.background = COLORS[(n - 40) as usize],
+            48 => {
+                if let Some(color) = extended_color(&mut params) {
+                    format.background = color;
+                }
+            }
+            49 => format.background = base.background,
+            n @ 90..=97 => format.color = BRIGHT_COLORS[(n - 90) as usize],
+            n @ 100..=107 => format.background = BRIGHT_COLORS[(n - 100) as usize],
+            _ => {}
+        }
+    }
+}
+
+/// Converts `text` containing ANSI escape sequences into a layout job. Colors and text styles
+/// are applied on top of `base`, all other escape sequences are skipped.
+pub fn layout_job(text: &str, base: TextFormat) -> LayoutJob {
+    let mut job = LayoutJob::default();
+    let mut format = base.clone();
+    let mut rest = text;
+
+    while let Some(start) = rest.find('\x1b') {
+        if start > 0 {
+            job.append(&rest[..start], 0., format.clone());
+        }
+        rest = &rest[start + 1..];
+        if let Some(seq) = rest.strip_prefix('[') {
+            // a control sequence ends with a byte in the range 0x40..=0x7e
+            match seq.find(|c: char| ('\x40'..='\x7e').contains(&c)) {
+                Some(end) => {
+                    if seq[end..].starts_with('m') {
+                        apply_sgr(&seq[..end], &mut format, &base);
+                    }
+                    rest = &seq[end + 1..];
+                }
+                None => rest = "",
+            }
+        } else {
+            let mut chars = rest.chars();
+            chars.next();
+            rest = chars.as_str();
+        }
+    }
+    if !rest.is_empty() {
+        job.append(rest, 0., format);
+    }
+
+    job
+}
@@ -1,5 +1,7 @@
+pub mod ansi;
 mod editable_list;
 mod popup;
+mod progress;
 
 use egui::{
     style::{Selection, Widgets},
@@ -10,6 +12,7 @@ use std::string::ToString;
 
 pub use editable_list::{EditableList, EditableListBuilder};
 pub use popup::{ActionPopup, Popup};
+pub use progress::TransferProgress;
 
 pub mod color {
     use egui::{Color32, Rgba};
@@ -62,6 +65,10 @@ pub mod icon {
     pub const ARROW_DOWN: &str = "\u{2B8B}";
     pub const ARROW_LEFT: &str = "\u{2B05}";
     pub const ARROW_RIGHT: &str = "\u{27A1}";
+    pub const ARROW_UP: &str = "\u{2B06}";
+    pub const FOLDER: &str = "\u{1F4C1}";
+    pub const FILE: &str = "\u{1F4C4}";
+    pub const LINK: &str = "\u{1F517}";
     pub const RESTART: &str = "\u{1F504}";
     pub const NETWORK: &str = "\u{1F5A7}";
 }
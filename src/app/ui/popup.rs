@@ -2,10 +2,10 @@ use crate::event::EventRequest;
 
 use egui::Widget;
 
-/// A popup that has a specified action to run on confirmation.
+/// A popup that has specified actions to run on confirmation.
 pub struct ActionPopup {
-    /// The action to run on confirmation
-    action: EventRequest,
+    /// The actions to run on confirmation
+    actions: Vec<EventRequest>,
     /// The popup definition
     popup: Popup,
 }
@@ -13,7 +13,7 @@ pub struct ActionPopup {
 impl ActionPopup {
     pub fn builder(action: EventRequest) -> ActionPopupBuilder {
         ActionPopupBuilder {
-            action,
+            actions: vec![action],
             builder: PopupBuilder::default(),
         }
     }
@@ -28,9 +28,9 @@ impl ActionPopup {
         self.popup.confirmed
     }
 
-    /// Consume this popup returning the action that should be run
-    pub fn action(self) -> EventRequest {
-        self.action
+    /// Consume this popup returning the actions that should be run
+    pub fn actions(self) -> Vec<EventRequest> {
+        self.actions
     }
 }
 
@@ -98,11 +98,17 @@ impl Widget for &mut Popup {
 
 #[derive(Debug)]
 pub struct ActionPopupBuilder {
-    action: EventRequest,
+    actions: Vec<EventRequest>,
     builder: PopupBuilder,
 }
 
 impl ActionPopupBuilder {
+    /// Adds another action that will be run after the previous ones on confirmation.
+    pub fn action(mut self, action: EventRequest) -> Self {
+        self.actions.push(action);
+        self
+    }
+
     pub fn title(mut self, title: impl Into<String>) -> Self {
         self.builder = self.builder.title(title);
         self
@@ -115,7 +121,7 @@ impl ActionPopupBuilder {
 
     pub fn build(self) -> ActionPopup {
         ActionPopup {
-            action: self.action,
+            actions: self.actions,
             popup: self.builder.build(),
         }
     }
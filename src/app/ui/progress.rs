@@ -0,0 +1,293 @@
+use docker_api::api::ImageBuildChunk;
+use egui::{Color32, Label, ProgressBar, RichText, Widget};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayerPhase {
+    Waiting,
+    Downloading,
+    Downloaded,
+    Extracting,
+    Pushing,
+    Complete,
+}
+
+/// State of a single layer of a pull or a push.
+#[derive(Debug, Clone)]
+pub struct LayerProgress {
+    pub id: String,
+    pub status: String,
+    pub phase: LayerPhase,
+    pub total: Option<u64>,
+    /// Bytes downloaded when pulling or uploaded when pushing
+    pub transferred: u64,
+    pub extracted: u64,
+}
+
+impl LayerProgress {
+    fn new(id: String) -> Self {
+        Self {
+            id,
+            status: String::new(),
+            phase: LayerPhase::Waiting,
+            total: None,
+            transferred: 0,
+            extracted: 0,
+        }
+    }
+
+    fn update(&mut self, status: &str, current: Option<u64>, total: Option<u64>) {
+        if total.is_some() {
+            self.total = total;
+        }
+        let current = current.unwrap_or_default();
+        if status.starts_with("Downloading") {
+            self.phase = LayerPhase::Downloading;
+            self.transferred = current;
+        } else if status.starts_with("Pushing") {
+            self.phase = LayerPhase::Pushing;
+            self.transferred = current;
+        } else if status.starts_with("Verifying") || status.starts_with("Download complete") {
+            self.phase = LayerPhase::Downloaded;
+            self.transferred = self.total.unwrap_or(self.transferred);
+        } else if status.starts_with("Extracting") {
+            self.phase = LayerPhase::Extracting;
+            self.transferred = self.total.unwrap_or(self.transferred);
+            self.extracted = current;
+        } else if status.starts_with("Pull complete")
+            || status.starts_with("Already exists")
+            || status.starts_with("Pushed")
+            || status.starts_with("Layer already exists")
+            || status.starts_with("Mounted from")
+        {
+            self.phase = LayerPhase::Complete;
+            self.transferred = self.total.unwrap_or(self.transferred);
+            self.extracted = self.total.unwrap_or(self.extracted);
+        }
+        self.status = status.to_string();
+    }
+
+    /// Progress of this layer between 0 and 1. When pulling downloading and extracting each
+    /// account for a half of the progress.
+    pub fn fraction(&self) -> f32 {
+        let ratio = |current: u64| match self.total {
+            Some(total) if total > 0 => (current as f32 / total as f32).min(1.),
+            _ => 0.,
+        };
+        match self.phase {
+            LayerPhase::Waiting => 0.,
+            LayerPhase::Downloading => ratio(self.transferred) / 2.,
+            LayerPhase::Downloaded => 0.5,
+            LayerPhase::Extracting => 0.5 + ratio(self.extracted) / 2.,
+            LayerPhase::Pushing => ratio(self.transferred),
+            LayerPhase::Complete => 1.,
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.phase == LayerPhase::Complete
+    }
+
+    fn text(&self) -> String {
+        let current = match self.phase {
+            LayerPhase::Extracting => self.extracted,
+            _ => self.transferred,
+        };
+        match (self.phase, self.total) {
+            (
+                LayerPhase::Downloading | LayerPhase::Extracting | LayerPhase::Pushing,
+                Some(total),
+            ) => {
+                format!(
+                    "{} {} / {}",
+                    self.phase_name(),
+                    crate::conv_b(current),
+                    crate::conv_b(total)
+                )
+            }
+            _ => self.status.clone(),
+        }
+    }
+
+    fn phase_name(&self) -> &'static str {
+        match self.phase {
+            LayerPhase::Waiting => "Waiting",
+            LayerPhase::Downloading => "Downloading",
+            LayerPhase::Downloaded => "Downloaded",
+            LayerPhase::Extracting => "Extracting",
+            LayerPhase::Pushing => "Pushing",
+            LayerPhase::Complete => "Complete",
+        }
+    }
+}
+
+/// Progress of an image transfer like a pull, a push or an import built from the chunks
+/// reported by docker.
+#[derive(Debug, Default)]
+pub struct TransferProgress {
+    /// Layers in the order they were first reported
+    pub layers: Vec<LayerProgress>,
+    /// Status messages that don't belong to any layer
+    pub messages: Vec<String>,
+    pub error: Option<String>,
+    pub finished: bool,
+    started: Option<Instant>,
+}
+
+impl TransferProgress {
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty() && self.messages.is_empty() && self.error.is_none()
+    }
+
+    pub fn extend(&mut self, chunks: impl IntoIterator<Item = ImageBuildChunk>) {
+        for chunk in chunks {
+            self.push(chunk);
+        }
+    }
+
+    pub fn push(&mut self, chunk: ImageBuildChunk) {
+        match chunk {
+            ImageBuildChunk::Update { stream } => self.messages.push(stream.trim_end().to_string()),
+            ImageBuildChunk::Error { error, .. } => self.error = Some(error),
+            ImageBuildChunk::Digest { aux } => {
+                self.finished = true;
+                self.messages.push(format!("Digest: {}", aux.id));
+            }
+            ImageBuildChunk::PullStatus {
+                status,
+                id,
+                progress: _,
+                progress_detail,
+            } => {
+                let (current, total) = progress_detail
+                    .map(|p| (p.current, p.total))
+                    .unwrap_or_default();
+                match id {
+                    Some(id) if !status.starts_with("Pulling from") => {
+                        if current.is_some() && self.started.is_none() {
+                            self.started = Some(Instant::now());
+                        }
+                        let layer = match self.layers.iter_mut().position(|l| l.id == id) {
+                            Some(i) => &mut self.layers[i],
+                            None => {
+                                self.layers.push(LayerProgress::new(id));
+                                self.layers.last_mut().unwrap()
+                            }
+                        };
+                        layer.update(&status, current, total);
+                    }
+                    id => {
+                        // a push reports the digest as `<tag>: digest: <digest> size: <size>`
+                        if status.starts_with("Digest") || status.contains("digest: ") {
+                            self.finished = true;
+                        }
+                        self.messages.push(match id {
+                            Some(id) => format!("{}: {}", id, status),
+                            None => status,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    /// Overall progress between 0 and 1 as the average progress of all layers.
+    pub fn fraction(&self) -> f32 {
+        if self.finished {
+            return 1.;
+        }
+        if self.layers.is_empty() {
+            return 0.;
+        }
+        self.layers.iter().map(LayerProgress::fraction).sum::<f32>() / self.layers.len() as f32
+    }
+
+    /// Returns the transferred bytes and the total bytes of all layers with a known size.
+    pub fn bytes(&self) -> (u64, u64) {
+        self.layers
+            .iter()
+            .filter_map(|l| l.total.map(|total| (l.transferred, total)))
+            .fold((0, 0), |(transferred, all), (t, total)| {
+                (transferred + t, all + total)
+            })
+    }
+
+    /// Estimated time left based on the average transfer rate so far.
+    pub fn eta(&self) -> Option<Duration> {
+        let elapsed = self.started?.elapsed().as_secs_f64();
+        let (transferred, total) = self.bytes();
+        if self.finished || transferred == 0 || transferred >= total || elapsed < 1. {
+            return None;
+        }
+        let rate = transferred as f64 / elapsed;
+        Some(Duration::from_secs_f64((total - transferred) as f64 / rate))
+    }
+}
+
+impl Widget for &TransferProgress {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        ui.vertical(|ui| {
+            let fraction = self.fraction();
+            let (transferred, total) = self.bytes();
+            let mut text = format!("{:.0}%", fraction * 100.);
+            if total > 0 {
+                text.push_str(&format!(
+                    " - {} / {}",
+                    crate::conv_b(transferred),
+                    crate::conv_b(total)
+                ));
+            }
+            if let Some(eta) = self.eta() {
+                text.push_str(&format!(" - {}s left", eta.as_secs()));
+            }
+            ui.add(
+                ProgressBar::new(fraction)
+                    .text(text)
+                    .desired_width(400.)
+                    .animate(!self.finished && self.error.is_none()),
+            );
+
+            egui::Grid::new("transfer_progress_layers").show(ui, |ui| {
+                for layer in self.layers.iter().filter(|l| !l.is_complete()) {
+                    ui.add(Label::new(RichText::new(&layer.id).monospace()));
+                    ui.add(
+                        ProgressBar::new(layer.fraction())
+                            .text(layer.text())
+                            .desired_width(300.),
+                    );
+                    ui.end_row();
+                }
+            });
+
+            let completed = self.layers.iter().filter(|l| l.is_complete()).count();
+            if completed > 0 {
+                egui::CollapsingHeader::new(format!("{} completed layers", completed))
+                    .id_source("transfer_progress_completed")
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        egui::Grid::new("transfer_progress_completed_layers").show(ui, |ui| {
+                            for layer in self.layers.iter().filter(|l| l.is_complete()) {
+                                ui.add(Label::new(RichText::new(&layer.id).monospace()));
+                                ui.label(&layer.status);
+                                ui.end_row();
+                            }
+                        });
+                    });
+            }
+
+            for message in &self.messages {
+                ui.label(message);
+            }
+            if let Some(error) = &self.error {
+                ui.add(Label::new(
+                    RichText::new(format!("Error: {}", error)).color(Color32::RED),
+                ));
+            }
+        })
+        .response
+    }
+}
@@ -1,12 +1,15 @@
-use crate::worker::{Logs, RunningContainerStats};
+use crate::app::credentials::DockerCredentials;
+use crate::worker::{
+    DirListing, JobId, JobUpdate, Logs, LogsConfig, LogsExportFormat, RunningContainerStats,
+};
 
 use docker_api::api::{
-    Change, ClearCacheInfo, ContainerCreateOpts, ContainerDetails, ContainerId, ContainerInfo,
-    ContainerListOpts, ContainersPruneInfo, DataUsage, DeleteStatus, DistributionInspectInfo,
-    Event, History, ImageBuildChunk, ImageDetails, ImageId, ImageInfo, ImageListOpts,
+    BuildOpts, Change, ClearCacheInfo, ContainerCommitOpts, ContainerCreateOpts, ContainerDetails,
+    ContainerId, ContainerInfo, ContainerListOpts, ContainersPruneInfo, DataUsage, DeleteStatus,
+    DistributionInspectInfo, Event, History, ImageDetails, ImageId, ImageInfo, ImageListOpts,
     ImagesPruneInfo, Info, NetworkCreateOpts, NetworkId, NetworkInfo, NetworkListOpts,
-    NetworksPruneInfo, RegistryAuth, SearchResult, TagOpts, Top, Version, VolumeCreateOpts,
-    VolumeId, VolumeListOpts, VolumePruneOpts, VolumesInfo, VolumesPruneInfo,
+    NetworksPruneInfo, RegistryAuth, SearchResult, TagOpts, Top, UpdateContainerOpts, Version,
+    VolumeCreateOpts, VolumeId, VolumeListOpts, VolumePruneOpts, VolumesInfo, VolumesPruneInfo,
 };
 use docker_api::Error;
 use std::path::PathBuf;
@@ -17,6 +20,16 @@ pub struct SystemInspectInfo {
     pub info: Info,
 }
 
+#[derive(Debug)]
+pub enum LogsExportStatus {
+    InProgress {
+        lines: usize,
+    },
+    Finished(anyhow::Result<(ContainerId, PathBuf)>),
+    /// The export was stopped before it finished
+    Cancelled,
+}
+
 #[derive(Debug)]
 pub struct ImageInspectInfo {
     pub details: ImageDetails,
@@ -37,6 +50,8 @@ pub enum EventRequest {
     NotifyGui(GuiEvent),
     Network(NetworkEvent),
     Volume(VolumeEvent),
+    Jobs(JobsEvent),
+    DockerCredentials,
 }
 
 #[derive(Debug)]
@@ -50,6 +65,8 @@ pub enum EventResponse {
     NotifyGui(GuiEventResponse),
     Network(NetworkEventResponse),
     Volume(VolumeEventResponse),
+    Jobs(JobsEventResponse),
+    DockerCredentials(anyhow::Result<DockerCredentials>),
 }
 
 //####################################################################################################
@@ -57,22 +74,121 @@ pub enum EventResponse {
 #[derive(Debug)]
 pub enum ContainerEvent {
     List(Option<ContainerListOpts>),
-    Delete { id: String },
+    Delete {
+        id: String,
+    },
     Stats,
     Logs,
     Details,
-    Stop { id: String },
-    Unpause { id: String },
-    Pause { id: String },
-    Start { id: String },
-    TraceStart { id: String },
-    Create(ContainerCreateOpts),
-    Rename { id: String, name: String },
-    ForceDelete { id: String },
+    Stop {
+        id: String,
+    },
+    Unpause {
+        id: String,
+    },
+    Pause {
+        id: String,
+    },
+    Start {
+        id: String,
+    },
+    TraceStart {
+        id: String,
+    },
+    /// Creates a container, connects it to additional `networks` and optionally starts it
+    Create {
+        opts: ContainerCreateOpts,
+        networks: Vec<String>,
+        /// Aliases of the container in all of its networks
+        aliases: Vec<String>,
+        start: bool,
+    },
+    Rename {
+        id: String,
+        name: String,
+    },
+    Export {
+        id: ContainerId,
+        output_path: PathBuf,
+    },
+    ListDir {
+        id: ContainerId,
+        path: String,
+        running: bool,
+    },
+    /// Copies a file or a directory out of a container, without an `output_path` the contents
+    /// of a file are returned for a preview
+    CopyFrom {
+        id: ContainerId,
+        path: String,
+        output_path: Option<PathBuf>,
+        /// Whether to save `path` as a tar archive rather than the contents of a single file
+        archive: bool,
+    },
+    /// Copies a file from the host to directory `path` of a container
+    CopyTo {
+        id: ContainerId,
+        path: String,
+        input_path: PathBuf,
+    },
+    Commit {
+        id: ContainerId,
+        opts: ContainerCommitOpts,
+    },
+    Update {
+        id: ContainerId,
+        opts: UpdateContainerOpts,
+    },
+    /// Replaces a container with a new one created from `opts`, the new container is started if
+    /// the replaced one was running
+    Recreate {
+        id: ContainerId,
+        /// Image of the new container, checked to exist before the original is touched
+        image: String,
+        opts: ContainerCreateOpts,
+        networks: Vec<String>,
+        start: bool,
+    },
+    ForceDelete {
+        id: String,
+    },
     Prune,
-    Restart { id: String },
+    Restart {
+        id: String,
+    },
     ProcessList,
     Changes,
+    Attach {
+        id: String,
+    },
+    AttachInput {
+        input: Vec<u8>,
+    },
+    AttachOutput,
+    Detach,
+    Exec {
+        id: String,
+        cmd: Vec<String>,
+        env: Vec<String>,
+        user: Option<String>,
+        workdir: Option<String>,
+    },
+    ExecOutput,
+    /// Stops following the output of the command, the command itself keeps running
+    ExecDetach,
+    ConfigureLogs(LogsConfig),
+    ExportLogs {
+        id: String,
+        output_path: PathBuf,
+        format: LogsExportFormat,
+        timestamps: bool,
+    },
+    CancelLogsExport,
+    /// Sets the containers followed by the aggregated logs view
+    AggregateLogs {
+        ids: Vec<ContainerId>,
+    },
+    AggregatedLogs,
 }
 
 #[derive(Debug)]
@@ -89,12 +205,35 @@ pub enum ContainerEventResponse {
     InspectNotFound,
     Create(anyhow::Result<ContainerId>),
     Rename(anyhow::Result<()>),
+    Export(anyhow::Result<(ContainerId, PathBuf)>),
+    Commit(anyhow::Result<ImageId>),
+    Update(anyhow::Result<ContainerId>),
+    Recreate(anyhow::Result<ContainerId>),
+    ListDir {
+        path: String,
+        result: anyhow::Result<DirListing>,
+    },
+    FilePreview {
+        path: String,
+        result: anyhow::Result<Vec<u8>>,
+    },
+    CopyFrom(anyhow::Result<PathBuf>),
+    CopyTo(anyhow::Result<String>),
     ForceDelete(anyhow::Result<ContainerId>),
     Prune(anyhow::Result<ContainersPruneInfo>),
     Restart(anyhow::Result<ContainerId>),
-    RestartInProgress { id: String },
+    RestartInProgress {
+        id: String,
+    },
     ProcessList(anyhow::Result<Top>),
     Changes(anyhow::Result<Option<Vec<Change>>>),
+    AttachOutput(anyhow::Result<Box<Logs>>),
+    Detached,
+    ExecOutput(anyhow::Result<Box<Logs>>),
+    ExecFinished,
+    LogsConfigured,
+    ExportLogs(LogsExportStatus),
+    AggregatedLogs(Vec<(ContainerId, Box<Logs>)>),
 }
 
 //####################################################################################################
@@ -125,13 +264,23 @@ pub enum ImageEvent {
     Import {
         path: PathBuf,
     },
-    PullChunks,
     Prune,
     ClearCache,
     Tag {
         id: String,
         opts: TagOpts,
     },
+    Build {
+        /// Context directory and tag of the build displayed in the jobs list
+        context: String,
+        tag: String,
+        opts: BuildOpts,
+    },
+    Push {
+        image: String,
+        tag: Option<String>,
+        auth: Option<RegistryAuth>,
+    },
 }
 
 #[derive(Debug)]
@@ -140,8 +289,13 @@ pub enum ImageEventResponse {
     Inspect(Box<ImageInspectInfo>),
     Delete(Result<DeleteStatus, (ImageId, Error)>),
     Save(anyhow::Result<(ImageId, PathBuf)>),
-    Pull(anyhow::Result<ImageId>),
-    PullChunks(Vec<ImageBuildChunk>),
+    Pull {
+        image: String,
+        result: anyhow::Result<ImageId>,
+    },
+    Build(anyhow::Result<ImageId>),
+    /// Reference of the pushed image on success
+    Push(anyhow::Result<String>),
     Search(anyhow::Result<Vec<SearchResult>>),
     ForceDelete(anyhow::Result<DeleteStatus>),
     Import(anyhow::Result<String>),
@@ -155,16 +309,41 @@ pub enum ImageEventResponse {
 #[derive(Debug)]
 pub enum NetworkEvent {
     List(Option<NetworkListOpts>),
-    Delete { id: NetworkId },
+    Delete {
+        id: NetworkId,
+    },
+    Inspect {
+        id: NetworkId,
+    },
+    /// Inspects networks `ids` to find out which containers are attached to them
+    Topology {
+        ids: Vec<NetworkId>,
+    },
     Prune,
     Create(NetworkCreateOpts),
+    Connect {
+        network: NetworkId,
+        container: ContainerId,
+        aliases: Vec<String>,
+        ipv4: Option<String>,
+        ipv6: Option<String>,
+    },
+    Disconnect {
+        network: NetworkId,
+        container: ContainerId,
+        force: bool,
+    },
 }
 #[derive(Debug)]
 pub enum NetworkEventResponse {
     List(Vec<NetworkInfo>),
     Delete(anyhow::Result<NetworkId>),
+    Inspect(anyhow::Result<Box<NetworkInfo>>),
+    Topology(anyhow::Result<Vec<NetworkInfo>>),
     Prune(anyhow::Result<NetworksPruneInfo>),
     Create(anyhow::Result<NetworkId>),
+    Connect(anyhow::Result<(NetworkId, ContainerId)>),
+    Disconnect(anyhow::Result<(NetworkId, ContainerId)>),
 }
 
 //####################################################################################################
@@ -203,3 +382,21 @@ pub enum VolumeEventResponse {
     Prune(anyhow::Result<VolumesPruneInfo>),
     Create(anyhow::Result<VolumeId>),
 }
+
+//####################################################################################################
+
+#[derive(Debug)]
+pub enum JobsEvent {
+    /// Returns all jobs with the progress reported since the last poll
+    List,
+    Cancel {
+        id: JobId,
+    },
+    /// Removes all jobs that are no longer active
+    Clear,
+}
+
+#[derive(Debug)]
+pub enum JobsEventResponse {
+    List(Vec<JobUpdate>),
+}
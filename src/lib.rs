@@ -92,7 +92,135 @@ pub fn convert_memory(s: &str) -> Result<u64> {
     }
 }
 
+/// Splits a command line into words like a POSIX shell does. Supports single and double quotes
+/// as well as escaping characters with a backslash.
+pub fn split_shell_words(s: &str) -> Result<Vec<String>> {
+    let mut words = vec![];
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err(Error::msg("unterminated single quote")),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => word.push(c),
+                            Some('\n') => {}
+                            Some(c) => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                            None => return Err(Error::msg("unterminated double quote")),
+                        },
+                        Some(c) => word.push(c),
+                        None => return Err(Error::msg("unterminated double quote")),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some('\n') => {}
+                Some(c) => {
+                    in_word = true;
+                    word.push(c);
+                }
+                None => return Err(Error::msg("unexpected backslash at the end")),
+            },
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            c => {
+                in_word = true;
+                word.push(c);
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+
+    Ok(words)
+}
+
+/// Joins words into a command line that [`split_shell_words`](split_shell_words) splits back into
+/// the same words, words with special characters are single quoted.
+pub fn join_shell_words<S: AsRef<str>>(words: &[S]) -> String {
+    words
+        .iter()
+        .map(|word| {
+            let word = word.as_ref();
+            let is_safe = |c: char| c.is_ascii_alphanumeric() || "_-./:=@,+%".contains(c);
+            if !word.is_empty() && word.chars().all(is_safe) {
+                word.to_string()
+            } else {
+                format!("'{}'", word.replace('\'', r#"'\''"#))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 #[cfg(not(target_os = "macos"))]
 pub const DEFAULT_DOCKER_ADDR: &str = "unix:///var/run/docker.sock";
 #[cfg(target_os = "macos")]
 pub const DEFAULT_DOCKER_ADDR: &str = "unix:///run/docker.sock";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_quoted_words() {
+        assert_eq!(
+            split_shell_words(r#"sh -c 'echo "a b"' "x\"y" a\ b '' end"#).unwrap(),
+            ["sh", "-c", r#"echo "a b""#, r#"x"y"#, "a b", "", "end"]
+        );
+        assert_eq!(
+            split_shell_words("  a\t b \\\n c  ").unwrap(),
+            ["a", "b", "c"]
+        );
+    }
+
+    #[test]
+    fn rejects_unterminated_quotes() {
+        assert!(split_shell_words("echo 'a").is_err());
+        assert!(split_shell_words(r#"echo "a"#).is_err());
+        assert!(split_shell_words("echo \\").is_err());
+    }
+
+    #[test]
+    fn joins_words_that_split_back() {
+        let words = [
+            "nginx",
+            "-g",
+            "daemon off;",
+            "it's",
+            "",
+            "$HOME",
+            "--flag=value",
+            r#"a "quoted" \ word"#,
+        ];
+        let joined = join_shell_words(&words);
+        assert_eq!(
+            joined,
+            r#"nginx -g 'daemon off;' 'it'\''s' '' '$HOME' --flag=value 'a "quoted" \ word'"#
+        );
+        assert_eq!(split_shell_words(&joined).unwrap(), words);
+    }
+}
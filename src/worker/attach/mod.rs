@@ -0,0 +1,116 @@
+use crate::worker::{Logs, WorkerEvent};
+
+use anyhow::anyhow;
+use docker_api::{api::ContainerId, Docker};
+use futures::{AsyncWriteExt, StreamExt};
+use log::{debug, error};
+use tokio::sync::mpsc;
+
+#[derive(Debug)]
+pub struct AttachWorker {
+    pub current_id: ContainerId,
+    pub rx_events: mpsc::Receiver<WorkerEvent>,
+    pub rx_input: mpsc::Receiver<Vec<u8>>,
+    pub tx_output: mpsc::Sender<anyhow::Result<Box<Logs>>>,
+    pub output: Box<Logs>,
+    /// Set once the attached stream ends, the worker exits after the remaining output is sent
+    pub finished: bool,
+}
+
+/// A new attach worker with the channels to send it events and input and to receive the output of
+/// the container
+pub type AttachWorkerParts = (
+    AttachWorker,
+    mpsc::Sender<WorkerEvent>,
+    mpsc::Sender<Vec<u8>>,
+    mpsc::Receiver<anyhow::Result<Box<Logs>>>,
+);
+
+impl AttachWorker {
+    pub fn new(current_id: impl Into<ContainerId>) -> AttachWorkerParts {
+        let (tx_output, rx_output) = mpsc::channel::<anyhow::Result<Box<Logs>>>(128);
+        let (tx_input, rx_input) = mpsc::channel::<Vec<u8>>(128);
+        let (tx_events, rx_events) = mpsc::channel::<WorkerEvent>(128);
+
+        (
+            Self {
+                current_id: current_id.into(),
+                rx_events,
+                rx_input,
+                tx_output,
+                output: Box::new(Logs(vec![])),
+                finished: false,
+            },
+            tx_events,
+            tx_input,
+            rx_output,
+        )
+    }
+    async fn send_output(&mut self) {
+        debug!("got poll data request, sending attach output");
+        if let Err(e) = self
+            .tx_output
+            .send(Ok(std::mem::take(&mut self.output)))
+            .await
+        {
+            error!("failed to send attach output: {}", e);
+        }
+    }
+    pub async fn work(mut self, docker: Docker) {
+        let container = docker.containers().get(&self.current_id);
+        let multiplexer = match container.attach().await {
+            Ok(multiplexer) => multiplexer,
+            Err(e) => {
+                let _ = self
+                    .tx_output
+                    .send(Err(anyhow!(
+                        "failed to attach to container `{}` - {}",
+                        self.current_id,
+                        e
+                    )))
+                    .await;
+                return;
+            }
+        };
+        let (reader, writer) = multiplexer.split();
+        futures::pin_mut!(reader);
+        futures::pin_mut!(writer);
+
+        loop {
+            tokio::select! {
+                chunk = reader.next(), if !self.finished => {
+                    match chunk {
+                        Some(Ok(chunk)) => {
+                            log::trace!("adding attach chunk");
+                            self.output.0.push(chunk);
+                        }
+                        Some(Err(e)) => {
+                            error!("failed to read attach output: {}", e);
+                            self.finished = true;
+                        }
+                        None => {
+                            debug!("attach stream of container `{}` ended", self.current_id);
+                            self.finished = true;
+                        }
+                    }
+                }
+                Some(input) = self.rx_input.recv() => {
+                    if let Err(e) = writer.write_all(&input).await {
+                        error!("failed to write to attached container: {}", e);
+                    }
+                }
+                event = self.rx_events.recv() => {
+                    match event {
+                        Some(WorkerEvent::PollData) => {
+                            self.send_output().await;
+                            if self.finished {
+                                break;
+                            }
+                        }
+                        Some(WorkerEvent::Kill) | None => break,
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use crate::worker::WorkerEvent;
+
+use anyhow::Error;
+use docker_api::{api::ContainerId, Docker};
+use futures::StreamExt;
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+use tokio::sync::mpsc;
+
+/// Exports the filesystem of a container as a tar archive.
+#[derive(Debug)]
+pub struct ContainerExportWorker {
+    pub id: ContainerId,
+    pub output_path: PathBuf,
+    pub rx_events: mpsc::Receiver<WorkerEvent>,
+    pub tx_results: mpsc::Sender<anyhow::Result<(ContainerId, PathBuf)>>,
+}
+
+/// A new export worker with the channels to send it events and to receive the result of the export
+pub type ContainerExportWorkerParts = (
+    ContainerExportWorker,
+    mpsc::Sender<WorkerEvent>,
+    mpsc::Receiver<anyhow::Result<(ContainerId, PathBuf)>>,
+);
+
+impl ContainerExportWorker {
+    pub fn new(id: ContainerId, output_path: PathBuf) -> ContainerExportWorkerParts {
+        let (tx_results, rx_results) = mpsc::channel::<anyhow::Result<(ContainerId, PathBuf)>>(128);
+        let (tx_events, rx_events) = mpsc::channel::<WorkerEvent>(128);
+
+        (
+            Self {
+                id,
+                output_path,
+                rx_events,
+                tx_results,
+            },
+            tx_events,
+            rx_results,
+        )
+    }
+
+    pub async fn work(mut self, docker: Docker) {
+        log::trace!("starting container `{}` export", self.id);
+        let container = docker.containers().get(&self.id);
+        let mut export_stream = container.export();
+        let mut export_file = match File::create(&self.output_path) {
+            Ok(f) => f,
+            Err(e) => {
+                let _ = self
+                    .tx_results
+                    .send(Err(Error::msg(format!(
+                        "opening file to export container failed - {}",
+                        e
+                    ))))
+                    .await;
+                return;
+            }
+        };
+        loop {
+            tokio::select! {
+                bytes = export_stream.next() => {
+                    match bytes {
+                        Some(Ok(chunk)) => {
+                            log::trace!("saving container export chunk");
+                            if let Err(e) = export_file.write_all(&chunk) {
+                                let _ = self
+                                    .tx_results
+                                    .send(Err(Error::msg(format!(
+                                        "writing container export failed - {}",
+                                        e
+                                    ))))
+                                    .await;
+                                return;
+                            }
+                        }
+                        Some(Err(e)) => {
+                            let _ = self
+                                .tx_results
+                                .send(Err(Error::msg(format!("container export failed - {}", e))))
+                                .await;
+                            return;
+                        }
+                        None => {
+                            log::trace!("container `{}` export finished successfuly", self.id);
+                            let Self { id, tx_results, output_path, .. }  = self;
+                            let _ = tx_results.send(Ok((id, output_path))).await;
+                            return;
+                        }
+                    }
+                }
+                event = self.rx_events.recv() => {
+                    match event {
+                        Some(WorkerEvent::Kill) => break,
+                        _ => continue,
+                    }
+                }
+            }
+        }
+    }
+}
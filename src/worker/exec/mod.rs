@@ -0,0 +1,102 @@
+use crate::worker::{Logs, WorkerEvent};
+
+use anyhow::anyhow;
+use docker_api::{
+    api::{ContainerId, ExecContainerOpts},
+    Docker,
+};
+use futures::StreamExt;
+use log::{debug, error};
+use tokio::sync::mpsc;
+
+#[derive(Debug)]
+pub struct ExecWorker {
+    pub current_id: ContainerId,
+    pub opts: ExecContainerOpts,
+    pub rx_events: mpsc::Receiver<WorkerEvent>,
+    pub tx_output: mpsc::Sender<anyhow::Result<Box<Logs>>>,
+    pub output: Box<Logs>,
+    /// Set once the command exits, the worker stops after the remaining output is sent
+    pub finished: bool,
+}
+
+/// A new exec worker with the channels to send it events and to receive the output of the command
+pub type ExecWorkerParts = (
+    ExecWorker,
+    mpsc::Sender<WorkerEvent>,
+    mpsc::Receiver<anyhow::Result<Box<Logs>>>,
+);
+
+impl ExecWorker {
+    pub fn new(current_id: impl Into<ContainerId>, opts: ExecContainerOpts) -> ExecWorkerParts {
+        let (tx_output, rx_output) = mpsc::channel::<anyhow::Result<Box<Logs>>>(128);
+        let (tx_events, rx_events) = mpsc::channel::<WorkerEvent>(128);
+
+        (
+            Self {
+                current_id: current_id.into(),
+                opts,
+                rx_events,
+                tx_output,
+                output: Box::new(Logs(vec![])),
+                finished: false,
+            },
+            tx_events,
+            rx_output,
+        )
+    }
+    async fn send_output(&mut self) {
+        debug!("got poll data request, sending exec output");
+        if let Err(e) = self
+            .tx_output
+            .send(Ok(std::mem::take(&mut self.output)))
+            .await
+        {
+            error!("failed to send exec output: {}", e);
+        }
+    }
+    pub async fn work(mut self, docker: Docker) {
+        let container = docker.containers().get(&self.current_id);
+        let exec_stream = container.exec(&self.opts);
+        futures::pin_mut!(exec_stream);
+
+        loop {
+            tokio::select! {
+                chunk = exec_stream.next(), if !self.finished => {
+                    match chunk {
+                        Some(Ok(chunk)) => {
+                            log::trace!("adding exec chunk");
+                            self.output.0.push(chunk);
+                        }
+                        Some(Err(e)) => {
+                            let _ = self
+                                .tx_output
+                                .send(Err(anyhow!(
+                                    "failed to execute command in container `{}` - {}",
+                                    self.current_id,
+                                    e
+                                )))
+                                .await;
+                            self.finished = true;
+                        }
+                        None => {
+                            debug!("exec in container `{}` finished", self.current_id);
+                            self.finished = true;
+                        }
+                    }
+                }
+                event = self.rx_events.recv() => {
+                    match event {
+                        Some(WorkerEvent::PollData) => {
+                            self.send_output().await;
+                            if self.finished {
+                                break;
+                            }
+                        }
+                        Some(WorkerEvent::Kill) | None => break,
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,654 @@
+//! Browsing and copying files of a container through the archive API.
+
+use anyhow::{anyhow, Context, Result};
+use docker_api::{api::ExecContainerOpts, conn::TtyChunk, Docker};
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc;
+
+/// Files bigger than this are not previewed
+pub const PREVIEW_LIMIT: u64 = 1024 * 1024;
+/// Archives contain the whole tree of a directory, listing a directory through the archive API
+/// stops after reading this many entries so that big trees are only listed partially
+const MAX_ARCHIVE_ENTRIES: usize = 10_000;
+/// Maximum count of symbolic links followed to find the kind of the target of a link
+const MAX_LINK_HOPS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FileKind {
+    Directory,
+    File,
+    Symlink,
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileEntry {
+    pub name: String,
+    pub kind: FileKind,
+    pub size: u64,
+    /// Target of a symbolic link
+    pub link: Option<String>,
+    /// Kind of the target of a symbolic link, `None` when it couldn't be determined
+    pub link_kind: Option<FileKind>,
+}
+
+impl FileEntry {
+    /// Returns the kind of the entry, symbolic links are resolved to the kind of their target.
+    pub fn target_kind(&self) -> FileKind {
+        match self.kind {
+            FileKind::Symlink => self.link_kind.unwrap_or(FileKind::Other),
+            kind => kind,
+        }
+    }
+}
+
+/// Entries of a directory of a container.
+#[derive(Debug, Clone, Default)]
+pub struct DirListing {
+    pub entries: Vec<FileEntry>,
+    /// Set when only a part of the entries was listed as the directory tree is too big
+    pub truncated: bool,
+}
+
+/// Joins a directory of a container with the name of an entry in it.
+pub fn join_path(dir: &str, name: &str) -> String {
+    if dir.ends_with('/') {
+        format!("{}{}", dir, name)
+    } else {
+        format!("{}/{}", dir, name)
+    }
+}
+
+/// Returns the absolute path of the target of symbolic link `link` located in directory `dir`,
+/// `.` and `..` components are resolved.
+pub fn resolve_link(dir: &str, link: &str) -> String {
+    let path = if link.starts_with('/') {
+        link.to_string()
+    } else {
+        join_path(dir, link)
+    };
+    let mut components = vec![];
+    for component in path.split('/') {
+        match component {
+            "" | "." => {}
+            ".." => {
+                components.pop();
+            }
+            component => components.push(component),
+        }
+    }
+    format!("/{}", components.join("/"))
+}
+
+fn parent_dir(path: &str) -> String {
+    Path::new(path)
+        .parent()
+        .map(|parent| parent.to_string_lossy().to_string())
+        .unwrap_or_else(|| "/".to_string())
+}
+
+/// Lists the entries of directory `path` of a container. Running containers are listed with `ls`
+/// to avoid downloading the whole directory tree, stopped containers or containers without `ls`
+/// are listed through the archive API.
+pub async fn list_dir(docker: &Docker, id: &str, path: &str, running: bool) -> Result<DirListing> {
+    let mut listing = if running {
+        match list_dir_exec(docker, id, path).await {
+            Ok(entries) => DirListing {
+                entries,
+                truncated: false,
+            },
+            Err(e) => {
+                log::debug!("listing `{}` with ls failed, using archive - {:#}", path, e);
+                list_dir_archive(docker, id, path).await?
+            }
+        }
+    } else {
+        list_dir_archive(docker, id, path).await?
+    };
+    listing.entries.sort_by(|a, b| match a.kind.cmp(&b.kind) {
+        std::cmp::Ordering::Equal => a.name.cmp(&b.name),
+        cmp => cmp,
+    });
+    Ok(listing)
+}
+
+async fn list_dir_exec(docker: &Docker, id: &str, path: &str) -> Result<Vec<FileEntry>> {
+    // the trailing slash makes `ls` list the target of a symbolic link to a directory
+    let dir = format!("{}/", path.trim_end_matches('/'));
+    let mut entries = ls(docker, id, &dir, false).await?;
+    if entries.iter().any(|entry| entry.kind == FileKind::Symlink) {
+        // listing again with links dereferenced tells the kinds of their targets
+        match ls(docker, id, &dir, true).await {
+            Ok(targets) => {
+                let targets = targets
+                    .into_iter()
+                    .map(|entry| (entry.name.clone(), entry))
+                    .collect::<HashMap<_, _>>();
+                for entry in entries.iter_mut() {
+                    if entry.kind != FileKind::Symlink {
+                        continue;
+                    }
+                    if let Some(target) = targets.get(&entry.name) {
+                        // broken links are still listed as links
+                        if target.kind != FileKind::Symlink {
+                            entry.link_kind = Some(target.kind);
+                            entry.size = target.size;
+                        }
+                    }
+                }
+            }
+            Err(e) => log::debug!("resolving links of `{}` failed - {:#}", dir, e),
+        }
+    }
+    Ok(entries)
+}
+
+/// Runs `ls -lA` in directory `dir` of a container, links are followed with `dereference`.
+async fn ls(docker: &Docker, id: &str, dir: &str, dereference: bool) -> Result<Vec<FileEntry>> {
+    let flags = if dereference { "-lAL" } else { "-lA" };
+    let opts = ExecContainerOpts::builder()
+        .cmd(vec!["ls", flags, "--", dir])
+        .env(vec!["LC_ALL=C"])
+        .attach_stdout(true)
+        .attach_stderr(true)
+        .build();
+    let container = docker.containers().get(id);
+    let mut stream = container.exec(&opts);
+    let mut stdout = vec![];
+    let mut stderr = vec![];
+    while let Some(chunk) = stream.next().await {
+        match chunk.context("executing ls failed")? {
+            TtyChunk::StdOut(bytes) => stdout.extend(bytes),
+            TtyChunk::StdErr(bytes) => stderr.extend(bytes),
+            TtyChunk::StdIn(_) => {}
+        }
+    }
+    if stdout.is_empty() && !stderr.is_empty() {
+        return Err(anyhow!("{}", String::from_utf8_lossy(&stderr).trim()));
+    }
+
+    Ok(String::from_utf8_lossy(&stdout)
+        .lines()
+        .filter_map(parse_ls_line)
+        .collect())
+}
+
+/// Parses a line of `ls -l` output like
+/// `lrwxrwxrwx    1 root     root            12 Oct 16 12:00 sh -> /bin/busybox`.
+/// Device files have their major and minor numbers in place of the size like
+/// `crw-rw-rw-    1 root     root        1,   3 Oct 16 12:00 null`.
+fn parse_ls_line(line: &str) -> Option<FileEntry> {
+    let mut rest = line.trim_start();
+    let mut fields = Vec::with_capacity(9);
+    let mut count = 8;
+    while fields.len() < count {
+        let end = rest.find(char::is_whitespace)?;
+        fields.push(&rest[..end]);
+        rest = rest[end..].trim_start();
+        if fields.len() == 5 && fields[4].ends_with(',') {
+            count += 1;
+        }
+    }
+    let kind = match fields[0].chars().next()? {
+        'd' => FileKind::Directory,
+        '-' => FileKind::File,
+        'l' => FileKind::Symlink,
+        _ => FileKind::Other,
+    };
+    let (name, link) = match (kind, rest.split_once(" -> ")) {
+        (FileKind::Symlink, Some((name, link))) => (name, Some(link.to_string())),
+        _ => (rest, None),
+    };
+    if name.is_empty() || name == "." || name == ".." {
+        return None;
+    }
+
+    Some(FileEntry {
+        name: name.to_string(),
+        kind,
+        size: fields[4].parse().unwrap_or_default(),
+        link,
+        link_kind: None,
+    })
+}
+
+async fn list_dir_archive(docker: &Docker, id: &str, path: &str) -> Result<DirListing> {
+    let dir = path.to_string();
+    with_archive(docker, id, path, move |reader| {
+        read_dir_archive(reader, &dir)
+    })
+    .await
+}
+
+fn entry_kind(entry_type: tar::EntryType) -> FileKind {
+    match entry_type {
+        tar::EntryType::Regular | tar::EntryType::Continuous => FileKind::File,
+        tar::EntryType::Directory => FileKind::Directory,
+        tar::EntryType::Symlink => FileKind::Symlink,
+        _ => FileKind::Other,
+    }
+}
+
+/// Lists the entries of directory `path` from its archive. The archive contains the whole tree
+/// of the directory so it is read up to [`MAX_ARCHIVE_ENTRIES`](MAX_ARCHIVE_ENTRIES).
+fn read_dir_archive(reader: impl Read, path: &str) -> Result<DirListing> {
+    let dir = resolve_link("/", path);
+    // entries are relative to the parent of `path` unless it is the root directory
+    let root = if dir == "/" {
+        dir.clone()
+    } else {
+        parent_dir(&dir)
+    };
+
+    let mut archive = tar::Archive::new(reader);
+    let mut listing = DirListing::default();
+    // every entry of the tree is kept to find the kind of targets of links
+    let mut tree = HashMap::new();
+    for (i, entry) in archive
+        .entries()
+        .context("reading the archive failed")?
+        .enumerate()
+    {
+        if i == MAX_ARCHIVE_ENTRIES {
+            listing.truncated = true;
+            break;
+        }
+        let entry = entry.context("reading an archive entry failed")?;
+        let entry_path = resolve_link(&root, &entry.path()?.to_string_lossy());
+        let kind = entry_kind(entry.header().entry_type());
+        let link = entry
+            .link_name()?
+            .map(|link| link.to_string_lossy().to_string());
+        if entry_path != dir && parent_dir(&entry_path) == dir {
+            listing.entries.push(FileEntry {
+                name: entry_path
+                    .rsplit('/')
+                    .next()
+                    .unwrap_or_default()
+                    .to_string(),
+                kind,
+                size: entry.size(),
+                link: link.clone(),
+                link_kind: None,
+            });
+        }
+        tree.insert(entry_path, (kind, entry.size(), link));
+    }
+
+    for entry in listing.entries.iter_mut() {
+        let mut target = match (&entry.kind, &entry.link) {
+            (FileKind::Symlink, Some(link)) => resolve_link(&dir, link),
+            _ => continue,
+        };
+        for _ in 0..MAX_LINK_HOPS {
+            match tree.get(&target) {
+                Some((FileKind::Symlink, _, Some(link))) => {
+                    target = resolve_link(&parent_dir(&target), link)
+                }
+                Some((kind, size, _)) => {
+                    entry.link_kind = Some(*kind);
+                    entry.size = *size;
+                    break;
+                }
+                None => break,
+            }
+        }
+    }
+
+    Ok(listing)
+}
+
+/// Reads an archive sent by [`with_archive`](with_archive) chunk by chunk.
+struct ArchiveReader {
+    rx: mpsc::Receiver<io::Result<Vec<u8>>>,
+    chunk: Vec<u8>,
+    pos: usize,
+}
+
+impl Read for ArchiveReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.chunk.len() {
+            match self.rx.blocking_recv() {
+                Some(chunk) => {
+                    self.chunk = chunk?;
+                    self.pos = 0;
+                }
+                None => return Ok(0),
+            }
+        }
+        let len = buf.len().min(self.chunk.len() - self.pos);
+        buf[..len].copy_from_slice(&self.chunk[self.pos..self.pos + len]);
+        self.pos += len;
+        Ok(len)
+    }
+}
+
+/// Streams the archive of `path` of a container to `read` running on a blocking thread so that
+/// the archive is never held in memory as a whole. The download stops once `read` returns.
+async fn with_archive<T, F>(docker: &Docker, id: &str, path: &str, read: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce(ArchiveReader) -> Result<T> + Send + 'static,
+{
+    let (tx, rx) = mpsc::channel(16);
+    let reader = tokio::task::spawn_blocking(move || {
+        read(ArchiveReader {
+            rx,
+            chunk: vec![],
+            pos: 0,
+        })
+    });
+
+    let container = docker.containers().get(id);
+    let mut stream = container.copy_from(path);
+    while let Some(chunk) = stream.next().await {
+        let chunk =
+            chunk.map_err(|e| io::Error::other(format!("copying `{}` failed - {}", path, e)));
+        let failed = chunk.is_err();
+        // sending fails once the reader has read all it needs
+        if tx.send(chunk).await.is_err() || failed {
+            break;
+        }
+    }
+    drop(tx);
+
+    reader.await.context("reading the archive failed")?
+}
+
+/// Returns the contents of file `path` of a container.
+pub async fn read_file(docker: &Docker, id: &str, path: &str) -> Result<Vec<u8>> {
+    let file = path.to_string();
+    with_archive(docker, id, path, move |reader| {
+        let mut archive = tar::Archive::new(reader);
+        let mut entry = first_entry(&mut archive, &file)?;
+        if entry.size() > PREVIEW_LIMIT {
+            return Err(anyhow!("`{}` is too big to preview", file));
+        }
+        let mut data = Vec::with_capacity(entry.size() as usize);
+        entry
+            .read_to_end(&mut data)
+            .with_context(|| format!("reading `{}` failed", file))?;
+        Ok(data)
+    })
+    .await
+}
+
+/// Returns the entry of the archive of a single regular file.
+fn first_entry<'a, R: Read>(
+    archive: &'a mut tar::Archive<R>,
+    path: &str,
+) -> Result<tar::Entry<'a, R>> {
+    let entry = archive
+        .entries()
+        .context("reading the archive failed")?
+        .next()
+        .ok_or_else(|| anyhow!("the archive of `{}` is empty", path))?
+        .context("reading an archive entry failed")?;
+    if entry_kind(entry.header().entry_type()) != FileKind::File {
+        return Err(anyhow!("`{}` is not a regular file", path));
+    }
+    Ok(entry)
+}
+
+/// Copies file or directory `path` of a container to `output_path` on the host. A directory is
+/// saved as a tar archive when `archive` is set, otherwise the contents of a file are written.
+pub async fn download(
+    docker: &Docker,
+    id: &str,
+    path: &str,
+    output_path: PathBuf,
+    archive: bool,
+) -> Result<PathBuf> {
+    if archive {
+        let mut file = tokio::fs::File::create(&output_path)
+            .await
+            .with_context(|| format!("creating `{}` failed", output_path.display()))?;
+        let container = docker.containers().get(id);
+        let mut stream = container.copy_from(path);
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.with_context(|| format!("copying `{}` failed", path))?;
+            file.write_all(&chunk)
+                .await
+                .with_context(|| format!("writing `{}` failed", output_path.display()))?;
+        }
+        file.flush()
+            .await
+            .with_context(|| format!("writing `{}` failed", output_path.display()))?;
+        return Ok(output_path);
+    }
+
+    let file = path.to_string();
+    with_archive(docker, id, path, move |reader| {
+        let mut archive = tar::Archive::new(reader);
+        let mut entry = first_entry(&mut archive, &file)?;
+        let mut output = std::fs::File::create(&output_path)
+            .with_context(|| format!("creating `{}` failed", output_path.display()))?;
+        io::copy(&mut entry, &mut output)
+            .with_context(|| format!("writing `{}` failed", output_path.display()))?;
+        Ok(output_path)
+    })
+    .await
+}
+
+/// Copies file `input_path` from the host to directory `dir` of a container returning the path
+/// of the new file in the container.
+pub async fn upload(docker: &Docker, id: &str, dir: &str, input_path: &Path) -> Result<String> {
+    let name = input_path
+        .file_name()
+        .ok_or_else(|| anyhow!("`{}` is not a file", input_path.display()))?
+        .to_string_lossy()
+        .to_string();
+    let bytes = tokio::fs::read(input_path)
+        .await
+        .with_context(|| format!("reading `{}` failed", input_path.display()))?;
+    let path = join_path(dir, &name);
+    docker
+        .containers()
+        .get(id)
+        .copy_file_into(&path, &bytes)
+        .await
+        .with_context(|| format!("copying file to `{}` failed", path))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, kind: FileKind, size: u64, link: Option<&str>) -> FileEntry {
+        FileEntry {
+            name: name.to_string(),
+            kind,
+            size,
+            link: link.map(str::to_string),
+            link_kind: None,
+        }
+    }
+
+    fn header(path: &str, entry_type: tar::EntryType, size: u64) -> tar::Header {
+        let mut header = tar::Header::new_gnu();
+        header.set_path(path).unwrap();
+        header.set_entry_type(entry_type);
+        header.set_size(size);
+        header.set_mode(0o755);
+        header.set_cksum();
+        header
+    }
+
+    fn archive(build: impl FnOnce(&mut tar::Builder<Vec<u8>>)) -> Vec<u8> {
+        let mut builder = tar::Builder::new(vec![]);
+        build(&mut builder);
+        builder.into_inner().unwrap()
+    }
+
+    fn append_file(builder: &mut tar::Builder<Vec<u8>>, path: &str, data: &[u8]) {
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Regular);
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        builder.append_data(&mut header, path, data).unwrap();
+    }
+
+    fn append_dir(builder: &mut tar::Builder<Vec<u8>>, path: &str) {
+        builder
+            .append(&header(path, tar::EntryType::Directory, 0), io::empty())
+            .unwrap();
+    }
+
+    fn append_link(builder: &mut tar::Builder<Vec<u8>>, path: &str, target: &str) {
+        let mut header = header(path, tar::EntryType::Symlink, 0);
+        header.set_link_name(target).unwrap();
+        header.set_cksum();
+        builder.append(&header, io::empty()).unwrap();
+    }
+
+    /// Returns a record of a PAX extended header like `30 path=some/long/path\n`.
+    fn pax_record(key: &str, value: &str) -> String {
+        let record = format!(" {}={}\n", key, value);
+        // the length of a record includes the length field itself
+        let mut len = record.len();
+        while len != len.to_string().len() + record.len() {
+            len = len.to_string().len() + record.len();
+        }
+        format!("{}{}", len, record)
+    }
+
+    #[test]
+    fn parses_ls_lines() {
+        assert_eq!(
+            parse_ls_line("-rw-r--r--    1 root     root          1024 Oct 16 12:00 my file.txt"),
+            Some(entry("my file.txt", FileKind::File, 1024, None))
+        );
+        assert_eq!(
+            parse_ls_line("drwxr-xr-x    2 root     root          4096 Oct 16 12:00 etc"),
+            Some(entry("etc", FileKind::Directory, 4096, None))
+        );
+        assert_eq!(
+            parse_ls_line(
+                "lrwxrwxrwx    1 root     root            12 Oct 16 12:00 sh -> /bin/busybox"
+            ),
+            Some(entry("sh", FileKind::Symlink, 12, Some("/bin/busybox")))
+        );
+        assert_eq!(
+            parse_ls_line("crw-rw-rw-    1 root     root        1,   3 Oct 16 12:00 null"),
+            Some(entry("null", FileKind::Other, 0, None))
+        );
+        assert_eq!(
+            parse_ls_line("crw-rw-rw- 1 root root 1, 3 Oct 16 12:00 null"),
+            Some(entry("null", FileKind::Other, 0, None))
+        );
+        assert_eq!(
+            parse_ls_line("drwxr-xr-x    1 root     root          4096 Oct 16 12:00 .."),
+            None
+        );
+        assert_eq!(parse_ls_line("total 12"), None);
+    }
+
+    #[test]
+    fn joins_paths() {
+        assert_eq!(join_path("/", "etc"), "/etc");
+        assert_eq!(join_path("/etc", "hosts"), "/etc/hosts");
+        assert_eq!(join_path("/etc/", "hosts"), "/etc/hosts");
+    }
+
+    #[test]
+    fn resolves_links() {
+        assert_eq!(resolve_link("/bin", "/usr/bin/env"), "/usr/bin/env");
+        assert_eq!(resolve_link("/bin", "busybox"), "/bin/busybox");
+        assert_eq!(resolve_link("/usr/lib/", "../../lib/./x"), "/lib/x");
+        assert_eq!(resolve_link("/", "../../etc"), "/etc");
+    }
+
+    #[test]
+    fn lists_first_level_of_archive() {
+        let data = archive(|builder| {
+            append_dir(builder, "etc/");
+            append_file(builder, "etc/hosts", b"127.0.0.1 localhost\n");
+            append_dir(builder, "etc/ssl/");
+            append_file(builder, "etc/ssl/cert.pem", b"cert");
+            append_link(builder, "etc/cert.pem", "ssl/cert.pem");
+            append_link(builder, "etc/certs", "ssl");
+            append_link(builder, "etc/chain", "cert.pem");
+            append_link(builder, "etc/broken", "missing");
+            // longer than the 100 bytes of the name field of the header
+            let long_name = format!("etc/{}", "a".repeat(120));
+            append_file(builder, &long_name, b"long");
+        });
+
+        let mut listing = read_dir_archive(&data[..], "/etc").unwrap();
+        listing.entries.sort_by(|a, b| a.name.cmp(&b.name));
+        assert!(!listing.truncated);
+
+        let mut cert = entry("cert.pem", FileKind::Symlink, 4, Some("ssl/cert.pem"));
+        cert.link_kind = Some(FileKind::File);
+        let mut certs = entry("certs", FileKind::Symlink, 0, Some("ssl"));
+        certs.link_kind = Some(FileKind::Directory);
+        let mut chain = entry("chain", FileKind::Symlink, 4, Some("cert.pem"));
+        chain.link_kind = Some(FileKind::File);
+        assert_eq!(
+            listing.entries,
+            vec![
+                entry(&"a".repeat(120), FileKind::File, 4, None),
+                entry("broken", FileKind::Symlink, 0, Some("missing")),
+                cert,
+                certs,
+                chain,
+                entry("hosts", FileKind::File, 20, None),
+                entry("ssl", FileKind::Directory, 0, None),
+            ]
+        );
+        assert_eq!(listing.entries[1].target_kind(), FileKind::Other);
+    }
+
+    #[test]
+    fn lists_archive_with_pax_paths() {
+        let name = format!("{}/file", "b".repeat(150));
+        let record = pax_record("path", &name);
+
+        let data = archive(|builder| {
+            let mut pax = header("PaxHeaders/file", tar::EntryType::XHeader, 0);
+            pax.set_size(record.len() as u64);
+            pax.set_cksum();
+            builder.append(&pax, record.as_bytes()).unwrap();
+            append_file(builder, "truncated", b"pax");
+        });
+
+        let listing = read_dir_archive(&data[..], &format!("/{}", "b".repeat(150))).unwrap();
+        assert_eq!(
+            listing.entries,
+            vec![entry("file", FileKind::File, 3, None)]
+        );
+    }
+
+    #[test]
+    fn truncates_big_archives() {
+        let data = archive(|builder| {
+            append_dir(builder, "data/");
+            for i in 0..MAX_ARCHIVE_ENTRIES {
+                append_file(builder, &format!("data/{}", i), b"");
+            }
+        });
+
+        let listing = read_dir_archive(&data[..], "/data").unwrap();
+        assert!(listing.truncated);
+        assert_eq!(listing.entries.len(), MAX_ARCHIVE_ENTRIES - 1);
+    }
+
+    #[test]
+    fn reads_first_entry_of_archive() {
+        let data = archive(|builder| append_file(builder, "hosts", b"127.0.0.1 localhost\n"));
+        let mut tar = tar::Archive::new(&data[..]);
+        let mut entry = first_entry(&mut tar, "/etc/hosts").unwrap();
+        let mut contents = String::new();
+        entry.read_to_string(&mut contents).unwrap();
+        assert_eq!(contents, "127.0.0.1 localhost\n");
+
+        let data = archive(|builder| append_dir(builder, "etc/"));
+        let mut tar = tar::Archive::new(&data[..]);
+        assert!(first_entry(&mut tar, "/etc").is_err());
+    }
+}
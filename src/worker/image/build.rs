@@ -0,0 +1,110 @@
+use crate::worker::WorkerEvent;
+
+use anyhow::Error;
+use docker_api::{
+    api::{BuildOpts, ImageBuildChunk, ImageId},
+    Docker,
+};
+use futures::StreamExt;
+use log::error;
+use tokio::sync::mpsc;
+
+#[derive(Debug)]
+pub struct ImageBuildWorker {
+    pub opts: BuildOpts,
+    pub rx_events: mpsc::Receiver<WorkerEvent>,
+    pub tx_results: mpsc::Sender<anyhow::Result<ImageId>>,
+    pub tx_chunks: mpsc::Sender<Vec<ImageBuildChunk>>,
+}
+
+/// A new build worker with the channels to send it events and to receive build chunks and the ID of
+/// the built image
+pub type ImageBuildWorkerParts = (
+    ImageBuildWorker,
+    mpsc::Sender<WorkerEvent>,
+    mpsc::Receiver<Vec<ImageBuildChunk>>,
+    mpsc::Receiver<anyhow::Result<ImageId>>,
+);
+
+impl ImageBuildWorker {
+    pub fn new(opts: BuildOpts) -> ImageBuildWorkerParts {
+        let (tx_results, rx_results) = mpsc::channel::<anyhow::Result<ImageId>>(128);
+        let (tx_chunks, rx_chunks) = mpsc::channel::<Vec<ImageBuildChunk>>(128);
+        let (tx_events, rx_events) = mpsc::channel::<WorkerEvent>(128);
+
+        (
+            Self {
+                opts,
+                rx_events,
+                tx_chunks,
+                tx_results,
+            },
+            tx_events,
+            rx_chunks,
+            rx_results,
+        )
+    }
+    pub async fn work(mut self, docker: Docker) {
+        log::trace!("starting image build");
+        let images = docker.images();
+        let mut build_stream = images.build(&self.opts);
+        let mut chunks = vec![];
+        let mut image_id = None;
+
+        macro_rules! finish {
+            ($result:expr) => {
+                let _ = self.tx_results.send($result).await;
+                if let Err(e) = self.tx_chunks.try_send(std::mem::take(&mut chunks)) {
+                    error!("failed to send image build chunks: {}", e);
+                }
+                return;
+            };
+        }
+        loop {
+            tokio::select! {
+                chunk = build_stream.next() => {
+                    match chunk {
+                        Some(Ok(chunk)) => {
+                            log::trace!("{:?}", chunk);
+                            match &chunk {
+                                ImageBuildChunk::Digest { aux } => image_id = Some(aux.id.clone()),
+                                ImageBuildChunk::Update { stream } => {
+                                    // the legacy builder reports the id only in the output
+                                    if let Some(id) = stream.trim().strip_prefix("Successfully built ") {
+                                        image_id = Some(id.to_string());
+                                    }
+                                }
+                                ImageBuildChunk::Error { error, .. } => {
+                                    let error = error.clone();
+                                    chunks.push(chunk);
+                                    finish!(Err(Error::msg(error)));
+                                }
+                                _ => {}
+                            }
+                            chunks.push(chunk);
+                        }
+                        Some(Err(e)) => {
+                            finish!(Err(Error::msg(format!("image build failed - {}", e))));
+                        }
+                        None => {
+                            log::trace!("image build finished successfuly");
+                            finish!(image_id
+                                .take()
+                                .ok_or_else(|| Error::msg("image build finished without an image id")));
+                        }
+                    }
+                }
+                event = self.rx_events.recv() => {
+                    match event {
+                        Some(WorkerEvent::PollData) =>
+                        if let Err(e) = self.tx_chunks.send(std::mem::take(&mut chunks)).await {
+                            error!("failed to send image build chunks: {}", e);
+                        },
+                        Some(WorkerEvent::Kill) => break,
+                        None => continue,
+                    }
+                }
+            }
+        }
+    }
+}
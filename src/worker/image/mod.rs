@@ -1,3 +1,5 @@
+pub mod build;
 pub mod export;
 pub mod import;
 pub mod pull;
+pub mod push;
@@ -0,0 +1,117 @@
+use crate::worker::WorkerEvent;
+
+use anyhow::Error;
+use docker_api::{
+    api::{ImageBuildChunk, ImagePushOpts, RegistryAuth},
+    Docker,
+};
+use futures::StreamExt;
+use log::error;
+use tokio::sync::mpsc;
+
+#[derive(Debug)]
+pub struct ImagePushWorker {
+    pub image: String,
+    pub tag: Option<String>,
+    pub auth: Option<RegistryAuth>,
+    pub rx_events: mpsc::Receiver<WorkerEvent>,
+    pub tx_results: mpsc::Sender<anyhow::Result<String>>,
+    pub tx_chunks: mpsc::Sender<Vec<ImageBuildChunk>>,
+}
+
+/// A new push worker with the channels to send it events and to receive push chunks and the result
+pub type ImagePushWorkerParts = (
+    ImagePushWorker,
+    mpsc::Sender<WorkerEvent>,
+    mpsc::Receiver<Vec<ImageBuildChunk>>,
+    mpsc::Receiver<anyhow::Result<String>>,
+);
+
+impl ImagePushWorker {
+    pub fn new(
+        image: String,
+        tag: Option<String>,
+        auth: Option<RegistryAuth>,
+    ) -> ImagePushWorkerParts {
+        let (tx_results, rx_results) = mpsc::channel::<anyhow::Result<String>>(128);
+        let (tx_chunks, rx_chunks) = mpsc::channel::<Vec<ImageBuildChunk>>(128);
+        let (tx_events, rx_events) = mpsc::channel::<WorkerEvent>(128);
+
+        (
+            Self {
+                image,
+                tag,
+                auth,
+                rx_events,
+                tx_chunks,
+                tx_results,
+            },
+            tx_events,
+            rx_chunks,
+            rx_results,
+        )
+    }
+    pub async fn work(mut self, docker: Docker) {
+        log::trace!("starting image `{}` push", self.image);
+        let mut opts = ImagePushOpts::builder();
+        if let Some(tag) = &self.tag {
+            opts = opts.tag(tag);
+        }
+        if let Some(auth) = self.auth.take() {
+            opts = opts.auth(auth);
+        }
+        let opts = opts.build();
+        let reference = match &self.tag {
+            Some(tag) => format!("{}:{}", self.image, tag),
+            None => self.image.clone(),
+        };
+        let images = docker.images();
+        let image = images.get(&self.image);
+        let mut push_stream = image.push(&opts);
+        let mut chunks = vec![];
+
+        macro_rules! finish {
+            ($result:expr) => {
+                let _ = self.tx_results.send($result).await;
+                if let Err(e) = self.tx_chunks.try_send(std::mem::take(&mut chunks)) {
+                    error!("failed to send image push chunks: {}", e);
+                }
+                return;
+            };
+        }
+        loop {
+            tokio::select! {
+                chunk = push_stream.next() => {
+                    match chunk {
+                        Some(Ok(chunk)) => {
+                            log::trace!("{:?}", chunk);
+                            if let ImageBuildChunk::Error { error, .. } = &chunk {
+                                let error = error.clone();
+                                chunks.push(chunk);
+                                finish!(Err(Error::msg(error)));
+                            }
+                            chunks.push(chunk);
+                        }
+                        Some(Err(e)) => {
+                            finish!(Err(Error::msg(format!("image push failed - {}", e))));
+                        }
+                        None => {
+                            log::trace!("image `{}` push finished successfuly", reference);
+                            finish!(Ok(reference));
+                        }
+                    }
+                }
+                event = self.rx_events.recv() => {
+                    match event {
+                        Some(WorkerEvent::PollData) =>
+                        if let Err(e) = self.tx_chunks.send(std::mem::take(&mut chunks)).await {
+                            error!("failed to send image push chunks: {}", e);
+                        },
+                        Some(WorkerEvent::Kill) => break,
+                        None => continue,
+                    }
+                }
+            }
+        }
+    }
+}
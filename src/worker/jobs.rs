@@ -0,0 +1,360 @@
+use crate::event::{ContainerEventResponse, EventResponse, ImageEventResponse};
+use crate::worker::{
+    ContainerExportWorker, ImageBuildWorker, ImageExportWorker, ImageImportWorker, ImagePullWorker,
+    ImagePushWorker, WorkerEvent,
+};
+
+use anyhow::anyhow;
+use docker_api::{
+    api::{BuildOpts, ContainerId, ImageBuildChunk, ImageId, RegistryAuth},
+    Docker,
+};
+use log::{error, trace};
+use std::path::PathBuf;
+use tokio::sync::mpsc::{self, error::TryRecvError};
+
+/// Maximum number of jobs running at the same time, the rest waits in the queue.
+const MAX_CONCURRENT_JOBS: usize = 3;
+
+pub type JobId = usize;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum JobKind {
+    Pull { image: String },
+    Import { path: PathBuf },
+    Export { image: ImageId, path: PathBuf },
+    ContainerExport { id: ContainerId, path: PathBuf },
+    Build { context: String, tag: String },
+    Push { image: String, tag: Option<String> },
+}
+
+impl std::fmt::Display for JobKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JobKind::Pull { image } => write!(f, "pull {}", image),
+            JobKind::Import { path } => write!(f, "import {}", path.display()),
+            JobKind::Export { image, path } => {
+                write!(f, "export {} to {}", image, path.display())
+            }
+            JobKind::ContainerExport { id, path } => {
+                write!(f, "export container {} to {}", id, path.display())
+            }
+            JobKind::Build { context, tag } if tag.is_empty() => write!(f, "build {}", context),
+            JobKind::Build { context, tag } => write!(f, "build {} from {}", tag, context),
+            JobKind::Push {
+                image,
+                tag: Some(tag),
+            } => write!(f, "push {}:{}", image, tag),
+            JobKind::Push { image, tag: None } => write!(f, "push {}", image),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Finished(String),
+    Failed(String),
+    Cancelled,
+}
+
+impl JobStatus {
+    pub fn is_active(&self) -> bool {
+        matches!(self, JobStatus::Queued | JobStatus::Running)
+    }
+}
+
+/// State of a job along with the progress reported since the last poll.
+#[derive(Debug)]
+pub struct JobUpdate {
+    pub id: JobId,
+    pub kind: JobKind,
+    pub status: JobStatus,
+    pub chunks: Vec<ImageBuildChunk>,
+}
+
+enum JobResults {
+    Image(mpsc::Receiver<anyhow::Result<ImageId>>),
+    /// Reference of a pushed image
+    Push(mpsc::Receiver<anyhow::Result<String>>),
+    /// Exports of images and containers
+    Export(mpsc::Receiver<anyhow::Result<(String, PathBuf)>>),
+}
+
+struct RunningJob {
+    tx_event: mpsc::Sender<WorkerEvent>,
+    /// Workers that don't report any progress have no chunks
+    rx_chunks: Option<mpsc::Receiver<Vec<ImageBuildChunk>>>,
+    rx_results: JobResults,
+}
+
+struct Job {
+    id: JobId,
+    kind: JobKind,
+    status: JobStatus,
+    auth: Option<RegistryAuth>,
+    build_opts: Option<BuildOpts>,
+    running: Option<RunningJob>,
+    chunks: Vec<ImageBuildChunk>,
+}
+
+impl Job {
+    fn start(&mut self, docker: &Docker) {
+        trace!("starting job {} `{}`", self.id, self.kind);
+        let running = match &self.kind {
+            JobKind::Pull { image } => {
+                let w = ImagePullWorker::new(image.clone(), self.auth.take());
+                let _ = tokio::spawn(w.0.work(docker.clone()));
+                RunningJob {
+                    tx_event: w.1,
+                    rx_chunks: Some(w.2),
+                    rx_results: JobResults::Image(w.3),
+                }
+            }
+            JobKind::Import { path } => {
+                let w = ImageImportWorker::new(path);
+                let _ = tokio::spawn(w.0.work(docker.clone()));
+                RunningJob {
+                    tx_event: w.1,
+                    rx_chunks: Some(w.2),
+                    rx_results: JobResults::Image(w.3),
+                }
+            }
+            JobKind::Export { image, path } => {
+                let w = ImageExportWorker::new(image.clone(), path.clone());
+                let _ = tokio::spawn(w.0.work(docker.clone()));
+                RunningJob {
+                    tx_event: w.1,
+                    rx_chunks: None,
+                    rx_results: JobResults::Export(w.2),
+                }
+            }
+            JobKind::ContainerExport { id, path } => {
+                let w = ContainerExportWorker::new(id.clone(), path.clone());
+                let _ = tokio::spawn(w.0.work(docker.clone()));
+                RunningJob {
+                    tx_event: w.1,
+                    rx_chunks: None,
+                    rx_results: JobResults::Export(w.2),
+                }
+            }
+            JobKind::Build { context, .. } => {
+                // the options are only missing when the build wasn't added with `add_build`
+                let opts = self
+                    .build_opts
+                    .take()
+                    .unwrap_or_else(|| BuildOpts::builder(context).build());
+                let w = ImageBuildWorker::new(opts);
+                let _ = tokio::spawn(w.0.work(docker.clone()));
+                RunningJob {
+                    tx_event: w.1,
+                    rx_chunks: Some(w.2),
+                    rx_results: JobResults::Image(w.3),
+                }
+            }
+            JobKind::Push { image, tag } => {
+                let w = ImagePushWorker::new(image.clone(), tag.clone(), self.auth.take());
+                let _ = tokio::spawn(w.0.work(docker.clone()));
+                RunningJob {
+                    tx_event: w.1,
+                    rx_chunks: Some(w.2),
+                    rx_results: JobResults::Push(w.3),
+                }
+            }
+        };
+        self.running = Some(running);
+        self.status = JobStatus::Running;
+    }
+
+    /// Checks whether the job finished returning the response that should be sent to the gui.
+    async fn check_finished(&mut self) -> Option<EventResponse> {
+        let running = self.running.as_mut()?;
+        macro_rules! try_result {
+            ($rx:expr) => {
+                match $rx.try_recv() {
+                    Ok(result) => result,
+                    Err(TryRecvError::Empty) => return None,
+                    Err(TryRecvError::Disconnected) => Err(anyhow!("job stopped unexpectedly")),
+                }
+            };
+        }
+        let rsp = match &mut running.rx_results {
+            JobResults::Image(rx) => {
+                let result = try_result!(rx);
+                self.status = match &result {
+                    Ok(id) => JobStatus::Finished(id.clone()),
+                    Err(e) => JobStatus::Failed(format!("{:#}", e)),
+                };
+                EventResponse::Image(match &self.kind {
+                    JobKind::Pull { image } => ImageEventResponse::Pull {
+                        image: image.clone(),
+                        result,
+                    },
+                    JobKind::Build { .. } => ImageEventResponse::Build(result),
+                    _ => ImageEventResponse::Import(result),
+                })
+            }
+            JobResults::Push(rx) => {
+                let result = try_result!(rx);
+                self.status = match &result {
+                    Ok(reference) => JobStatus::Finished(reference.clone()),
+                    Err(e) => JobStatus::Failed(format!("{:#}", e)),
+                };
+                EventResponse::Image(ImageEventResponse::Push(result))
+            }
+            JobResults::Export(rx) => {
+                let result = try_result!(rx);
+                self.status = match &result {
+                    Ok((_, path)) => JobStatus::Finished(path.display().to_string()),
+                    Err(e) => JobStatus::Failed(format!("{:#}", e)),
+                };
+                match &self.kind {
+                    JobKind::ContainerExport { .. } => {
+                        EventResponse::Container(ContainerEventResponse::Export(result))
+                    }
+                    _ => EventResponse::Image(ImageEventResponse::Save(result)),
+                }
+            }
+        };
+        // the remaining chunks are sent before the worker exits
+        if let Some(rx_chunks) = running.rx_chunks.as_mut() {
+            if let Some(chunks) = rx_chunks.recv().await {
+                self.chunks.extend(chunks);
+            }
+        }
+        self.running = None;
+        Some(rsp)
+    }
+}
+
+/// Keeps track of long running image transfers like pulls, imports and exports as well as image
+/// builds.
+#[derive(Default)]
+pub struct JobManager {
+    next_id: JobId,
+    jobs: Vec<Job>,
+}
+
+impl JobManager {
+    /// Queues a new job, it is started once there is a free slot.
+    pub fn add(&mut self, kind: JobKind, auth: Option<RegistryAuth>) -> JobId {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.jobs.push(Job {
+            id,
+            kind,
+            status: JobStatus::Queued,
+            auth,
+            build_opts: None,
+            running: None,
+            chunks: vec![],
+        });
+        id
+    }
+
+    /// Queues a new image build, `kind` describes the build in the jobs list.
+    pub fn add_build(&mut self, kind: JobKind, opts: BuildOpts) -> JobId {
+        let id = self.add(kind, None);
+        if let Some(job) = self.jobs.last_mut() {
+            job.build_opts = Some(opts);
+        }
+        id
+    }
+
+    /// Whether a job of the same kind is queued or running.
+    pub fn is_active(&self, kind: &JobKind) -> bool {
+        self.jobs
+            .iter()
+            .any(|j| &j.kind == kind && j.status.is_active())
+    }
+
+    pub fn start_queued(&mut self, docker: &Docker) {
+        let mut running = self.jobs.iter().filter(|j| j.running.is_some()).count();
+        for job in &mut self.jobs {
+            if running >= MAX_CONCURRENT_JOBS {
+                break;
+            }
+            if job.status == JobStatus::Queued {
+                job.start(docker);
+                running += 1;
+            }
+        }
+    }
+
+    /// Collects the progress of all running jobs and returns the state of every job.
+    pub async fn poll(&mut self) -> Vec<JobUpdate> {
+        for job in &mut self.jobs {
+            let running = match job.running.as_mut() {
+                Some(running) => running,
+                None => continue,
+            };
+            let rx_chunks = match running.rx_chunks.as_mut() {
+                Some(rx_chunks) => rx_chunks,
+                None => continue,
+            };
+            if running.tx_event.send(WorkerEvent::PollData).await.is_err() {
+                // the worker finished, remaining chunks are collected along with the result
+                continue;
+            }
+            if let Some(chunks) = rx_chunks.recv().await {
+                job.chunks.extend(chunks);
+            }
+        }
+        self.jobs
+            .iter_mut()
+            .map(|job| JobUpdate {
+                id: job.id,
+                kind: job.kind.clone(),
+                status: job.status.clone(),
+                chunks: std::mem::take(&mut job.chunks),
+            })
+            .collect()
+    }
+
+    /// Returns responses of all jobs that finished since the last check.
+    pub async fn finished(&mut self) -> Vec<EventResponse> {
+        let mut responses = vec![];
+        for job in &mut self.jobs {
+            if let Some(rsp) = job.check_finished().await {
+                trace!("job {} `{}` finished", job.id, job.kind);
+                responses.push(rsp);
+            }
+        }
+        responses
+    }
+
+    /// Cancels a queued or running job. Returns `false` if there is no such active job.
+    pub async fn cancel(&mut self, id: JobId) -> bool {
+        let job = match self
+            .jobs
+            .iter_mut()
+            .find(|j| j.id == id && j.status.is_active())
+        {
+            Some(job) => job,
+            None => return false,
+        };
+        if let Some(running) = job.running.take() {
+            if let Err(e) = running.tx_event.send(WorkerEvent::Kill).await {
+                error!("failed to kill worker of job {}: {}", id, e);
+            }
+        }
+        job.status = JobStatus::Cancelled;
+        true
+    }
+
+    /// Removes all jobs that are no longer active.
+    pub fn clear(&mut self) {
+        self.jobs.retain(|j| j.status.is_active());
+    }
+
+    pub async fn kill_all(&mut self) {
+        for job in self.jobs.drain(..) {
+            if let Some(running) = job.running {
+                if let Err(e) = running.tx_event.send(WorkerEvent::Kill).await {
+                    error!("failed to kill worker of job {}: {}", job.id, e);
+                }
+            }
+        }
+    }
+}
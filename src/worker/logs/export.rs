@@ -0,0 +1,242 @@
+use crate::worker::WorkerEvent;
+
+use anyhow::Error;
+use docker_api::{
+    api::{ContainerId, LogsOpts},
+    conn::TtyChunk,
+    Docker,
+};
+use futures::StreamExt;
+use log::error;
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use tokio::sync::mpsc;
+
+/// Progress is reported every time this many lines are written
+const PROGRESS_STEP: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LogsExportFormat {
+    PlainText,
+    JsonLines,
+}
+
+impl AsRef<str> for LogsExportFormat {
+    fn as_ref(&self) -> &str {
+        match &self {
+            LogsExportFormat::PlainText => "plain text",
+            LogsExportFormat::JsonLines => "JSON lines",
+        }
+    }
+}
+
+impl Default for LogsExportFormat {
+    fn default() -> Self {
+        Self::PlainText
+    }
+}
+
+#[derive(Serialize)]
+struct LogEntry<'a> {
+    stream: &'a str,
+    timestamp: &'a str,
+    message: &'a str,
+}
+
+#[derive(Debug)]
+pub struct LogsExportWorker {
+    pub container_id: ContainerId,
+    pub output_path: PathBuf,
+    pub format: LogsExportFormat,
+    /// Whether to prefix lines with timestamps when exporting as plain text
+    pub timestamps: bool,
+    pub rx_events: mpsc::Receiver<WorkerEvent>,
+    pub tx_progress: mpsc::Sender<usize>,
+    pub tx_results: mpsc::Sender<anyhow::Result<(ContainerId, PathBuf)>>,
+    pub lines_written: usize,
+}
+
+/// A new logs export worker with the channels to send it events and to receive the number of
+/// written lines and the result
+pub type LogsExportWorkerParts = (
+    LogsExportWorker,
+    mpsc::Sender<WorkerEvent>,
+    mpsc::Receiver<usize>,
+    mpsc::Receiver<anyhow::Result<(ContainerId, PathBuf)>>,
+);
+
+impl LogsExportWorker {
+    pub fn new(
+        container_id: ContainerId,
+        output_path: PathBuf,
+        format: LogsExportFormat,
+        timestamps: bool,
+    ) -> LogsExportWorkerParts {
+        let (tx_results, rx_results) = mpsc::channel::<anyhow::Result<(ContainerId, PathBuf)>>(128);
+        let (tx_progress, rx_progress) = mpsc::channel::<usize>(128);
+        let (tx_events, rx_events) = mpsc::channel::<WorkerEvent>(128);
+
+        (
+            Self {
+                container_id,
+                output_path,
+                format,
+                timestamps,
+                rx_events,
+                tx_progress,
+                tx_results,
+                lines_written: 0,
+            },
+            tx_events,
+            rx_progress,
+            rx_results,
+        )
+    }
+
+    fn write_line(
+        &mut self,
+        writer: &mut impl Write,
+        stream: &str,
+        line: &str,
+    ) -> std::io::Result<()> {
+        let line = line.trim_end_matches(&['\r', '\n'][..]);
+        let escaped_bytes =
+            strip_ansi_escapes::strip(line.as_bytes()).unwrap_or_else(|_| line.as_bytes().to_vec());
+        let line = String::from_utf8_lossy(&escaped_bytes);
+        // the logs are always requested with timestamps that prefix each line
+        let (timestamp, message) = line.split_once(' ').unwrap_or(("", &line));
+
+        match self.format {
+            LogsExportFormat::PlainText if self.timestamps => {
+                writeln!(writer, "{} {}", timestamp, message)?
+            }
+            LogsExportFormat::PlainText => writeln!(writer, "{}", message)?,
+            LogsExportFormat::JsonLines => {
+                serde_json::to_writer(
+                    &mut *writer,
+                    &LogEntry {
+                        stream,
+                        timestamp,
+                        message,
+                    },
+                )?;
+                writer.write_all(b"\n")?;
+            }
+        }
+
+        self.lines_written += 1;
+        if self.lines_written % PROGRESS_STEP == 0 {
+            let _ = self.tx_progress.try_send(self.lines_written);
+        }
+        Ok(())
+    }
+
+    async fn finish(self, result: anyhow::Result<()>) {
+        let Self {
+            container_id,
+            output_path,
+            tx_results,
+            ..
+        } = self;
+        let _ = tx_results
+            .send(result.map(|_| (container_id, output_path)))
+            .await;
+    }
+
+    pub async fn work(mut self, docker: Docker) {
+        log::trace!("starting container `{}` logs export", self.container_id);
+        let file = match OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&self.output_path)
+        {
+            Ok(f) => f,
+            Err(e) => {
+                self.finish(Err(Error::msg(format!(
+                    "opening file to export logs failed - {}",
+                    e
+                ))))
+                .await;
+                return;
+            }
+        };
+        let mut writer = BufWriter::new(file);
+        let container = docker.containers().get(&self.container_id);
+        let mut logs_stream = container.logs(
+            &LogsOpts::builder()
+                .stderr(true)
+                .stdout(true)
+                .timestamps(true)
+                .all()
+                .build(),
+        );
+        // incomplete lines of stdout and stderr
+        let mut pending = [String::new(), String::new()];
+
+        loop {
+            tokio::select! {
+                log_data = logs_stream.next() => {
+                    match log_data {
+                        Some(Ok(chunk)) => {
+                            let (i, data) = match chunk {
+                                TtyChunk::StdOut(data) => (0, data),
+                                TtyChunk::StdErr(data) => (1, data),
+                                TtyChunk::StdIn(_) => continue,
+                            };
+                            pending[i].push_str(&String::from_utf8_lossy(&data));
+                            while let Some(pos) = pending[i].find('\n') {
+                                let line = pending[i].drain(..=pos).collect::<String>();
+                                let stream = if i == 0 { "stdout" } else { "stderr" };
+                                if let Err(e) = self.write_line(&mut writer, stream, &line) {
+                                    error!("{}", e);
+                                    self.finish(Err(Error::msg(format!(
+                                        "writing logs to file failed - {}",
+                                        e
+                                    ))))
+                                    .await;
+                                    return;
+                                }
+                            }
+                        }
+                        Some(Err(e)) => {
+                            self.finish(Err(Error::msg(format!(
+                                "failed to read container logs - {}",
+                                e
+                            ))))
+                            .await;
+                            return;
+                        }
+                        None => {
+                            let mut result = Ok(());
+                            for (line, stream) in pending.iter().zip(["stdout", "stderr"]) {
+                                if !line.is_empty() {
+                                    if let Err(e) = self.write_line(&mut writer, stream, line) {
+                                        result = Err(e);
+                                    }
+                                }
+                            }
+                            let result = result
+                                .and_then(|_| writer.flush())
+                                .map_err(|e| Error::msg(format!("writing logs to file failed - {}", e)));
+                            log::trace!(
+                                "container `{}` logs export finished",
+                                self.container_id
+                            );
+                            self.finish(result).await;
+                            return;
+                        }
+                    }
+                }
+                event = self.rx_events.recv() => {
+                    match event {
+                        Some(WorkerEvent::Kill) => break,
+                        _ => continue,
+                    }
+                }
+            }
+        }
+    }
+}
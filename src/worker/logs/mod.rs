@@ -1,5 +1,8 @@
+pub mod export;
+
 use crate::worker::WorkerEvent;
 
+use chrono::{DateTime, Utc};
 use docker_api::{
     api::{ContainerId, LogsOpts},
     conn::TtyChunk,
@@ -12,17 +15,44 @@ use tokio::sync::mpsc;
 #[derive(Debug, Default, Clone)]
 pub struct Logs(pub Vec<TtyChunk>);
 
+/// Options used when opening the logs stream of a container
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogsConfig {
+    pub timestamps: bool,
+    /// Count of lines initially read from the end of the logs, whole history is read when `None`
+    pub tail: Option<usize>,
+    /// Only read logs created after this date
+    pub since: Option<DateTime<Utc>>,
+    /// Maximum count of lines kept by the logs view, older lines are dropped
+    pub max_lines: usize,
+}
+
+impl Default for LogsConfig {
+    fn default() -> Self {
+        Self {
+            timestamps: false,
+            tail: Some(1000),
+            since: None,
+            max_lines: 10_000,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct LogsWorker {
     pub current_id: ContainerId,
+    pub config: LogsConfig,
     pub rx_events: mpsc::Receiver<WorkerEvent>,
     pub tx_logs: mpsc::Sender<Box<Logs>>,
-    pub logs: Box<Logs>,
+    /// Chunks read since the last poll, the view polls them often enough and bounds the count of
+    /// lines itself so that no line is cut in half here
+    pub chunks: Vec<TtyChunk>,
 }
 
 impl LogsWorker {
     pub fn new(
         current_id: impl Into<ContainerId>,
+        config: LogsConfig,
     ) -> (Self, mpsc::Sender<WorkerEvent>, mpsc::Receiver<Box<Logs>>) {
         let (tx_logs, rx_logs) = mpsc::channel::<Box<Logs>>(128);
         let (tx_events, rx_events) = mpsc::channel::<WorkerEvent>(128);
@@ -30,9 +60,10 @@ impl LogsWorker {
         (
             Self {
                 current_id: current_id.into(),
+                config,
                 rx_events,
                 tx_logs,
-                logs: Box::new(Logs(vec![])),
+                chunks: vec![],
             },
             tx_events,
             rx_logs,
@@ -40,27 +71,33 @@ impl LogsWorker {
     }
     async fn send_logs(&mut self) {
         debug!("got poll data request, sending logs");
-        if let Err(e) = self.tx_logs.send(std::mem::take(&mut self.logs)).await {
+        let logs = Box::new(Logs(self.chunks.drain(..).collect()));
+        if let Err(e) = self.tx_logs.send(logs).await {
             error!("failed to send container logs: {}", e);
         }
     }
     pub async fn work(mut self, docker: Docker) {
         let container = docker.containers().get(&self.current_id);
-        let mut logs_stream = container.logs(
-            &LogsOpts::builder()
-                .stderr(true)
-                .stdout(true)
-                .follow(true)
-                .all()
-                .build(),
-        );
+        let mut opts = LogsOpts::builder()
+            .stderr(true)
+            .stdout(true)
+            .follow(true)
+            .timestamps(self.config.timestamps);
+        opts = match self.config.tail {
+            Some(n_lines) => opts.n_lines(n_lines),
+            None => opts.all(),
+        };
+        if let Some(since) = &self.config.since {
+            opts = opts.since(since);
+        }
+        let mut logs_stream = container.logs(&opts.build());
         loop {
             tokio::select! {
                 log_data = logs_stream.next() => {
                     match log_data {
                         Some(Ok(chunk)) => {
                             log::trace!("adding chunk");
-                            self.logs.0.push(chunk);
+                            self.chunks.push(chunk);
                         }
                         Some(Err(e)) => {
                             match e {
@@ -1,27 +1,46 @@
+mod attach;
+mod container_export;
 mod events;
+mod exec;
+mod files;
 mod image;
+mod jobs;
 mod logs;
 mod stats;
 
+use crate::app::credentials;
 use crate::event::{
     ContainerEvent, ContainerEventResponse, EventRequest, EventResponse, ImageEvent,
-    ImageEventResponse, ImageInspectInfo, NetworkEvent, NetworkEventResponse, SystemInspectInfo,
-    VolumeEvent, VolumeEventResponse,
+    ImageEventResponse, ImageInspectInfo, JobsEvent, JobsEventResponse, LogsExportStatus,
+    NetworkEvent, NetworkEventResponse, SystemInspectInfo, VolumeEvent, VolumeEventResponse,
 };
+pub use attach::AttachWorker;
+pub use container_export::ContainerExportWorker;
 pub use events::EventsWorker;
-pub use image::{export::ImageExportWorker, import::ImageImportWorker, pull::ImagePullWorker};
-pub use logs::{Logs, LogsWorker};
+pub use exec::ExecWorker;
+pub use files::{join_path, resolve_link, DirListing, FileEntry, FileKind, PREVIEW_LIMIT};
+pub use image::{
+    build::ImageBuildWorker, export::ImageExportWorker, import::ImageImportWorker,
+    pull::ImagePullWorker, push::ImagePushWorker,
+};
+pub use jobs::{JobId, JobKind, JobManager, JobStatus, JobUpdate};
+pub use logs::{
+    export::{LogsExportFormat, LogsExportWorker},
+    Logs, LogsConfig, LogsWorker,
+};
 pub use stats::{RunningContainerStats, StatsWorker};
 
 use anyhow::{anyhow, Context, Result};
 use docker_api::{
     api::{
-        ClearCacheOpts, ContainerId, Event, ImageBuildChunk, ImageId, ImageListOpts,
-        ImagePruneOpts, ImagesPruneFilter, RmContainerOpts, RmImageOpts,
+        ClearCacheOpts, ContainerConnectionOpts, ContainerCreateOpts, ContainerDisconnectionOpts,
+        ContainerId, EndpointIpamConfig, Event, ExecContainerOpts, ImageListOpts, ImagePruneOpts,
+        ImagesPruneFilter, NetworkId, NetworkInfo, RmContainerOpts, RmImageOpts,
     },
     Docker,
 };
 use log::{debug, error, trace};
+use std::collections::HashMap;
 use std::time::Duration;
 use tokio::sync::mpsc;
 
@@ -127,7 +146,8 @@ async fn inner_work(
     };
 
     loop {
-        check_image_in_progress_events(&mut workers).await;
+        check_jobs(&docker, &mut workers).await;
+        check_container_in_progress_events(&mut workers).await;
 
         if let Some(req) = inner_rx_req.recv().await {
             let event_str = format!("{:?}", req);
@@ -145,7 +165,7 @@ async fn inner_work(
 struct WorkerHandles {
     current_uri: String,
     containers: ContainerWorkerHandles,
-    images: ImageWorkerHandles,
+    jobs: JobManager,
     tx_sys_events_event: mpsc::Sender<WorkerEvent>,
     rx_sys_events: mpsc::Receiver<Vec<Event>>,
     tx_rsp: mpsc::Sender<EventResponse>,
@@ -156,7 +176,7 @@ impl Default for WorkerHandles {
         Self {
             current_uri: String::new(),
             containers: ContainerWorkerHandles::default(),
-            images: ImageWorkerHandles::default(),
+            jobs: JobManager::default(),
             tx_sys_events_event: mpsc::channel::<WorkerEvent>(1).0,
             rx_sys_events: mpsc::channel::<Vec<Event>>(1).1,
             tx_rsp: mpsc::channel::<EventResponse>(1).0,
@@ -166,54 +186,146 @@ impl Default for WorkerHandles {
 
 struct ContainerWorkerHandles {
     current_id: Option<ContainerId>,
+    logs_config: LogsConfig,
     tx_logs_event: mpsc::Sender<WorkerEvent>,
     rx_logs: mpsc::Receiver<Box<Logs>>,
     tx_stats_event: mpsc::Sender<WorkerEvent>,
     rx_stats: mpsc::Receiver<Box<RunningContainerStats>>,
+    attach_id: Option<ContainerId>,
+    tx_attach_event: mpsc::Sender<WorkerEvent>,
+    tx_attach_input: mpsc::Sender<Vec<u8>>,
+    rx_attach_output: mpsc::Receiver<anyhow::Result<Box<Logs>>>,
+    exec_in_progress: bool,
+    tx_exec_event: mpsc::Sender<WorkerEvent>,
+    rx_exec_output: mpsc::Receiver<anyhow::Result<Box<Logs>>>,
+    logs_export_in_progress: bool,
+    tx_logs_export_event: mpsc::Sender<WorkerEvent>,
+    rx_logs_export_progress: mpsc::Receiver<usize>,
+    rx_logs_export_results: mpsc::Receiver<anyhow::Result<(ContainerId, std::path::PathBuf)>>,
+    aggregated_logs: AggregatedLogsHandles,
 }
 
 impl Default for ContainerWorkerHandles {
     fn default() -> Self {
         Self {
             current_id: None,
+            logs_config: LogsConfig::default(),
             tx_logs_event: mpsc::channel::<WorkerEvent>(1).0,
             rx_logs: mpsc::channel::<Box<Logs>>(1).1,
             tx_stats_event: mpsc::channel::<WorkerEvent>(1).0,
             rx_stats: mpsc::channel::<Box<RunningContainerStats>>(1).1,
+            attach_id: None,
+            tx_attach_event: mpsc::channel::<WorkerEvent>(1).0,
+            tx_attach_input: mpsc::channel::<Vec<u8>>(1).0,
+            rx_attach_output: mpsc::channel::<anyhow::Result<Box<Logs>>>(1).1,
+            exec_in_progress: false,
+            tx_exec_event: mpsc::channel::<WorkerEvent>(1).0,
+            rx_exec_output: mpsc::channel::<anyhow::Result<Box<Logs>>>(1).1,
+            logs_export_in_progress: false,
+            tx_logs_export_event: mpsc::channel::<WorkerEvent>(1).0,
+            rx_logs_export_progress: mpsc::channel::<usize>(1).1,
+            rx_logs_export_results: mpsc::channel::<
+                anyhow::Result<(ContainerId, std::path::PathBuf)>,
+            >(1)
+            .1,
+            aggregated_logs: AggregatedLogsHandles::default(),
         }
     }
 }
 
-struct ImageWorkerHandles {
-    pull_in_progress: bool,
-    export_in_progress: bool,
-    import_in_progress: bool,
-    tx_pull_event: mpsc::Sender<WorkerEvent>,
-    rx_pull_chunks: mpsc::Receiver<Vec<ImageBuildChunk>>,
-    rx_pull_results: mpsc::Receiver<anyhow::Result<ImageId>>,
-    tx_export_event: mpsc::Sender<WorkerEvent>,
-    rx_export_results: mpsc::Receiver<anyhow::Result<(ImageId, std::path::PathBuf)>>,
-    tx_import_event: mpsc::Sender<WorkerEvent>,
-    rx_import_chunks: mpsc::Receiver<Vec<ImageBuildChunk>>,
-    rx_import_results: mpsc::Receiver<anyhow::Result<ImageId>>,
+/// Count of lines initially read from each container of the aggregated logs
+const AGGREGATED_LOGS_TAIL: usize = 100;
+
+impl ContainerWorkerHandles {
+    /// Kills the logs export worker returning whether an export was in progress.
+    async fn kill_logs_export(&mut self) -> bool {
+        if !self.logs_export_in_progress {
+            return false;
+        }
+        if let Err(e) = self.tx_logs_export_event.send(WorkerEvent::Kill).await {
+            error!("failed to kill logs export worker: {}", e);
+        }
+        self.logs_export_in_progress = false;
+        true
+    }
 }
 
-impl Default for ImageWorkerHandles {
-    fn default() -> Self {
-        Self {
-            pull_in_progress: false,
-            export_in_progress: false,
-            import_in_progress: false,
-            tx_pull_event: mpsc::channel::<WorkerEvent>(1).0,
-            rx_pull_chunks: mpsc::channel::<Vec<ImageBuildChunk>>(1).1,
-            rx_pull_results: mpsc::channel::<anyhow::Result<ImageId>>(1).1,
-            tx_export_event: mpsc::channel::<WorkerEvent>(1).0,
-            rx_export_results: mpsc::channel::<anyhow::Result<(ImageId, std::path::PathBuf)>>(1).1,
-            tx_import_event: mpsc::channel::<WorkerEvent>(1).0,
-            rx_import_chunks: mpsc::channel::<Vec<ImageBuildChunk>>(1).1,
-            rx_import_results: mpsc::channel::<anyhow::Result<ImageId>>(1).1,
+#[derive(Default)]
+/// Logs workers of all containers displayed in the aggregated logs view, these are independent
+/// of the worker following the currently inspected container.
+struct AggregatedLogsHandles {
+    workers: HashMap<ContainerId, (mpsc::Sender<WorkerEvent>, mpsc::Receiver<Box<Logs>>)>,
+}
+
+impl AggregatedLogsHandles {
+    /// Spawns logs workers for new containers and kills workers of containers not in `ids`.
+    async fn set_containers(&mut self, ids: Vec<ContainerId>, docker: &Docker) {
+        let removed = self
+            .workers
+            .keys()
+            .filter(|id| !ids.contains(id))
+            .cloned()
+            .collect::<Vec<_>>();
+        for id in removed {
+            if let Some((tx_event, _)) = self.workers.remove(&id) {
+                if let Err(e) = tx_event.send(WorkerEvent::Kill).await {
+                    error!("failed to kill aggregated logs worker of `{}`: {}", id, e);
+                }
+            }
+        }
+
+        for id in ids {
+            if self.workers.contains_key(&id) {
+                continue;
+            }
+            let config = LogsConfig {
+                // timestamps are required to merge lines of different containers
+                timestamps: true,
+                tail: Some(AGGREGATED_LOGS_TAIL),
+                ..Default::default()
+            };
+            let w = LogsWorker::new(&id, config);
+            let _ = tokio::spawn(w.0.work(docker.clone()));
+            self.workers.insert(id, (w.1, w.2));
+        }
+    }
+
+    /// Collects logs read by all workers since the last poll.
+    async fn poll(&mut self) -> Vec<(ContainerId, Box<Logs>)> {
+        let mut logs = vec![];
+        let mut finished = vec![];
+        for (id, (tx_event, rx_logs)) in self.workers.iter_mut() {
+            if tx_event.send(WorkerEvent::PollData).await.is_err() {
+                // the worker exits when the container is removed
+                finished.push(id.clone());
+                continue;
+            }
+            match rx_logs.recv().await {
+                Some(chunks) => logs.push((id.clone(), chunks)),
+                None => finished.push(id.clone()),
+            }
+        }
+        for id in finished {
+            trace!("aggregated logs worker of `{}` finished", id);
+            self.workers.remove(&id);
+        }
+        logs
+    }
+
+    async fn kill_all(&mut self) {
+        for (id, (tx_event, _)) in self.workers.drain() {
+            if let Err(e) = tx_event.send(WorkerEvent::Kill).await {
+                error!("failed to kill aggregated logs worker of `{}`: {}", id, e);
+            }
         }
     }
+
+    /// Replaces the workers of all followed containers with ones using `docker`.
+    async fn respawn(&mut self, docker: &Docker) {
+        let ids = self.workers.keys().cloned().collect::<Vec<_>>();
+        self.kill_all().await;
+        self.set_containers(ids, docker).await;
+    }
 }
 
 async fn handle_event(
@@ -228,6 +340,7 @@ async fn handle_event(
                 event,
                 &mut workers.tx_rsp,
                 &mut workers.containers,
+                &mut workers.jobs,
             )
             .await
             {
@@ -240,7 +353,7 @@ async fn handle_event(
             }
         }
         EventRequest::Image(event) => {
-            match handle_image_event(docker, event, &mut workers.images).await {
+            match handle_image_event(docker, event, &mut workers.jobs).await {
                 Ok(Some(rsp)) => Some(rsp),
                 Ok(None) => None,
                 Err(e) => {
@@ -259,16 +372,7 @@ async fn handle_event(
                 Ok(d) => d,
                 Err(e) => return Some(EventResponse::DockerUriChange(Err(e))),
             };
-            if workers.images.pull_in_progress {
-                if let Err(e) = workers.images.tx_pull_event.send(WorkerEvent::Kill).await {
-                    error!("failed to kill image pull worker: {}", e);
-                }
-            }
-            if workers.images.export_in_progress {
-                if let Err(e) = workers.images.tx_export_event.send(WorkerEvent::Kill).await {
-                    error!("failed to kill image export worker: {}", e);
-                }
-            }
+            workers.jobs.kill_all().await;
 
             if let Some(id) = workers.containers.current_id.as_ref() {
                 if let Err(e) = workers
@@ -293,11 +397,45 @@ async fn handle_event(
                 workers.containers.rx_stats = s.2;
                 let _ = tokio::spawn(s.0.work(docker.clone()));
 
-                let w = LogsWorker::new(id);
+                let w = LogsWorker::new(id, workers.containers.logs_config.clone());
                 workers.containers.tx_logs_event = w.1;
                 workers.containers.rx_logs = w.2;
                 let _ = tokio::spawn(w.0.work(docker.clone()));
             }
+            if workers.containers.kill_logs_export().await {
+                let rsp = EventResponse::Container(ContainerEventResponse::ExportLogs(
+                    LogsExportStatus::Cancelled,
+                ));
+                let _ = workers.tx_rsp.send(rsp).await;
+            }
+            workers.containers.aggregated_logs.respawn(docker).await;
+            if workers.containers.exec_in_progress {
+                if let Err(e) = workers
+                    .containers
+                    .tx_exec_event
+                    .send(WorkerEvent::Kill)
+                    .await
+                {
+                    error!("failed to kill exec worker: {}", e);
+                }
+                workers.containers.exec_in_progress = false;
+            }
+            if let Some(id) = workers.containers.attach_id.as_ref() {
+                if let Err(e) = workers
+                    .containers
+                    .tx_attach_event
+                    .send(WorkerEvent::Kill)
+                    .await
+                {
+                    error!("failed to kill attach worker: {}", e);
+                }
+
+                let a = AttachWorker::new(id);
+                workers.containers.tx_attach_event = a.1;
+                workers.containers.tx_attach_input = a.2;
+                workers.containers.rx_attach_output = a.3;
+                let _ = tokio::spawn(a.0.work(docker.clone()));
+            }
             Some(EventResponse::DockerUriChange(Ok(())))
         }
         EventRequest::SystemInspect => {
@@ -326,6 +464,18 @@ async fn handle_event(
             }
         }
         EventRequest::NotifyGui(event) => Some(EventResponse::NotifyGui(event.into())),
+        EventRequest::DockerCredentials => {
+            // credential helpers are external programs that can wait for user input
+            let tx_rsp = workers.tx_rsp.clone();
+            tokio::spawn(async move {
+                let res = tokio::task::spawn_blocking(credentials::docker_config)
+                    .await
+                    .context("reading docker credentials failed")
+                    .and_then(|res| res);
+                let _ = tx_rsp.send(EventResponse::DockerCredentials(res)).await;
+            });
+            None
+        }
         EventRequest::SystemEvents => {
             if let Err(e) = workers
                 .tx_sys_events_event
@@ -350,6 +500,27 @@ async fn handle_event(
                         .context("deleting network failed"),
                 )))
             }
+            NetworkEvent::Inspect { id } => {
+                Some(EventResponse::Network(NetworkEventResponse::Inspect(
+                    docker
+                        .networks()
+                        .get(&id)
+                        .inspect()
+                        .await
+                        .map(Box::new)
+                        .context("inspecting network failed"),
+                )))
+            }
+            NetworkEvent::Topology { ids } => {
+                // inspecting every network takes a while so it doesn't block other events
+                let docker = docker.clone();
+                let tx_rsp = workers.tx_rsp.clone();
+                tokio::spawn(async move {
+                    let rsp = NetworkEventResponse::Topology(network_topology(&docker, ids).await);
+                    let _ = tx_rsp.send(EventResponse::Network(rsp)).await;
+                });
+                None
+            }
             NetworkEvent::List(opts) => Some(EventResponse::Network(NetworkEventResponse::List(
                 match docker
                     .networks()
@@ -381,6 +552,70 @@ async fn handle_event(
                         .context("failed to create a network"),
                 )))
             }
+            NetworkEvent::Connect {
+                network,
+                container,
+                aliases,
+                ipv4,
+                ipv6,
+            } => {
+                let mut opts = ContainerConnectionOpts::builder(&container);
+                if !aliases.is_empty() {
+                    opts = opts.aliases(aliases);
+                }
+                if ipv4.is_some() || ipv6.is_some() {
+                    let mut ipam = EndpointIpamConfig::new();
+                    if let Some(ipv4) = ipv4 {
+                        ipam = ipam.ipv4(ipv4);
+                    }
+                    if let Some(ipv6) = ipv6 {
+                        ipam = ipam.ipv6(ipv6);
+                    }
+                    opts = opts.ipam_config(ipam);
+                }
+                Some(EventResponse::Network(NetworkEventResponse::Connect(
+                    docker
+                        .networks()
+                        .get(&network)
+                        .connect(&opts.build())
+                        .await
+                        .map(|_| (network, container))
+                        .context("connecting container to network failed"),
+                )))
+            }
+            NetworkEvent::Disconnect {
+                network,
+                container,
+                force,
+            } => {
+                let opts = ContainerDisconnectionOpts::builder(&container)
+                    .force(force)
+                    .build();
+                Some(EventResponse::Network(NetworkEventResponse::Disconnect(
+                    docker
+                        .networks()
+                        .get(&network)
+                        .disconnect(&opts)
+                        .await
+                        .map(|_| (network, container))
+                        .context("disconnecting container from network failed"),
+                )))
+            }
+        },
+        EventRequest::Jobs(event) => match event {
+            JobsEvent::List => Some(EventResponse::Jobs(JobsEventResponse::List(
+                workers.jobs.poll().await,
+            ))),
+            JobsEvent::Cancel { id } => {
+                if workers.jobs.cancel(id).await {
+                    workers.jobs.start_queued(docker);
+                }
+                None
+            }
+            JobsEvent::Clear => {
+                workers.jobs.clear();
+                None
+            }
         },
         EventRequest::Volume(event) => match event {
             VolumeEvent::Delete { id } => Some(EventResponse::Volume(VolumeEventResponse::Delete(
@@ -424,6 +659,7 @@ async fn handle_container_event(
     event: ContainerEvent,
     tx_rsp: &mut mpsc::Sender<EventResponse>,
     container_workers: &mut ContainerWorkerHandles,
+    jobs: &mut JobManager,
 ) -> Result<Option<EventResponse>> {
     match event {
         ContainerEvent::List(opts) => {
@@ -457,6 +693,27 @@ async fn handle_container_event(
                 }
             }
 
+            if container_workers.attach_id.is_some() {
+                if let Err(e) = container_workers
+                    .tx_attach_event
+                    .send(WorkerEvent::Kill)
+                    .await
+                {
+                    error!("failed to send kill event to attach worker: {}", e);
+                }
+                container_workers.attach_id = None;
+            }
+            if container_workers.exec_in_progress {
+                if let Err(e) = container_workers
+                    .tx_exec_event
+                    .send(WorkerEvent::Kill)
+                    .await
+                {
+                    error!("failed to send kill event to exec worker: {}", e);
+                }
+                container_workers.exec_in_progress = false;
+            }
+
             container_workers.current_id = Some(id.clone());
 
             let s = StatsWorker::new(&id);
@@ -464,7 +721,7 @@ async fn handle_container_event(
             container_workers.rx_stats = s.2;
             let _ = tokio::spawn(s.0.work(docker.clone()));
 
-            let w = LogsWorker::new(&id);
+            let w = LogsWorker::new(&id, container_workers.logs_config.clone());
             container_workers.tx_logs_event = w.1;
             container_workers.rx_logs = w.2;
             let _ = tokio::spawn(w.0.work(docker.clone()));
@@ -556,6 +813,214 @@ async fn handle_container_event(
                 Ok(None)
             }
         }
+        ContainerEvent::Attach { id } => {
+            if container_workers.attach_id.is_some() {
+                if let Err(e) = container_workers
+                    .tx_attach_event
+                    .send(WorkerEvent::Kill)
+                    .await
+                {
+                    error!("failed to send kill event to attach worker: {}", e);
+                }
+            }
+
+            let a = AttachWorker::new(&id);
+            container_workers.tx_attach_event = a.1;
+            container_workers.tx_attach_input = a.2;
+            container_workers.rx_attach_output = a.3;
+            let _ = tokio::spawn(a.0.work(docker.clone()));
+
+            container_workers.attach_id = Some(id);
+            Ok(None)
+        }
+        ContainerEvent::AttachInput { input } => {
+            if let Err(e) = container_workers.tx_attach_input.send(input).await {
+                return Err(anyhow!("failed to send input to attached container: {}", e));
+            }
+            Ok(None)
+        }
+        ContainerEvent::AttachOutput => {
+            if container_workers.attach_id.is_none() {
+                return Ok(None);
+            }
+            if let Err(e) = container_workers
+                .tx_attach_event
+                .send(WorkerEvent::PollData)
+                .await
+            {
+                // the worker might have already exited, any remaining output is still buffered
+                // in the channel
+                trace!("failed to notify attach worker: {}", e);
+            }
+            trace!("notified attach worker to poll data, reading output");
+            if let Some(output) = container_workers.rx_attach_output.recv().await {
+                Ok(Some(EventResponse::Container(
+                    ContainerEventResponse::AttachOutput(output),
+                )))
+            } else {
+                container_workers.attach_id = None;
+                Ok(Some(EventResponse::Container(
+                    ContainerEventResponse::Detached,
+                )))
+            }
+        }
+        ContainerEvent::Detach => {
+            if container_workers.attach_id.take().is_some() {
+                if let Err(e) = container_workers
+                    .tx_attach_event
+                    .send(WorkerEvent::Kill)
+                    .await
+                {
+                    error!("failed to send kill event to attach worker: {}", e);
+                }
+            }
+            Ok(Some(EventResponse::Container(
+                ContainerEventResponse::Detached,
+            )))
+        }
+        ContainerEvent::Exec {
+            id,
+            cmd,
+            env,
+            user,
+            workdir,
+        } => {
+            if container_workers.exec_in_progress {
+                if let Err(e) = container_workers
+                    .tx_exec_event
+                    .send(WorkerEvent::Kill)
+                    .await
+                {
+                    error!("failed to send kill event to exec worker: {}", e);
+                }
+            }
+
+            let mut opts = ExecContainerOpts::builder()
+                .cmd(cmd)
+                .attach_stdout(true)
+                .attach_stderr(true);
+            if !env.is_empty() {
+                opts = opts.env(env);
+            }
+            if let Some(user) = user.as_deref() {
+                opts = opts.user(user);
+            }
+            if let Some(workdir) = workdir.as_deref() {
+                opts = opts.working_dir(workdir);
+            }
+
+            let w = ExecWorker::new(&id, opts.build());
+            container_workers.tx_exec_event = w.1;
+            container_workers.rx_exec_output = w.2;
+            let _ = tokio::spawn(w.0.work(docker.clone()));
+
+            container_workers.exec_in_progress = true;
+            Ok(None)
+        }
+        ContainerEvent::ExecOutput => {
+            if !container_workers.exec_in_progress {
+                return Ok(None);
+            }
+            if let Err(e) = container_workers
+                .tx_exec_event
+                .send(WorkerEvent::PollData)
+                .await
+            {
+                // the worker exits once the command finishes, the remaining output is still
+                // buffered in the channel
+                trace!("failed to notify exec worker: {}", e);
+            }
+            trace!("notified exec worker to poll data, reading output");
+            if let Some(output) = container_workers.rx_exec_output.recv().await {
+                Ok(Some(EventResponse::Container(
+                    ContainerEventResponse::ExecOutput(output),
+                )))
+            } else {
+                container_workers.exec_in_progress = false;
+                Ok(Some(EventResponse::Container(
+                    ContainerEventResponse::ExecFinished,
+                )))
+            }
+        }
+        ContainerEvent::ExecDetach => {
+            if container_workers.exec_in_progress {
+                if let Err(e) = container_workers
+                    .tx_exec_event
+                    .send(WorkerEvent::Kill)
+                    .await
+                {
+                    error!("failed to send kill event to exec worker: {}", e);
+                }
+                container_workers.exec_in_progress = false;
+            }
+            Ok(Some(EventResponse::Container(
+                ContainerEventResponse::ExecFinished,
+            )))
+        }
+        ContainerEvent::ConfigureLogs(config) => {
+            container_workers.logs_config = config;
+            if let Some(id) = container_workers.current_id.as_ref() {
+                if let Err(e) = container_workers
+                    .tx_logs_event
+                    .send(WorkerEvent::Kill)
+                    .await
+                {
+                    error!("failed to send kill event to log worker: {}", e);
+                }
+
+                let w = LogsWorker::new(id, container_workers.logs_config.clone());
+                container_workers.tx_logs_event = w.1;
+                container_workers.rx_logs = w.2;
+                let _ = tokio::spawn(w.0.work(docker.clone()));
+            }
+            Ok(Some(EventResponse::Container(
+                ContainerEventResponse::LogsConfigured,
+            )))
+        }
+        ContainerEvent::ExportLogs {
+            id,
+            output_path,
+            format,
+            timestamps,
+        } => {
+            if container_workers.logs_export_in_progress {
+                return Err(anyhow!("another export of logs is already in progress"));
+            }
+            let d = docker.clone();
+            let w = LogsExportWorker::new(id, output_path, format, timestamps);
+            container_workers.tx_logs_export_event = w.1;
+            container_workers.rx_logs_export_progress = w.2;
+            container_workers.rx_logs_export_results = w.3;
+            tokio::task::spawn(async move {
+                w.0.work(d).await;
+            });
+            container_workers.logs_export_in_progress = true;
+            Ok(Some(EventResponse::Container(
+                ContainerEventResponse::ExportLogs(LogsExportStatus::InProgress { lines: 0 }),
+            )))
+        }
+        ContainerEvent::CancelLogsExport => {
+            if container_workers.kill_logs_export().await {
+                Ok(Some(EventResponse::Container(
+                    ContainerEventResponse::ExportLogs(LogsExportStatus::Cancelled),
+                )))
+            } else {
+                Ok(None)
+            }
+        }
+        ContainerEvent::AggregateLogs { ids } => {
+            container_workers
+                .aggregated_logs
+                .set_containers(ids, docker)
+                .await;
+            Ok(None)
+        }
+        ContainerEvent::AggregatedLogs => {
+            let logs = container_workers.aggregated_logs.poll().await;
+            Ok(Some(EventResponse::Container(
+                ContainerEventResponse::AggregatedLogs(logs),
+            )))
+        }
         ContainerEvent::Pause { id } => Ok(Some(EventResponse::Container(
             ContainerEventResponse::Pause(
                 docker
@@ -596,14 +1061,109 @@ async fn handle_container_event(
                     .context("starting container"),
             ),
         ))),
-        ContainerEvent::Create(opts) => Ok(Some(EventResponse::Container(
+        ContainerEvent::Create {
+            opts,
+            networks,
+            aliases,
+            start,
+        } => Ok(Some(EventResponse::Container(
             ContainerEventResponse::Create(
+                create_container(docker, &opts, &networks, &aliases, start).await,
+            ),
+        ))),
+        ContainerEvent::Recreate {
+            id,
+            image,
+            opts,
+            networks,
+            start,
+        } => {
+            let result = recreate_container(docker, &id, &image, &opts, &networks, start).await;
+            Ok(Some(EventResponse::Container(
+                ContainerEventResponse::Recreate(result),
+            )))
+        }
+        ContainerEvent::Export { id, output_path } => {
+            jobs.add(
+                JobKind::ContainerExport {
+                    id,
+                    path: output_path,
+                },
+                None,
+            );
+            jobs.start_queued(docker);
+            Ok(Some(EventResponse::Jobs(JobsEventResponse::List(
+                jobs.poll().await,
+            ))))
+        }
+        ContainerEvent::Commit { id, opts } => Ok(Some(EventResponse::Container(
+            ContainerEventResponse::Commit(
                 docker
                     .containers()
-                    .create(&opts)
+                    .get(&id)
+                    .commit(&opts)
+                    .await
+                    .context("committing container failed"),
+            ),
+        ))),
+        // copying files can take a while so it doesn't block handling of other events
+        ContainerEvent::ListDir { id, path, running } => {
+            let docker = docker.clone();
+            let tx_rsp = tx_rsp.clone();
+            tokio::spawn(async move {
+                let result = files::list_dir(&docker, &id, &path, running)
+                    .await
+                    .with_context(|| format!("listing directory `{}` failed", path));
+                let rsp = ContainerEventResponse::ListDir { path, result };
+                let _ = tx_rsp.send(EventResponse::Container(rsp)).await;
+            });
+            Ok(None)
+        }
+        ContainerEvent::CopyFrom {
+            id,
+            path,
+            output_path,
+            archive,
+        } => {
+            let docker = docker.clone();
+            let tx_rsp = tx_rsp.clone();
+            tokio::spawn(async move {
+                let rsp = match output_path {
+                    Some(output_path) => ContainerEventResponse::CopyFrom(
+                        files::download(&docker, &id, &path, output_path, archive).await,
+                    ),
+                    None => {
+                        let result = files::read_file(&docker, &id, &path).await;
+                        ContainerEventResponse::FilePreview { path, result }
+                    }
+                };
+                let _ = tx_rsp.send(EventResponse::Container(rsp)).await;
+            });
+            Ok(None)
+        }
+        ContainerEvent::CopyTo {
+            id,
+            path,
+            input_path,
+        } => {
+            let docker = docker.clone();
+            let tx_rsp = tx_rsp.clone();
+            tokio::spawn(async move {
+                let result = files::upload(&docker, &id, &path, &input_path).await;
+                let rsp = ContainerEventResponse::CopyTo(result);
+                let _ = tx_rsp.send(EventResponse::Container(rsp)).await;
+            });
+            Ok(None)
+        }
+        ContainerEvent::Update { id, opts } => Ok(Some(EventResponse::Container(
+            ContainerEventResponse::Update(
+                docker
+                    .containers()
+                    .get(&id)
+                    .update(&opts)
                     .await
-                    .map(|c| c.id().to_string())
-                    .context("failed to create a container"),
+                    .map(|_| id)
+                    .context("updating container failed"),
             ),
         ))),
         ContainerEvent::Rename { id, name } => {
@@ -702,10 +1262,207 @@ async fn handle_container_event(
     }
 }
 
+/// Creates a container, connects it to additional `networks` and optionally starts it. The
+/// container is removed again if connecting or starting it fails.
+async fn create_container(
+    docker: &Docker,
+    opts: &ContainerCreateOpts,
+    networks: &[String],
+    aliases: &[String],
+    start: bool,
+) -> Result<ContainerId> {
+    let container = docker
+        .containers()
+        .create(opts)
+        .await
+        .context("failed to create a container")?;
+    let id = container.id().to_string();
+    let set_up = async {
+        let attached = if networks.is_empty() && aliases.is_empty() {
+            vec![]
+        } else {
+            // the network of the network mode is already attached when the container is created
+            container
+                .inspect()
+                .await
+                .context("inspecting the created container failed")?
+                .network_settings
+                .networks
+                .into_keys()
+                .collect()
+        };
+        let connection_opts = || {
+            let mut opts = ContainerConnectionOpts::builder(&id);
+            if !aliases.is_empty() {
+                opts = opts.aliases(aliases);
+            }
+            opts.build()
+        };
+        if !aliases.is_empty() {
+            // aliases can't be set for the network of the network mode when creating a container
+            // so it's connected again with them
+            for name in &attached {
+                let network = docker.networks().get(name);
+                network
+                    .disconnect(&ContainerDisconnectionOpts::builder(&id).build())
+                    .await
+                    .with_context(|| {
+                        format!("disconnecting container from network `{}` failed", name)
+                    })?;
+                network.connect(&connection_opts()).await.with_context(|| {
+                    format!("connecting container to network `{}` failed", name)
+                })?;
+            }
+        }
+        for network in networks.iter().filter(|n| !attached.contains(n)) {
+            docker
+                .networks()
+                .get(network)
+                .connect(&connection_opts())
+                .await
+                .with_context(|| format!("connecting container to network `{}` failed", network))?;
+        }
+        if start {
+            container
+                .start()
+                .await
+                .context("starting container failed")?;
+        }
+        Ok::<_, anyhow::Error>(())
+    }
+    .await;
+
+    if let Err(e) = set_up {
+        return match container
+            .remove(&RmContainerOpts::builder().force(true).build())
+            .await
+        {
+            Ok(_) => Err(e.context(format!("created container {} was removed", id))),
+            Err(rm_e) => Err(e.context(format!(
+                "removing created container {} failed - {}",
+                id, rm_e
+            ))),
+        };
+    }
+    Ok(id)
+}
+
+/// Replaces the container `id` with a new one created from `opts`. The original container is
+/// renamed and stopped first so that the replacement can take over its name and ports, it is
+/// only removed once the replacement was created and started. If anything fails on the way the
+/// replacement is removed and the original container is restored.
+async fn recreate_container(
+    docker: &Docker,
+    id: &str,
+    image: &str,
+    opts: &ContainerCreateOpts,
+    networks: &[String],
+    start: bool,
+) -> Result<ContainerId> {
+    docker
+        .images()
+        .get(image)
+        .inspect()
+        .await
+        .with_context(|| {
+            format!(
+                "image `{}` not found, pull it before recreating the container",
+                image
+            )
+        })?;
+    let original = docker.containers().get(id);
+    let details = original
+        .inspect()
+        .await
+        .context("inspecting container failed")?;
+    let running = details.state.running;
+    let name = details.name.trim_start_matches('/').to_string();
+    let backup_name = format!("{}-{}-old", name, &id[..id.len().min(12)]);
+
+    original
+        .rename(&backup_name)
+        .await
+        .context("renaming the original container failed")?;
+    let replaced = async {
+        if running {
+            original
+                .stop(None)
+                .await
+                .context("stopping the original container failed")?;
+        }
+        create_container(docker, opts, networks, &[], start || running).await
+    }
+    .await;
+
+    match replaced {
+        Ok(new_id) => {
+            original
+                .remove(&RmContainerOpts::builder().force(true).build())
+                .await
+                .with_context(|| {
+                    format!(
+                        "container {} replaced the original container but removing the original \
+                         container {} failed",
+                        new_id, id
+                    )
+                })?;
+            Ok(new_id)
+        }
+        Err(e) => {
+            // a replacement that failed to connect or start was already removed
+            let restored = async {
+                original
+                    .rename(&name)
+                    .await
+                    .context("renaming the original container back failed")?;
+                if running {
+                    original
+                        .start()
+                        .await
+                        .context("starting the original container failed")?;
+                }
+                Ok::<_, anyhow::Error>(())
+            }
+            .await;
+            match restored {
+                Ok(()) => Err(e.context(format!(
+                    "recreating container {} failed, the original container was restored",
+                    id
+                ))),
+                Err(restore_error) => Err(e.context(format!(
+                    "recreating container {} failed and restoring it failed too, the original \
+                     container is kept as `{}`: {:#}",
+                    id, backup_name, restore_error
+                ))),
+            }
+        }
+    }
+}
+
+/// Inspects networks `ids` as only the inspect call returns the containers attached to a network.
+async fn network_topology(docker: &Docker, ids: Vec<NetworkId>) -> Result<Vec<NetworkInfo>> {
+    let mut inspected = Vec::with_capacity(ids.len());
+    let mut last_error = None;
+    for id in ids {
+        match docker.networks().get(&id).inspect().await {
+            Ok(network) => inspected.push(network),
+            // the network could have been deleted in the meantime
+            Err(e) => {
+                debug!("failed to inspect network {}: {}", id, e);
+                last_error = Some(e);
+            }
+        }
+    }
+    match last_error {
+        Some(e) if inspected.is_empty() => Err(e).context("inspecting networks failed"),
+        _ => Ok(inspected),
+    }
+}
+
 async fn handle_image_event(
     docker: &Docker,
     event: ImageEvent,
-    workers: &mut ImageWorkerHandles,
+    jobs: &mut JobManager,
 ) -> Result<Option<EventResponse>> {
     match event {
         ImageEvent::List(opts) => {
@@ -759,56 +1516,60 @@ async fn handle_image_event(
             ))))
         }
         ImageEvent::Save { id, output_path } => {
-            let d = docker.clone();
-            let i = ImageExportWorker::new(id, output_path);
-            workers.tx_export_event = i.1;
-            workers.rx_export_results = i.2;
-            tokio::task::spawn(async move {
-                i.0.work(d).await;
-            });
-            workers.export_in_progress = true;
-            Ok(None)
+            jobs.add(
+                JobKind::Export {
+                    image: id,
+                    path: output_path,
+                },
+                None,
+            );
+            jobs.start_queued(docker);
+            Ok(Some(EventResponse::Jobs(JobsEventResponse::List(
+                jobs.poll().await,
+            ))))
         }
         ImageEvent::Pull { image, auth } => {
-            if workers.pull_in_progress {
-                return Ok(None);
+            let kind = JobKind::Pull { image };
+            if jobs.is_active(&kind) {
+                return Err(anyhow!("job `{}` is already in progress", kind));
             }
-            let d = docker.clone();
-            let i = ImagePullWorker::new(image, auth);
-            workers.tx_pull_event = i.1;
-            workers.rx_pull_chunks = i.2;
-            workers.rx_pull_results = i.3;
-            tokio::task::spawn(async move {
-                i.0.work(d).await;
-            });
-            workers.pull_in_progress = true;
-            Ok(None)
+            jobs.add(kind, auth);
+            jobs.start_queued(docker);
+            Ok(Some(EventResponse::Jobs(JobsEventResponse::List(
+                jobs.poll().await,
+            ))))
         }
         ImageEvent::Import { path } => {
-            if workers.import_in_progress {
-                return Ok(None);
+            let kind = JobKind::Import { path };
+            if jobs.is_active(&kind) {
+                return Err(anyhow!("job `{}` is already in progress", kind));
             }
-            let d = docker.clone();
-            let i = ImageImportWorker::new(&path);
-            workers.tx_import_event = i.1;
-            workers.rx_import_chunks = i.2;
-            workers.rx_import_results = i.3;
-            tokio::task::spawn(async move {
-                i.0.work(d).await;
-            });
-            workers.import_in_progress = true;
-            Ok(None)
+            jobs.add(kind, None);
+            jobs.start_queued(docker);
+            Ok(Some(EventResponse::Jobs(JobsEventResponse::List(
+                jobs.poll().await,
+            ))))
         }
-        ImageEvent::PullChunks => {
-            if !workers.pull_in_progress {
-                return Ok(None);
+        ImageEvent::Build { context, tag, opts } => {
+            let kind = JobKind::Build { context, tag };
+            if jobs.is_active(&kind) {
+                return Err(anyhow!("job `{}` is already in progress", kind));
             }
-            if let Err(e) = workers.tx_pull_event.send(WorkerEvent::PollData).await {
-                return Err(anyhow!("failed to collect image import chunks: {}", e));
+            jobs.add_build(kind, opts);
+            jobs.start_queued(docker);
+            Ok(Some(EventResponse::Jobs(JobsEventResponse::List(
+                jobs.poll().await,
+            ))))
+        }
+        ImageEvent::Push { image, tag, auth } => {
+            let kind = JobKind::Push { image, tag };
+            if jobs.is_active(&kind) {
+                return Err(anyhow!("job `{}` is already in progress", kind));
             }
-            let chunks = workers.rx_pull_chunks.recv().await.unwrap_or_default();
-            Ok(Some(EventResponse::Image(ImageEventResponse::PullChunks(
-                chunks,
+            jobs.add(kind, auth);
+            jobs.start_queued(docker);
+            Ok(Some(EventResponse::Jobs(JobsEventResponse::List(
+                jobs.poll().await,
             ))))
         }
         ImageEvent::Search { image } => {
@@ -875,31 +1636,32 @@ async fn handle_image_event(
     }
 }
 
-async fn check_image_in_progress_events(workers: &mut WorkerHandles) {
-    if workers.images.export_in_progress {
-        if let Ok(res) = workers.images.rx_export_results.try_recv() {
-            let rsp = EventResponse::Image(ImageEventResponse::Save(res));
+async fn check_container_in_progress_events(workers: &mut WorkerHandles) {
+    if workers.containers.logs_export_in_progress {
+        if let Ok(res) = workers.containers.rx_logs_export_results.try_recv() {
+            let rsp = EventResponse::Container(ContainerEventResponse::ExportLogs(
+                LogsExportStatus::Finished(res),
+            ));
             let _ = workers.tx_rsp.send(rsp).await;
-            workers.images.export_in_progress = false;
-        }
-    }
-    if workers.images.pull_in_progress {
-        if let Ok(res) = workers.images.rx_pull_results.try_recv() {
-            let rsp = EventResponse::Image(ImageEventResponse::Pull(res));
-            let _ = workers.tx_rsp.send(rsp).await;
-            if let Some(chunks) = workers.images.rx_pull_chunks.recv().await {
-                let rsp = EventResponse::Image(ImageEventResponse::PullChunks(chunks));
+            workers.containers.logs_export_in_progress = false;
+        } else {
+            let mut lines = None;
+            while let Ok(n) = workers.containers.rx_logs_export_progress.try_recv() {
+                lines = Some(n);
+            }
+            if let Some(lines) = lines {
+                let rsp = EventResponse::Container(ContainerEventResponse::ExportLogs(
+                    LogsExportStatus::InProgress { lines },
+                ));
                 let _ = workers.tx_rsp.send(rsp).await;
             }
-            workers.images.pull_in_progress = false;
         }
     }
-    if workers.images.import_in_progress {
-        if let Ok(res) = workers.images.rx_import_results.try_recv() {
-            let rsp = EventResponse::Image(ImageEventResponse::Import(res));
-            let _ = workers.tx_rsp.send(rsp).await;
-            //if let Some(_) = _rx_import_chunks.recv().await {}
-            workers.images.pull_in_progress = false;
-        }
+}
+
+async fn check_jobs(docker: &Docker, workers: &mut WorkerHandles) {
+    for rsp in workers.jobs.finished().await {
+        let _ = workers.tx_rsp.send(rsp).await;
     }
+    workers.jobs.start_queued(docker);
 }